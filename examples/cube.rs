@@ -13,11 +13,12 @@ use crate::util::{App, EventHandlers};
 use vki::{
     BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor, BindingResource,
     BindingType, BlendDescriptor, BufferUsageFlags, Color, ColorStateDescriptor, ColorWriteFlags, CompareFunction,
-    CullMode, DepthStencilStateDescriptor, FrontFace, IndexFormat, InputStateDescriptor, InputStepMode, LoadOp,
-    PipelineLayoutDescriptor, PipelineStageDescriptor, PrimitiveTopology, RasterizationStateDescriptor,
-    RenderPassColorAttachmentDescriptor, RenderPassDepthStencilAttachmentDescriptor, RenderPassDescriptor,
-    RenderPipelineDescriptor, ShaderModuleDescriptor, ShaderStageFlags, StencilStateFaceDescriptor, StoreOp,
-    SwapchainError, VertexAttributeDescriptor, VertexBufferDescriptor, VertexFormat,
+    ConservativeRasterizationMode, CullMode, DepthStencilStateDescriptor, FrontFace, IndexFormat, InputStateDescriptor,
+    InputStepMode, LoadOp, MultisampleStateDescriptor, PipelineLayoutDescriptor, PipelineStageDescriptor, PolygonMode,
+    PrimitiveTopology, QueueType, RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor,
+    RenderPassDepthStencilAttachmentDescriptor, RenderPassDescriptor, RenderPipelineDescriptor, ShaderModuleDescriptor,
+    ShaderStageFlags, StencilStateFaceDescriptor, StoreOp, SwapchainError, VertexAttributeDescriptor,
+    VertexBufferDescriptor, VertexFormat,
 };
 
 #[repr(C)]
@@ -106,7 +107,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &uniforms,
     )?;
 
-    app.device.get_queue().submit(&[encoder.finish()?])?;
+    app.device.get_queue(QueueType::Graphics).submit(&[encoder.finish()?])?;
 
     #[rustfmt::skip]
     let bind_group_layout = app.device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -128,33 +129,42 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 resource: BindingResource::Buffer(uniform_buffer.clone(), 0..util::byte_length(&uniforms)),
             }
         ],
+        label: None,
     })?;
 
     let pipeline_layout = app.device.create_pipeline_layout(PipelineLayoutDescriptor {
         bind_group_layouts: vec![bind_group_layout],
         push_constant_ranges: vec![],
+        label: None,
     })?;
 
     let vs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/cube.vert.spv"),
+        label: None,
     })?;
 
     let fs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/cube.frag.spv"),
+        label: None,
     })?;
 
     #[rustfmt::skip]
     let render_pipeline = app.device.create_render_pipeline(RenderPipelineDescriptor {
-        layout: pipeline_layout,
-        vertex_stage: PipelineStageDescriptor { module: vs, entry_point: Cow::Borrowed("main") },
-        fragment_stage: PipelineStageDescriptor { module: fs, entry_point: Cow::Borrowed("main") },
+        layout: Some(pipeline_layout),
+        vertex_stage: PipelineStageDescriptor { module: vs, entry_point: Cow::Borrowed("main"), specialization_constants: Default::default() },
+        fragment_stage: PipelineStageDescriptor { module: fs, entry_point: Cow::Borrowed("main"), specialization_constants: Default::default() },
         rasterization_state: RasterizationStateDescriptor {
             front_face: FrontFace::Ccw,
             cull_mode: CullMode::Back,
+            polygon_mode: PolygonMode::Fill,
+            depth_clamp: false,
             depth_bias: 0,
             depth_bias_slope_scale: 0.0,
             depth_bias_clamp: 0.0,
+            line_width: 1.0,
+            conservative_rasterization: ConservativeRasterizationMode::Disabled,
         },
+        multisample_state: MultisampleStateDescriptor::default(),
         primitive_topology: PrimitiveTopology::TriangleList,
         color_states: vec![
             ColorStateDescriptor {
@@ -172,6 +182,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             stencil_front: StencilStateFaceDescriptor::IGNORE,
             stencil_read_mask: 0,
             stencil_write_mask: 0,
+            depth_bounds: None,
         }),
         input_state: InputStateDescriptor {
             index_format: IndexFormat::U16,
@@ -196,6 +207,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ],
         },
         sample_count: app.get_sample_count(),
+        label: None,
     })?;
 
     app.run(move |app| {
@@ -253,7 +265,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         let command_buffer = encoder.finish()?;
 
-        let queue = app.device.get_queue();
+        let queue = app.device.get_queue(QueueType::Graphics);
 
         queue.submit(&[command_buffer])?;
 
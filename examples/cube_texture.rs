@@ -16,13 +16,14 @@ use std::time::Instant;
 use vki::{
     AddressMode, BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor,
     BindingResource, BindingType, BlendDescriptor, BufferCopyView, BufferUsageFlags, Color, ColorStateDescriptor,
-    ColorWriteFlags, CompareFunction, CullMode, DepthStencilStateDescriptor, Extent3D, FilterMode, FrontFace,
-    IndexFormat, InputStateDescriptor, InputStepMode, LoadOp, Origin3D, PipelineLayoutDescriptor,
-    PipelineStageDescriptor, PrimitiveTopology, RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor,
-    RenderPassDepthStencilAttachmentDescriptor, RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor,
-    ShaderModuleDescriptor, ShaderStageFlags, StencilStateFaceDescriptor, StoreOp, SwapchainError, TextureBlitView,
-    TextureCopyView, TextureDescriptor, TextureDimension, TextureFormat, TextureUsageFlags, VertexAttributeDescriptor,
-    VertexBufferDescriptor, VertexFormat,
+    ColorWriteFlags, CompareFunction, ConservativeRasterizationMode, CullMode, DepthStencilStateDescriptor, Extent3D,
+    FilterMode, FrontFace, IndexFormat, InputStateDescriptor, InputStepMode, LoadOp, MultisampleStateDescriptor,
+    Origin3D, PipelineLayoutDescriptor, PipelineStageDescriptor, PolygonMode, PrimitiveTopology, QueueType,
+    RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor, RenderPassDepthStencilAttachmentDescriptor,
+    RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor, ShaderModuleDescriptor, ShaderStageFlags,
+    StencilStateFaceDescriptor, StoreOp, SwapchainError, TextureBlitView, TextureCopyView, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureUsageFlags, VertexAttributeDescriptor, VertexBufferDescriptor,
+    VertexFormat,
 };
 
 #[repr(C)]
@@ -105,6 +106,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         min_filter: FilterMode::Linear,
         mag_filter: FilterMode::Linear,
         compare_function: CompareFunction::Never,
+        label: None,
+        max_anisotropy: 1.0,
     })?;
 
     // create texture
@@ -121,6 +124,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         usage: TextureUsageFlags::SAMPLED | TextureUsageFlags::TRANSFER_DST | TextureUsageFlags::TRANSFER_SRC,
         size: texture_size,
         dimension: TextureDimension::D2,
+        label: None,
     })?;
 
     let container_texture_view = container_texture.create_default_view()?;
@@ -131,14 +135,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         BufferCopyView {
             offset: 0,
             buffer: &texture_buffer,
-            image_height: texture_size.height,
-            row_length: texture_size.width,
+            rows_per_image: texture_size.height,
+            bytes_per_row: texture_size.width * 4,
         },
         TextureCopyView {
             texture: &container_texture,
             origin: Origin3D { x: 0, y: 0, z: 0 },
             mip_level: 0,
             array_layer: 0,
+            array_layer_count: 1,
         },
         texture_size,
     );
@@ -188,7 +193,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         encoder.blit_texture_to_texture(src, dst, FilterMode::Linear);
     }
 
-    app.device.get_queue().submit(&[encoder.finish()?])?;
+    app.device.get_queue(QueueType::Graphics).submit(&[encoder.finish()?])?;
 
     #[rustfmt::skip]
     let bind_group_layout = app.device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -228,33 +233,42 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 resource: BindingResource::TextureView(container_texture_view),
             }
         ],
+        label: None,
     })?;
 
     let pipeline_layout = app.device.create_pipeline_layout(PipelineLayoutDescriptor {
         bind_group_layouts: vec![bind_group_layout],
         push_constant_ranges: vec![],
+        label: None,
     })?;
 
     let vs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/cube_texture.vert.spv"),
+        label: None,
     })?;
 
     let fs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/cube_texture.frag.spv"),
+        label: None,
     })?;
 
     #[rustfmt::skip]
     let render_pipeline = app.device.create_render_pipeline(RenderPipelineDescriptor {
-        layout: pipeline_layout,
-        vertex_stage: PipelineStageDescriptor { module: vs, entry_point: Cow::Borrowed("main") },
-        fragment_stage: PipelineStageDescriptor { module: fs, entry_point: Cow::Borrowed("main") },
+        layout: Some(pipeline_layout),
+        vertex_stage: PipelineStageDescriptor { module: vs, entry_point: Cow::Borrowed("main"), specialization_constants: Default::default() },
+        fragment_stage: PipelineStageDescriptor { module: fs, entry_point: Cow::Borrowed("main"), specialization_constants: Default::default() },
         rasterization_state: RasterizationStateDescriptor {
             front_face: FrontFace::Ccw,
             cull_mode: CullMode::Back,
+            polygon_mode: PolygonMode::Fill,
+            depth_clamp: false,
             depth_bias: 0,
             depth_bias_slope_scale: 0.0,
             depth_bias_clamp: 0.0,
+            line_width: 1.0,
+            conservative_rasterization: ConservativeRasterizationMode::Disabled,
         },
+        multisample_state: MultisampleStateDescriptor::default(),
         primitive_topology: PrimitiveTopology::TriangleList,
         color_states: vec![
             ColorStateDescriptor {
@@ -272,6 +286,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             stencil_front: StencilStateFaceDescriptor::IGNORE,
             stencil_read_mask: 0,
             stencil_write_mask: 0,
+            depth_bounds: None,
         }),
         input_state: InputStateDescriptor {
             index_format: IndexFormat::U16,
@@ -296,6 +311,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ],
         },
         sample_count: app.get_sample_count(),
+        label: None,
     })?;
 
     let start = Instant::now();
@@ -355,7 +371,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         let command_buffer = encoder.finish()?;
 
-        let queue = app.device.get_queue();
+        let queue = app.device.get_queue(QueueType::Graphics);
 
         queue.submit(&[command_buffer])?;
 
@@ -20,12 +20,13 @@ use std::time::{Duration, Instant};
 use vki::{
     AddressMode, BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor,
     BindingResource, BindingType, BlendDescriptor, Buffer, BufferUsageFlags, Color, ColorStateDescriptor,
-    ColorWriteFlags, CompareFunction, CullMode, DepthStencilStateDescriptor, FilterMode, FrontFace, IndexFormat,
-    InputStateDescriptor, InputStepMode, LoadOp, PipelineLayoutDescriptor, PipelineStageDescriptor, PrimitiveTopology,
-    PushConstantRange, RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor,
-    RenderPassDepthStencilAttachmentDescriptor, RenderPassDescriptor, RenderPipelineDescriptor, Sampler,
-    SamplerDescriptor, ShaderModuleDescriptor, ShaderStageFlags, StencilStateFaceDescriptor, StoreOp, SwapchainError,
-    TextureFormat, TextureView, VertexAttributeDescriptor, VertexBufferDescriptor, VertexFormat,
+    ColorWriteFlags, CompareFunction, ConservativeRasterizationMode, CullMode, DepthStencilStateDescriptor, FilterMode,
+    FrontFace, IndexFormat, InputStateDescriptor, InputStepMode, LoadOp, MultisampleStateDescriptor,
+    PipelineLayoutDescriptor, PipelineStageDescriptor, PolygonMode, PrimitiveTopology, PushConstantRange, QueueType,
+    RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor, RenderPassDepthStencilAttachmentDescriptor,
+    RenderPassDescriptor, RenderPipelineDescriptor, Sampler, SamplerDescriptor, ShaderModuleDescriptor,
+    ShaderStageFlags, StencilStateFaceDescriptor, StoreOp, SwapchainError, TextureFormat, TextureView,
+    VertexAttributeDescriptor, VertexBufferDescriptor, VertexFormat,
 };
 
 const MAX_MORPH_TARGETS: usize = 2;
@@ -938,7 +939,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         };
 
         let texture = util::create_texture_with_data(&app.device, &mut encoder, data, true, format, width, height)?;
-        util::generate_mipmaps(&mut encoder, &texture)?;
+        encoder.generate_mipmaps(&texture, texture.subresource_range());
         images.push(texture);
     }
 
@@ -985,6 +986,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             min_filter,
             mipmap_filter,
             compare_function: CompareFunction::Never,
+            label: None,
+            max_anisotropy: 1.0,
         })?);
     }
 
@@ -998,6 +1001,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         min_filter: FilterMode::Linear,
         mag_filter: FilterMode::Linear,
         compare_function: CompareFunction::Never,
+        label: None,
+        max_anisotropy: 1.0,
     })?;
 
     for texture in import.doc.textures() {
@@ -1577,7 +1582,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     0..camera_and_light_settings_buffer.size()
                 ),
             }
-        ]
+        ],
+        label: None,
     })?;
 
     let mut bind_group_1 = Vec::with_capacity(materials.len());
@@ -1654,6 +1660,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         bind_group_1.push(app.device.create_bind_group(BindGroupDescriptor {
             layout: bind_group_1_layout.clone(),
             bindings,
+            label: None,
         })?);
     }
 
@@ -1676,7 +1683,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     0..std::mem::size_of::<SkinSettings>()
                 ),
             }
-        ]
+        ],
+        label: None,
     })?;
 
     let push_constant_range = PushConstantRange {
@@ -1692,38 +1700,48 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             bind_group_2_layout.clone(),
         ],
         push_constant_ranges: vec![push_constant_range],
+        label: None,
     })?;
 
     let mut pipelines = HashMap::with_capacity(pipeline_keys.len());
 
     let vs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/gltf_viewer.vert.spv"),
+        label: None,
     })?;
 
     let fs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/gltf_viewer.frag.spv"),
+        label: None,
     })?;
 
     println!("Creating pipelines: {}", pipeline_keys.len());
     for (material_pipeline_key, mesh_pipeline_key) in pipeline_keys.drain(..) {
         #[rustfmt::skip]
         let render_pipeline_descriptor = RenderPipelineDescriptor {
-            layout: render_pipeline_layout.clone(),
+            layout: Some(render_pipeline_layout.clone()),
             vertex_stage: PipelineStageDescriptor {
                 module: vs.clone(),
                 entry_point: Cow::Borrowed("main"),
+                specialization_constants: Default::default(),
             },
             fragment_stage: PipelineStageDescriptor {
                 module: fs.clone(),
                 entry_point: Cow::Borrowed("main"),
+                specialization_constants: Default::default(),
             },
             rasterization_state: RasterizationStateDescriptor {
                 front_face: FrontFace::Ccw,
                 cull_mode: material_pipeline_key.cull_mode,
+                polygon_mode: PolygonMode::Fill,
+                depth_clamp: false,
                 depth_bias: 0,
                 depth_bias_slope_scale: 0.0,
                 depth_bias_clamp: 0.0,
+                line_width: 1.0,
+                conservative_rasterization: ConservativeRasterizationMode::Disabled,
             },
+            multisample_state: MultisampleStateDescriptor::default(),
             primitive_topology: mesh_pipeline_key.primitive_topology,
             color_states: vec![
                 ColorStateDescriptor {
@@ -1742,6 +1760,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     stencil_front: StencilStateFaceDescriptor::IGNORE,
                     stencil_read_mask: 0,
                     stencil_write_mask: 0,
+                    depth_bounds: None,
                 }
             ),
             input_state: InputStateDescriptor {
@@ -1827,6 +1846,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 ],
             },
             sample_count: app.get_sample_count(),
+            label: None,
         };
 
         let pipeline = app.device.create_render_pipeline(render_pipeline_descriptor)?;
@@ -1844,7 +1864,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     camera_and_light_settings.specular_env_mip_count = 24.0;
 
     let command_buffer = encoder.finish()?;
-    let queue = app.device.get_queue();
+    let queue = app.device.get_queue(QueueType::Graphics);
     queue.submit(&[command_buffer])?;
 
     app.run(move |app| {
@@ -2014,7 +2034,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         render_pass.end_pass();
 
         let command_buffer = encoder.finish()?;
-        let queue = app.device.get_queue();
+        let queue = app.device.get_queue(QueueType::Graphics);
         queue.submit(&[command_buffer])?;
 
         match queue.present(frame) {
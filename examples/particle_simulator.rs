@@ -12,9 +12,10 @@ use crate::util::{App, EventHandler, EventHandlers};
 use vki::{
     BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor, BindingResource,
     BindingType, BlendDescriptor, BlendFactor, BlendOperation, BufferUsageFlags, BufferViewDescriptor,
-    BufferViewFormat, Color, ColorStateDescriptor, ColorWriteFlags, ComputePipelineDescriptor, CullMode, Fence,
-    FrontFace, IndexFormat, InputStateDescriptor, InputStepMode, LoadOp, PipelineLayoutDescriptor,
-    PipelineStageDescriptor, PrimitiveTopology, RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor,
+    BufferViewFormat, Color, ColorStateDescriptor, ColorWriteFlags, ComputePipelineDescriptor,
+    ConservativeRasterizationMode, CullMode, Fence, FrontFace, IndexFormat, InputStateDescriptor, InputStepMode,
+    LoadOp, MultisampleStateDescriptor, PipelineLayoutDescriptor, PipelineStageDescriptor, PolygonMode,
+    PrimitiveTopology, QueueType, RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor,
     RenderPassDescriptor, RenderPipelineDescriptor, ShaderModuleDescriptor, ShaderStageFlags, StoreOp, SwapchainError,
     TextureFormat, VertexAttributeDescriptor, VertexBufferDescriptor, VertexFormat,
 };
@@ -183,7 +184,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &mvp_block_data,
     )?;
 
-    app.device.get_queue().submit(&[encoder.finish()?])?;
+    app.device.get_queue(QueueType::Graphics).submit(&[encoder.finish()?])?;
 
     #[rustfmt::skip]
     let compute_bind_group_layout = app.device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -223,6 +224,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 resource: BindingResource::Buffer(attractor_buffer.clone(), 0..util::byte_length(&attractor_block_data)),
             }
         ],
+        label: None,
     })?;
 
     #[rustfmt::skip]
@@ -245,50 +247,63 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 resource: BindingResource::Buffer(mvp_buffer.clone(), 0..util::byte_length(&mvp_block_data)),
             }
         ],
+        label: None,
     })?;
 
     let compute_pipeline_layout = app.device.create_pipeline_layout(PipelineLayoutDescriptor {
         bind_group_layouts: vec![compute_bind_group_layout],
         push_constant_ranges: vec![],
+        label: None,
     })?;
 
     let render_pipeline_layout = app.device.create_pipeline_layout(PipelineLayoutDescriptor {
         bind_group_layouts: vec![render_bind_group_layout],
         push_constant_ranges: vec![],
+        label: None,
     })?;
 
     let vs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/particle_simulator.vert.spv"),
+        label: None,
     })?;
 
     let fs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/particle_simulator.frag.spv"),
+        label: None,
     })?;
 
     let cs = app.device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/particle_simulator.comp.spv"),
+        label: None,
     })?;
 
     let compute_pipeline = app.device.create_compute_pipeline(ComputePipelineDescriptor {
-        layout: compute_pipeline_layout,
+        layout: Some(compute_pipeline_layout),
         compute_stage: PipelineStageDescriptor {
             module: cs,
             entry_point: Cow::Borrowed("main"),
+            specialization_constants: Default::default(),
         },
+        label: None,
     })?;
 
     #[rustfmt::skip]
     let render_pipeline = app.device.create_render_pipeline(RenderPipelineDescriptor {
-        layout: render_pipeline_layout,
-        vertex_stage: PipelineStageDescriptor { module: vs, entry_point: Cow::Borrowed("main") },
-        fragment_stage: PipelineStageDescriptor { module: fs, entry_point: Cow::Borrowed("main") },
+        layout: Some(render_pipeline_layout),
+        vertex_stage: PipelineStageDescriptor { module: vs, entry_point: Cow::Borrowed("main"), specialization_constants: Default::default() },
+        fragment_stage: PipelineStageDescriptor { module: fs, entry_point: Cow::Borrowed("main"), specialization_constants: Default::default() },
         rasterization_state: RasterizationStateDescriptor {
             front_face: FrontFace::Ccw,
             cull_mode: CullMode::None,
+            polygon_mode: PolygonMode::Fill,
+            depth_clamp: false,
             depth_bias: 0,
             depth_bias_slope_scale: 0.0,
             depth_bias_clamp: 0.0,
+            line_width: 1.0,
+            conservative_rasterization: ConservativeRasterizationMode::Disabled,
         },
+        multisample_state: MultisampleStateDescriptor::default(),
         primitive_topology: PrimitiveTopology::PointList,
         color_states: vec![
             ColorStateDescriptor {
@@ -321,6 +336,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ],
         },
         sample_count: app.get_sample_count(),
+        label: None,
     })?;
 
     let start_time = Instant::now();
@@ -382,6 +398,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Err(e) => return Err(e)?,
         };
 
+        // the simulation runs on the dedicated compute queue, if the device has one
         let mut encoder = app.device.create_command_encoder()?;
 
         if app.state.reset1 {
@@ -407,6 +424,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         compute_pass.dispatch(PARTICLE_GROUP_COUNT as u32, 1, 1);
         compute_pass.end_pass();
 
+        app.device.get_queue(QueueType::Compute).submit(&[encoder.finish()?])?;
+
+        let mut encoder = app.device.create_command_encoder()?;
+
         #[rustfmt::skip]
         let mut render_pass = encoder.begin_render_pass(RenderPassDescriptor {
             color_attachments: &[
@@ -429,7 +450,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         let command_buffer = encoder.finish()?;
 
-        let queue = app.device.get_queue();
+        let queue = app.device.get_queue(QueueType::Graphics);
 
         queue.submit(&[command_buffer])?;
 
@@ -1,5 +1,6 @@
 use vki::{
-    AdapterOptions, DeviceDescriptor, Instance, SwapchainDescriptor, SwapchainError, TextureFormat, TextureUsageFlags,
+    AdapterOptions, DeviceDescriptor, Instance, QueueType, SwapchainDescriptor, SwapchainError, TextureFormat,
+    TextureUsageFlags,
 };
 
 use winit::dpi::LogicalSize;
@@ -99,7 +100,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                     // Record drawing commands here!
 
-                    let queue = device.get_queue();
+                    let queue = device.get_queue(QueueType::Graphics);
 
                     // Submit drawing commands here!
 
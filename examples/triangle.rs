@@ -4,12 +4,12 @@ extern crate memoffset;
 use vki::{
     AdapterOptions, BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor,
     BindingResource, BindingType, BlendDescriptor, BlendFactor, BlendOperation, BufferDescriptor, BufferUsageFlags,
-    Color, ColorStateDescriptor, ColorWriteFlags, CullMode, DeviceDescriptor, FrontFace, IndexFormat,
-    InputStateDescriptor, InputStepMode, Instance, LoadOp, PipelineLayoutDescriptor, PipelineStageDescriptor,
-    PowerPreference, PrimitiveTopology, RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor,
-    RenderPassDescriptor, RenderPipelineDescriptor, ShaderModuleDescriptor, ShaderStageFlags, StoreOp,
-    SwapchainDescriptor, SwapchainError, TextureFormat, TextureUsageFlags, VertexAttributeDescriptor,
-    VertexBufferDescriptor, VertexFormat,
+    Color, ColorStateDescriptor, ColorWriteFlags, ConservativeRasterizationMode, CullMode, DeviceDescriptor, FrontFace,
+    IndexFormat, InputStateDescriptor, InputStepMode, Instance, LoadOp, MultisampleStateDescriptor,
+    PipelineLayoutDescriptor, PipelineStageDescriptor, PolygonMode, PowerPreference, PrimitiveTopology, QueueType,
+    RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor, RenderPassDescriptor, RenderPipelineDescriptor,
+    ShaderModuleDescriptor, ShaderStageFlags, StoreOp, SwapchainDescriptor, SwapchainError, TextureFormat,
+    TextureUsageFlags, VertexAttributeDescriptor, VertexBufferDescriptor, VertexFormat,
 };
 
 use winit::dpi::LogicalSize;
@@ -38,6 +38,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let instance = Instance::new()?;
     let adapter_options = AdapterOptions {
         power_preference: PowerPreference::HighPerformance,
+        ..AdapterOptions::default()
     };
 
     let adapter = instance.get_adapter(adapter_options)?;
@@ -67,10 +68,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let vertex_shader = device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/triangle.vert.spv"),
+        label: None,
     })?;
 
     let fragment_shader = device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/triangle.frag.spv"),
+        label: None,
     })?;
 
     let bind_group_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -84,6 +87,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
         bind_group_layouts: vec![bind_group_layout.clone()],
         push_constant_ranges: vec![],
+        label: None,
     })?;
 
     #[repr(C)]
@@ -98,6 +102,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let uniform_buffer = device.create_buffer(BufferDescriptor {
         size: uniforms_size_bytes,
         usage: BufferUsageFlags::UNIFORM | BufferUsageFlags::TRANSFER_DST,
+        label: None,
     })?;
 
     #[rustfmt::skip]
@@ -121,6 +126,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             binding: 0,
             resource: BindingResource::Buffer(uniform_buffer.clone(), 0..uniforms_size_bytes),
         }],
+        label: None,
     })?;
 
     #[repr(C)]
@@ -142,11 +148,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let vertex_buffer = device.create_buffer(BufferDescriptor {
         size: vertices_size_bytes,
         usage: BufferUsageFlags::VERTEX | BufferUsageFlags::TRANSFER_DST,
+        label: None,
     })?;
 
     let staging_vertex_buffer = device.create_buffer_mapped(BufferDescriptor {
         size: vertices_size_bytes,
         usage: BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::MAP_WRITE,
+        label: None,
     })?;
 
     staging_vertex_buffer.copy_from_slice(vertices)?;
@@ -161,7 +169,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         vertices_size_bytes,
     );
 
-    device.get_queue().submit(&[encoder.finish()?])?;
+    device.get_queue(QueueType::Graphics).submit(&[encoder.finish()?])?;
 
     let color_replace = BlendDescriptor {
         src_factor: BlendFactor::One,
@@ -170,15 +178,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let render_pipeline_descriptor = RenderPipelineDescriptor {
-        layout: pipeline_layout.clone(),
+        layout: Some(pipeline_layout.clone()),
         primitive_topology: PrimitiveTopology::TriangleList,
         vertex_stage: PipelineStageDescriptor {
             entry_point: Cow::Borrowed("main"),
             module: vertex_shader,
+            specialization_constants: Default::default(),
         },
         fragment_stage: PipelineStageDescriptor {
             entry_point: Cow::Borrowed("main"),
             module: fragment_shader,
+            specialization_constants: Default::default(),
         },
         input_state: InputStateDescriptor {
             index_format: IndexFormat::U16,
@@ -210,11 +220,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         rasterization_state: RasterizationStateDescriptor {
             front_face: FrontFace::Ccw,
             cull_mode: CullMode::None,
+            polygon_mode: PolygonMode::Fill,
+            depth_clamp: false,
             depth_bias: 0,
             depth_bias_slope_scale: 0.0,
             depth_bias_clamp: 0.0,
+            line_width: 1.0,
+            conservative_rasterization: ConservativeRasterizationMode::Disabled,
         },
+        multisample_state: MultisampleStateDescriptor::default(),
         sample_count: 1,
+        label: None,
     };
 
     let pipeline = device.create_render_pipeline(render_pipeline_descriptor)?;
@@ -294,7 +310,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     render_pass.draw(3, 1, 0, 1);
                     render_pass.end_pass();
 
-                    let queue = device.get_queue();
+                    let queue = device.get_queue(QueueType::Graphics);
 
                     queue.submit(&[encoder.finish()?])?;
 
@@ -4,12 +4,13 @@ extern crate memoffset;
 use vki::{
     AdapterOptions, BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor,
     BindingResource, BindingType, BlendDescriptor, BlendFactor, BlendOperation, BufferDescriptor, BufferUsageFlags,
-    Color, ColorStateDescriptor, ColorWriteFlags, CullMode, DeviceDescriptor, Extent3D, FrontFace, IndexFormat,
-    InputStateDescriptor, InputStepMode, Instance, LoadOp, PipelineLayoutDescriptor, PipelineStageDescriptor,
-    PrimitiveTopology, RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor, RenderPassDescriptor,
-    RenderPipelineDescriptor, ShaderModuleDescriptor, ShaderStageFlags, StoreOp, SwapchainDescriptor, SwapchainError,
-    TextureDescriptor, TextureDimension, TextureFormat, TextureUsageFlags, VertexAttributeDescriptor,
-    VertexBufferDescriptor, VertexFormat,
+    Color, ColorStateDescriptor, ColorWriteFlags, ConservativeRasterizationMode, CullMode, DeviceDescriptor, Extent3D,
+    FrontFace, IndexFormat, InputStateDescriptor, InputStepMode, Instance, LoadOp, MultisampleStateDescriptor,
+    PipelineLayoutDescriptor, PipelineStageDescriptor, PolygonMode, PrimitiveTopology, QueueType,
+    RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor, RenderPassDescriptor, RenderPipelineDescriptor,
+    ShaderModuleDescriptor, ShaderStageFlags, StoreOp, SwapchainDescriptor, SwapchainError, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureUsageFlags, VertexAttributeDescriptor, VertexBufferDescriptor,
+    VertexFormat,
 };
 
 use winit::dpi::LogicalSize;
@@ -65,10 +66,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let vertex_shader = device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/triangle.vert.spv"),
+        label: None,
     })?;
 
     let fragment_shader = device.create_shader_module(ShaderModuleDescriptor {
         code: include_bytes!("shaders/triangle.frag.spv"),
+        label: None,
     })?;
 
     let bind_group_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -82,6 +85,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
         bind_group_layouts: vec![bind_group_layout.clone()],
         push_constant_ranges: vec![],
+        label: None,
     })?;
 
     #[repr(C)]
@@ -96,6 +100,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let uniform_buffer = device.create_buffer(BufferDescriptor {
         size: uniforms_size_bytes,
         usage: BufferUsageFlags::UNIFORM | BufferUsageFlags::TRANSFER_DST,
+        label: None,
     })?;
 
     #[rustfmt::skip]
@@ -119,6 +124,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             binding: 0,
             resource: BindingResource::Buffer(uniform_buffer.clone(), 0..uniforms_size_bytes),
         }],
+        label: None,
     })?;
 
     #[repr(C)]
@@ -140,11 +146,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let vertex_buffer = device.create_buffer(BufferDescriptor {
         size: vertices_size_bytes,
         usage: BufferUsageFlags::VERTEX | BufferUsageFlags::TRANSFER_DST,
+        label: None,
     })?;
 
     let staging_vertex_buffer = device.create_buffer_mapped(BufferDescriptor {
         size: vertices_size_bytes,
         usage: BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::MAP_WRITE,
+        label: None,
     })?;
 
     staging_vertex_buffer.copy_from_slice(vertices)?;
@@ -159,7 +167,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         vertices_size_bytes,
     );
 
-    device.get_queue().submit(&[encoder.finish()?])?;
+    device.get_queue(QueueType::Graphics).submit(&[encoder.finish()?])?;
 
     let mut output_texture_descriptor = TextureDescriptor {
         sample_count: 8,
@@ -173,6 +181,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             height: window_height,
             depth: 1,
         },
+        label: None,
     };
 
     let mut output_texture = device.create_texture(output_texture_descriptor)?;
@@ -185,15 +194,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let render_pipeline_descriptor = RenderPipelineDescriptor {
-        layout: pipeline_layout.clone(),
+        layout: Some(pipeline_layout.clone()),
         primitive_topology: PrimitiveTopology::TriangleList,
         vertex_stage: PipelineStageDescriptor {
             entry_point: Cow::Borrowed("main"),
             module: vertex_shader,
+            specialization_constants: Default::default(),
         },
         fragment_stage: PipelineStageDescriptor {
             entry_point: Cow::Borrowed("main"),
             module: fragment_shader,
+            specialization_constants: Default::default(),
         },
         input_state: InputStateDescriptor {
             index_format: IndexFormat::U16,
@@ -225,11 +236,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         rasterization_state: RasterizationStateDescriptor {
             front_face: FrontFace::Ccw,
             cull_mode: CullMode::None,
+            polygon_mode: PolygonMode::Fill,
+            depth_clamp: false,
             depth_bias: 0,
             depth_bias_slope_scale: 0.0,
             depth_bias_clamp: 0.0,
+            line_width: 1.0,
+            conservative_rasterization: ConservativeRasterizationMode::Disabled,
         },
+        multisample_state: MultisampleStateDescriptor::default(),
         sample_count: output_texture_descriptor.sample_count,
+        label: None,
     };
 
     let pipeline = device.create_render_pipeline(render_pipeline_descriptor)?;
@@ -337,7 +354,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     render_pass.draw(3, 1, 0, 1);
                     render_pass.end_pass();
 
-                    let queue = device.get_queue();
+                    let queue = device.get_queue(QueueType::Graphics);
 
                     queue.submit(&[encoder.finish()?])?;
 
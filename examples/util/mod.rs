@@ -9,8 +9,8 @@ use std::slice;
 
 use vki::{
     Adapter, AdapterOptions, Buffer, BufferCopyView, BufferDescriptor, BufferUsageFlags, CommandEncoder, Device,
-    DeviceDescriptor, Error, Extensions, Extent3D, FilterMode, Instance, Origin3D, PowerPreference, Surface, Swapchain,
-    SwapchainDescriptor, Texture, TextureBlitView, TextureCopyView, TextureDescriptor, TextureDimension, TextureFormat,
+    DeviceDescriptor, Error, Extensions, Extent3D, Instance, Origin3D, PowerPreference, QueueType, Surface, Swapchain,
+    SwapchainDescriptor, Texture, TextureCopyView, TextureDescriptor, TextureDimension, TextureFormat,
     TextureUsageFlags, TextureView,
 };
 
@@ -59,6 +59,7 @@ fn create_swapchain_and_depth_view_and_color_view(
         dimension: TextureDimension::D2,
         usage: TextureUsageFlags::OUTPUT_ATTACHMENT,
         format: DEFAULT_DEPTH_FORMAT,
+        label: None,
     })?;
 
     let depth_view = depth_texture.create_default_view()?;
@@ -75,6 +76,7 @@ fn create_swapchain_and_depth_view_and_color_view(
         dimension: TextureDimension::D2,
         usage: TextureUsageFlags::OUTPUT_ATTACHMENT,
         format: DEFAULT_COLOR_FORMAT,
+        label: None,
     })?;
 
     let color_view = color_texture.create_default_view()?;
@@ -169,12 +171,15 @@ impl<T: 'static> App<T> {
                 Ok("1") | Ok("true") => PowerPreference::LowPower,
                 Ok(_) | Err(_) => PowerPreference::HighPerformance,
             },
+            ..AdapterOptions::default()
         })?;
         let device = adapter.create_device(DeviceDescriptor {
             surface_support: Some(&surface),
-            extensions: Extensions {
-                anisotropic_filtering: false,
-            },
+            required_limits: None,
+            extensions: Extensions::default(),
+            extra_queues: &[QueueType::Compute],
+            queue_type: QueueType::Graphics,
+            pipeline_cache_data: None,
         })?;
 
         let sample_count = 1;
@@ -367,7 +372,7 @@ impl<T: 'static> App<T> {
 
 /// Convenience function for submitting a command buffer and creating a new encoder
 pub fn submit(device: &Device, encoder: CommandEncoder) -> Result<CommandEncoder, vki::Error> {
-    device.get_queue().submit(&[encoder.finish()?])?;
+    device.get_queue(QueueType::Graphics).submit(&[encoder.finish()?])?;
     Ok(device.create_command_encoder()?)
 }
 
@@ -389,6 +394,7 @@ pub fn create_buffer_with_data<U: Copy + 'static>(
     let descriptor = BufferDescriptor {
         usage,
         size: size_bytes,
+        label: None,
     };
 
     if is_write_mapped {
@@ -406,6 +412,7 @@ pub fn create_staging_buffer<U: Copy + 'static>(device: &Device, data: &[U]) ->
     let descriptor = BufferDescriptor {
         usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
         size: byte_length(data),
+        label: None,
     };
     let mapped_buffer = device.create_buffer_mapped(descriptor)?;
     mapped_buffer.copy_from_slice(data)?;
@@ -454,6 +461,7 @@ pub fn create_texture_with_data(
         array_layer_count: 1,
         usage: TextureUsageFlags::SAMPLED | TextureUsageFlags::TRANSFER_SRC | TextureUsageFlags::TRANSFER_DST,
         dimension: TextureDimension::D2,
+        label: None,
     };
 
     let texture = device.create_texture(descriptor)?;
@@ -464,14 +472,15 @@ pub fn create_texture_with_data(
         BufferCopyView {
             offset: 0,
             buffer: &buffer,
-            image_height: size.height,
-            row_length: size.width,
+            rows_per_image: size.height,
+            bytes_per_row: data.len() as u32 / size.height,
         },
         TextureCopyView {
             texture: &texture,
             origin: Origin3D { x: 0, y: 0, z: 0 },
             mip_level: 0,
             array_layer: 0,
+            array_layer_count: 1,
         },
         size,
     );
@@ -501,55 +510,6 @@ pub fn create_texture(
     create_texture_with_data(device, encoder, data, has_mipmaps, format, width, height)
 }
 
-pub fn generate_mipmaps(encoder: &mut CommandEncoder, texture: &Texture) -> Result<(), Error> {
-    let mut mip_width = texture.size().width;
-    let mut mip_height = texture.size().height;
-
-    let mip_level_count = texture.mip_level_count();
-
-    for i in 1..mip_level_count {
-        let src = TextureBlitView {
-            texture: &texture,
-            mip_level: i - 1,
-            array_layer: 0,
-            bounds: [
-                Origin3D { x: 0, y: 0, z: 0 },
-                Origin3D {
-                    x: mip_width as i32,
-                    y: mip_height as i32,
-                    z: 1,
-                },
-            ],
-        };
-
-        if mip_width > 1 {
-            mip_width = mip_width / 2;
-        }
-
-        if mip_height > 1 {
-            mip_height = mip_height / 2;
-        }
-
-        let dst = TextureBlitView {
-            texture: &texture,
-            mip_level: i,
-            array_layer: 0,
-            bounds: [
-                Origin3D { x: 0, y: 0, z: 0 },
-                Origin3D {
-                    x: mip_width as i32,
-                    y: mip_height as i32,
-                    z: 1,
-                },
-            ],
-        };
-
-        encoder.blit_texture_to_texture(src, dst, FilterMode::Linear);
-    }
-
-    Ok(())
-}
-
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Rect<S> {
     pub left: S,
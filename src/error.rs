@@ -2,6 +2,8 @@ use ash::vk;
 
 use backtrace::Backtrace;
 
+use crate::{BindingType, ScalarType, ShaderBindingType, ShaderStageFlags, TextureFormat, VertexFormat};
+
 use std::error::Error as StdError;
 use std::fmt::{self, Display};
 
@@ -127,7 +129,7 @@ impl From<vk::Result> for Error {
 impl Into<vk::Result> for Error {
     fn into(self) -> vk::Result {
         match self.kind() {
-            ErrorKind::Message(_) => vk::Result::ERROR_VALIDATION_FAILED_EXT,
+            ErrorKind::Message(_) | ErrorKind::Pipeline(_) => vk::Result::ERROR_VALIDATION_FAILED_EXT,
             ErrorKind::Code(code) => *code,
         }
     }
@@ -156,6 +158,8 @@ impl Display for Error {
 pub enum ErrorKind {
     Code(VkResult),
     Message(String),
+    /// A pipeline descriptor does not match its shaders, see `PipelineError`
+    Pipeline(Box<PipelineError>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -179,6 +183,7 @@ impl From<Error> for SwapchainError {
             ErrorKind::Code(vk::Result::ERROR_OUT_OF_DATE_KHR) => SwapchainError::OutOfDate,
             ErrorKind::Code(code) => SwapchainError::Other(Error::from(code)),
             ErrorKind::Message(msg) => SwapchainError::Other(Error::from(msg)),
+            ErrorKind::Pipeline(e) => SwapchainError::Other(Error::from(*e)),
         }
     }
 }
@@ -221,6 +226,7 @@ impl From<Error> for FenceError {
             ErrorKind::Code(vk::Result::TIMEOUT) => FenceError::Timeout,
             ErrorKind::Code(code) => FenceError::Other(Error::from(code)),
             ErrorKind::Message(msg) => FenceError::Other(Error::from(msg)),
+            ErrorKind::Pipeline(e) => FenceError::Other(Error::from(*e)),
         }
     }
 }
@@ -241,3 +247,164 @@ impl Display for FenceError {
         write!(fmt, "{:?}", self)
     }
 }
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InstanceError {
+    MissingLayer(String),
+    MissingExtension(String),
+    UnsupportedApiVersion {
+        requested: (u32, u32, u32),
+        available: (u32, u32, u32),
+    },
+    Other(Error),
+}
+
+impl From<InstanceError> for Error {
+    fn from(e: InstanceError) -> Error {
+        match e {
+            InstanceError::MissingLayer(name) => Error::from(format!("instance layer not present: {}", name)),
+            InstanceError::MissingExtension(name) => Error::from(format!("instance extension not present: {}", name)),
+            InstanceError::UnsupportedApiVersion { requested, available } => Error::from(format!(
+                "requested api version {:?} exceeds the available instance version {:?}",
+                requested, available
+            )),
+            InstanceError::Other(e) => e,
+        }
+    }
+}
+
+impl From<Error> for InstanceError {
+    fn from(e: Error) -> InstanceError {
+        InstanceError::Other(e)
+    }
+}
+
+impl<'a> From<&'a Error> for InstanceError {
+    fn from(e: &'a Error) -> InstanceError {
+        InstanceError::Other(e.clone())
+    }
+}
+
+impl From<vk::Result> for InstanceError {
+    fn from(e: vk::Result) -> InstanceError {
+        InstanceError::Other(Error::from(e))
+    }
+}
+
+impl From<ash::InstanceError> for InstanceError {
+    fn from(e: ash::InstanceError) -> InstanceError {
+        InstanceError::Other(Error::from(e))
+    }
+}
+
+impl StdError for InstanceError {}
+
+impl Display for InstanceError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+/// A mismatch between a pipeline descriptor and the interface of its shader stages
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PipelineError {
+    EntryPointNotFound {
+        stage: ShaderStageFlags,
+        entry_point: String,
+    },
+    /// The entry point was declared for a different stage
+    EntryPointStageMismatch {
+        entry_point: String,
+        expected: ShaderStageFlags,
+        found: ShaderStageFlags,
+    },
+    /// The vertex shader reads a location that no vertex attribute provides
+    MissingVertexAttribute {
+        location: u32,
+    },
+    VertexAttributeTypeMismatch {
+        location: u32,
+        format: VertexFormat,
+        shader_type: ScalarType,
+    },
+    /// The fragment shader writes a location that has no color state
+    MissingColorState {
+        location: u32,
+    },
+    ColorStateTypeMismatch {
+        location: u32,
+        format: TextureFormat,
+        shader_type: ScalarType,
+    },
+    /// A fragment shader input is not written by the vertex shader with a compatible type
+    StageInterfaceMismatch {
+        location: u32,
+    },
+    MissingBinding {
+        stage: ShaderStageFlags,
+        set: u32,
+        binding: u32,
+    },
+    BindingTypeMismatch {
+        stage: ShaderStageFlags,
+        set: u32,
+        binding: u32,
+        layout_type: BindingType,
+        shader_type: ShaderBindingType,
+    },
+    /// The layout binding does not include the stage in its visibility
+    BindingNotVisible {
+        stage: ShaderStageFlags,
+        set: u32,
+        binding: u32,
+    },
+    /// The shader binding has no `BindingType` equivalent, so the layout can't be derived
+    UnsupportedBinding {
+        set: u32,
+        binding: u32,
+        shader_type: ShaderBindingType,
+        count: u32,
+    },
+    /// No push constant range of the layout covers the push constants used by the stage
+    MissingPushConstantRange {
+        stage: ShaderStageFlags,
+        offset: u32,
+        size: u32,
+    },
+    Other(Error),
+}
+
+impl From<PipelineError> for Error {
+    fn from(e: PipelineError) -> Error {
+        match e {
+            PipelineError::Other(e) => e,
+            e => Error {
+                kind: ErrorKind::Pipeline(Box::new(e)),
+                backtrace: backtrace(),
+            },
+        }
+    }
+}
+
+impl From<Error> for PipelineError {
+    fn from(e: Error) -> PipelineError {
+        match e.kind {
+            ErrorKind::Pipeline(e) => *e,
+            _ => PipelineError::Other(e),
+        }
+    }
+}
+
+impl From<vk::Result> for PipelineError {
+    fn from(e: vk::Result) -> PipelineError {
+        PipelineError::Other(Error::from(e))
+    }
+}
+
+impl StdError for PipelineError {}
+
+impl Display for PipelineError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
@@ -1,11 +1,16 @@
+use crate::imp::{binding, command_buffer, pipeline, texture};
 use crate::imp::{AdapterInner, DeviceInner, InstanceInner, SurfaceInner};
-use crate::{Adapter, AdapterOptions, Device, DeviceDescriptor, Extensions, PowerPreference};
+use crate::{
+    Adapter, AdapterOptions, Device, DeviceDescriptor, Extensions, Limits, PowerPreference, TextureFormat,
+    TextureFormatCapabilities,
+};
 
 use crate::error::Error;
 
 use ash::version::InstanceV1_0;
 use ash::vk;
 
+use std::borrow::Cow;
 use std::ffi::CStr;
 use std::fmt::{self, Debug};
 use std::sync::Arc;
@@ -23,6 +28,15 @@ impl Adapter {
         self.inner.properties()
     }
 
+    /// The best limits supported by the adapter, clamped to what vki itself supports.
+    pub fn limits(&self) -> Limits {
+        self.inner.limits
+    }
+
+    pub fn texture_format_capabilities(&self, format: TextureFormat) -> TextureFormatCapabilities {
+        self.inner.texture_format_capabilities(format)
+    }
+
     pub fn create_device(&self, descriptor: DeviceDescriptor) -> Result<Device, Error> {
         let device = DeviceInner::new(self.inner.clone(), descriptor)?;
         Ok(device.into())
@@ -36,118 +50,146 @@ impl Into<Adapter> for AdapterInner {
 }
 
 impl AdapterInner {
-    pub fn new(instance: Arc<InstanceInner>, options: AdapterOptions) -> Result<AdapterInner, Error> {
-        let new_inner = |physical_device: vk::PhysicalDevice,
-                         physical_device_properties: vk::PhysicalDeviceProperties| {
-            let instance = instance.clone();
-            let (extensions, physical_device_features) = unsafe {
-                // TODO: capture these
-                for p in instance
-                    .raw
-                    .enumerate_device_extension_properties(physical_device)?
-                    .iter()
-                {
-                    let name = CStr::from_ptr(p.extension_name.as_ptr());
-                    log::debug!("found physical device extension: {}", name.to_string_lossy());
-                }
-
-                // TODO: capture these
-                let mut num_layers = 0;
-                instance.raw.fp_v1_0().enumerate_device_layer_properties(
-                    physical_device,
-                    &mut num_layers,
-                    std::ptr::null_mut(),
-                );
-                let mut layers = vec![vk::LayerProperties::default(); num_layers as usize];
-                instance.raw.fp_v1_0().enumerate_device_layer_properties(
-                    physical_device,
-                    &mut num_layers,
-                    layers.as_mut_ptr(),
-                );
-                for p in layers.iter() {
-                    let name = CStr::from_ptr(p.layer_name.as_ptr());
-                    log::debug!("found physical device layer: {}", name.to_string_lossy());
-                }
-
-                let physical_device_features = instance.raw.get_physical_device_features(physical_device);
-                let extensions = Extensions {
-                    anisotropic_filtering: physical_device_features.sampler_anisotropy == vk::TRUE,
-                };
-                (extensions, physical_device_features)
-            };
+    fn from_physical_device(
+        instance: Arc<InstanceInner>,
+        physical_device: vk::PhysicalDevice,
+        physical_device_properties: vk::PhysicalDeviceProperties,
+        options: AdapterOptions,
+    ) -> Result<AdapterInner, Error> {
+        let (extensions, extension_properties, physical_device_features) = unsafe {
+            let extension_properties = instance.raw.enumerate_device_extension_properties(physical_device)?;
+            for p in extension_properties.iter() {
+                let name = CStr::from_ptr(p.extension_name.as_ptr());
+                log::debug!("found physical device extension: {}", name.to_string_lossy());
+            }
 
-            let mut physical_device_format_properties = Vec::new();
-            for format in VK_FORMATS.iter().cloned() {
-                let format_properties = unsafe {
-                    instance
-                        .raw
-                        .get_physical_device_format_properties(physical_device, format)
-                };
-                physical_device_format_properties.push((format, format_properties));
+            // TODO: capture these
+            let mut num_layers = 0;
+            instance.raw.fp_v1_0().enumerate_device_layer_properties(
+                physical_device,
+                &mut num_layers,
+                std::ptr::null_mut(),
+            );
+            let mut layers = vec![vk::LayerProperties::default(); num_layers as usize];
+            instance.raw.fp_v1_0().enumerate_device_layer_properties(
+                physical_device,
+                &mut num_layers,
+                layers.as_mut_ptr(),
+            );
+            for p in layers.iter() {
+                let name = CStr::from_ptr(p.layer_name.as_ptr());
+                log::debug!("found physical device layer: {}", name.to_string_lossy());
             }
-            physical_device_format_properties.sort_by(|(a, _), (b, _)| a.cmp(&b));
 
-            let queue_family_properties = unsafe {
+            let physical_device_features = instance.raw.get_physical_device_features(physical_device);
+            let extensions = supported_extensions(&physical_device_features, &extension_properties);
+            (extensions, extension_properties, physical_device_features)
+        };
+
+        let mut physical_device_format_properties = Vec::new();
+        for format in VK_FORMATS.iter().cloned() {
+            let format_properties = unsafe {
                 instance
                     .raw
-                    .get_physical_device_queue_family_properties(physical_device)
+                    .get_physical_device_format_properties(physical_device, format)
             };
+            physical_device_format_properties.push((format, format_properties));
+        }
+        physical_device_format_properties.sort_by(|(a, _), (b, _)| a.cmp(&b));
 
-            let name = unsafe { CStr::from_ptr(physical_device_properties.device_name.as_ptr()) }
-                .to_string_lossy()
-                .into_owned();
-
-            Ok(AdapterInner {
-                instance,
-                physical_device,
-                name,
-                physical_device_features,
-                physical_device_properties,
-                physical_device_format_properties,
-                queue_family_properties,
-                extensions,
-                options,
-            })
+        let queue_family_properties = unsafe {
+            instance
+                .raw
+                .get_physical_device_queue_family_properties(physical_device)
         };
 
+        let name = unsafe { CStr::from_ptr(physical_device_properties.device_name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+
+        let limits = supported_limits(&physical_device_properties.limits);
+
+        Ok(AdapterInner {
+            instance,
+            physical_device,
+            name,
+            physical_device_features,
+            physical_device_properties,
+            physical_device_format_properties,
+            queue_family_properties,
+            extension_properties,
+            extensions,
+            limits,
+            options,
+        })
+    }
+
+    fn enumerate_physical_devices(
+        instance: &InstanceInner,
+    ) -> Result<Vec<(vk::PhysicalDevice, vk::PhysicalDeviceProperties)>, Error> {
         unsafe {
             let physical_devices = match instance.raw.enumerate_physical_devices() {
                 Ok(physical_devices) => physical_devices,
                 Err(e) => {
                     log::error!("failed to enumerate physical devices: {:?}", e);
-                    return Err(Error::from(e))?;
+                    return Err(Error::from(e));
                 }
             };
-            let physical_device_properties = &mut Vec::with_capacity(physical_devices.len());
+            let mut result = Vec::with_capacity(physical_devices.len());
             for physical_device in physical_devices.iter().cloned() {
                 let properties = instance.raw.get_physical_device_properties(physical_device);
                 let name = CStr::from_ptr(properties.device_name.as_ptr());
-                log::debug!("found physical device: {:?} ({:?})", name, properties.device_type);
-                physical_device_properties.push(properties);
+                log::debug!(
+                    "found physical device: {:?} ({:?}, vendor_id: {:#x})",
+                    name,
+                    properties.device_type,
+                    properties.vendor_id
+                );
+                result.push((physical_device, properties));
             }
-            match options.power_preference {
-                PowerPreference::HighPerformance => {
-                    for (index, properties) in physical_device_properties.iter().enumerate() {
-                        if properties.device_type == vk::PhysicalDeviceType::DISCRETE_GPU {
-                            let inner = new_inner(physical_devices[index], *properties)?;
-                            return Ok(inner);
-                        }
-                    }
-                }
-                PowerPreference::LowPower => {
-                    for (index, properties) in physical_device_properties.iter().enumerate() {
-                        if properties.device_type == vk::PhysicalDeviceType::INTEGRATED_GPU {
-                            let inner = new_inner(physical_devices[index], *properties)?;
-                            return Ok(inner);
-                        }
-                    }
-                }
+            Ok(result)
+        }
+    }
+
+    pub fn enumerate(instance: Arc<InstanceInner>) -> Result<Vec<AdapterInner>, Error> {
+        AdapterInner::enumerate_physical_devices(&instance)?
+            .into_iter()
+            .map(|(physical_device, properties)| {
+                AdapterInner::from_physical_device(
+                    instance.clone(),
+                    physical_device,
+                    properties,
+                    AdapterOptions::default(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn new(instance: Arc<InstanceInner>, options: AdapterOptions) -> Result<AdapterInner, Error> {
+        let candidates: Vec<_> = AdapterInner::enumerate_physical_devices(&instance)?
+            .into_iter()
+            .filter(|(_, properties)| is_match(properties, &options))
+            .collect();
+
+        let preferred_type = match options.power_preference {
+            PowerPreference::HighPerformance => vk::PhysicalDeviceType::DISCRETE_GPU,
+            PowerPreference::LowPower => vk::PhysicalDeviceType::INTEGRATED_GPU,
+        };
+
+        let selected = candidates
+            .iter()
+            .find(|(_, properties)| properties.device_type == preferred_type)
+            .or_else(|| candidates.first())
+            .cloned();
+
+        match selected {
+            Some((physical_device, properties)) => {
+                AdapterInner::from_physical_device(instance, physical_device, properties, options)
+            }
+            None => {
+                log::error!("no adapters matched the adapter options: {:?}", options);
+                Err(Error::from("No adapters were found"))
             }
-            physical_devices
-                .first()
-                .cloned()
-                .ok_or_else(|| Error::from("No adapters were found"))
-                .and_then(|physical_device| new_inner(physical_device, physical_device_properties[0]))
         }
     }
 
@@ -193,6 +235,99 @@ impl AdapterInner {
         }
     }
 
+    pub fn format_properties(&self, format: vk::Format) -> vk::FormatProperties {
+        self.physical_device_format_properties
+            .binary_search_by_key(&format, |(format, _)| *format)
+            .map(|index| self.physical_device_format_properties[index].1)
+            .unwrap_or_default()
+    }
+
+    pub fn supports_extension(&self, name: &CStr) -> bool {
+        self.extension_properties
+            .iter()
+            .any(|p| unsafe { CStr::from_ptr(p.extension_name.as_ptr()) } == name)
+    }
+
+    /// VK_EXT_memory_budget is queried through vkGetPhysicalDeviceMemoryProperties2, which needs
+    /// VK_KHR_get_physical_device_properties2 or Vulkan 1.1 on both the instance and the physical device.
+    pub fn supports_memory_budget(&self) -> bool {
+        let version_1_1 = ash::vk_make_version!(1, 1, 0);
+        let memory_properties2 = self
+            .instance
+            .raw_ext
+            .get_physical_device_memory_properties2_khr
+            .is_some()
+            || (self.instance.api_version >= version_1_1 && self.physical_device_properties.api_version >= version_1_1);
+        memory_properties2 && self.supports_extension(vk::ExtMemoryBudgetFn::name())
+    }
+
+    /// The Vulkan format of `format` on this adapter, which resolves `D24PlusS8`
+    pub fn image_format(&self, format: TextureFormat) -> vk::Format {
+        match format {
+            TextureFormat::D24PlusS8 => {
+                let features = self
+                    .format_properties(vk::Format::D24_UNORM_S8_UINT)
+                    .optimal_tiling_features;
+                if features.contains(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT) {
+                    vk::Format::D24_UNORM_S8_UINT
+                } else {
+                    vk::Format::D32_SFLOAT_S8_UINT
+                }
+            }
+            format => texture::image_format(format),
+        }
+    }
+
+    pub fn texture_format_capabilities(&self, format: TextureFormat) -> TextureFormatCapabilities {
+        let features = self
+            .format_properties(self.image_format(format))
+            .optimal_tiling_features;
+        let limits = &self.physical_device_properties.limits;
+
+        let attachment_feature = if texture::is_depth_or_stencil(format) {
+            vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT
+        } else {
+            vk::FormatFeatureFlags::COLOR_ATTACHMENT
+        };
+        let render_attachment = features.contains(attachment_feature);
+        let sampled = features.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE);
+        let storage = features.contains(vk::FormatFeatureFlags::STORAGE_IMAGE);
+
+        // Multisampling is only available for formats that can be rendered to. The counts
+        // are further restricted by every other usage the format supports.
+        let mut sample_counts = vk::SampleCountFlags::TYPE_1;
+        if render_attachment {
+            sample_counts = if texture::is_depth(format) && texture::is_stencil(format) {
+                limits.framebuffer_depth_sample_counts & limits.framebuffer_stencil_sample_counts
+            } else if texture::is_depth(format) {
+                limits.framebuffer_depth_sample_counts
+            } else {
+                limits.framebuffer_color_sample_counts
+            };
+            if sampled {
+                sample_counts &= if texture::is_depth_or_stencil(format) {
+                    limits.sampled_image_depth_sample_counts
+                } else if texture::is_integer(format) {
+                    limits.sampled_image_integer_sample_counts
+                } else {
+                    limits.sampled_image_color_sample_counts
+                };
+            }
+            sample_counts |= vk::SampleCountFlags::TYPE_1;
+        }
+
+        TextureFormatCapabilities {
+            sampled,
+            storage,
+            render_attachment,
+            blendable: features.contains(vk::FormatFeatureFlags::COLOR_ATTACHMENT_BLEND),
+            linear_filterable: features.contains(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR),
+            blit_src: features.contains(vk::FormatFeatureFlags::BLIT_SRC),
+            blit_dst: features.contains(vk::FormatFeatureFlags::BLIT_DST),
+            sample_counts: sample_counts.as_raw(),
+        }
+    }
+
     pub fn properties(&self) -> AdapterProperties {
         let api_major = ash::vk_version_major!(self.physical_device_properties.api_version);
         let api_minor = ash::vk_version_minor!(self.physical_device_properties.api_version);
@@ -213,6 +348,7 @@ impl AdapterInner {
             device_type: self.physical_device_properties.device_type,
             device_name,
             limits: self.physical_device_properties.limits,
+            memory_budget: self.supports_memory_budget(),
         }
     }
 }
@@ -226,6 +362,8 @@ pub struct AdapterProperties<'a> {
     pub device_id: u32,
     pub device_type: ash::vk::PhysicalDeviceType,
     pub limits: ash::vk::PhysicalDeviceLimits,
+    /// `Device::memory_report` includes heap budgets (VK_EXT_memory_budget)
+    pub memory_budget: bool,
 }
 
 impl Debug for Adapter {
@@ -239,6 +377,78 @@ impl Debug for Adapter {
     }
 }
 
+fn supported_extensions(
+    features: &vk::PhysicalDeviceFeatures,
+    extension_properties: &[vk::ExtensionProperties],
+) -> Extensions {
+    let has_extension = |name: &CStr| {
+        extension_properties
+            .iter()
+            .any(|p| unsafe { CStr::from_ptr(p.extension_name.as_ptr()) } == name)
+    };
+    Extensions {
+        depth_clamp: features.depth_clamp == vk::TRUE,
+        fill_mode_non_solid: features.fill_mode_non_solid == vk::TRUE,
+        wide_lines: features.wide_lines == vk::TRUE,
+        multi_draw_indirect: features.multi_draw_indirect == vk::TRUE,
+        sampler_anisotropy: features.sampler_anisotropy == vk::TRUE,
+        texture_compression_bc: features.texture_compression_bc == vk::TRUE,
+        texture_compression_etc2: features.texture_compression_etc2 == vk::TRUE,
+        texture_compression_astc: features.texture_compression_astc_ldr == vk::TRUE,
+        independent_blend: features.independent_blend == vk::TRUE,
+        shader_float64: features.shader_float64 == vk::TRUE,
+        robust_buffer_access: features.robust_buffer_access == vk::TRUE,
+        depth_bounds: features.depth_bounds == vk::TRUE,
+        sample_rate_shading: features.sample_rate_shading == vk::TRUE,
+        texture_cube_array: features.image_cube_array == vk::TRUE,
+        conservative_rasterization: has_extension(vk::ExtConservativeRasterizationFn::name()),
+    }
+}
+
+fn supported_limits(limits: &vk::PhysicalDeviceLimits) -> Limits {
+    Limits {
+        max_bind_groups: limits
+            .max_bound_descriptor_sets
+            .min(command_buffer::MAX_BIND_GROUPS as u32),
+        max_bindings_per_bind_group: limits
+            .max_per_stage_resources
+            .min(binding::MAX_BINDINGS_PER_GROUP as u32),
+        max_dynamic_uniform_buffers_per_pipeline_layout: limits.max_descriptor_set_uniform_buffers_dynamic,
+        max_dynamic_storage_buffers_per_pipeline_layout: limits.max_descriptor_set_storage_buffers_dynamic,
+        max_uniform_buffer_binding_size: limits.max_uniform_buffer_range,
+        max_storage_buffer_binding_size: limits.max_storage_buffer_range,
+        max_texture_dimension_1d: limits.max_image_dimension1_d,
+        max_texture_dimension_2d: limits.max_image_dimension2_d,
+        max_texture_dimension_3d: limits.max_image_dimension3_d,
+        max_texture_array_layers: limits.max_image_array_layers,
+        max_push_constant_size: limits
+            .max_push_constants_size
+            .min(pipeline::MAX_PUSH_CONSTANTS_SIZE as u32),
+        min_uniform_buffer_offset_alignment: limits.min_uniform_buffer_offset_alignment as u32,
+        min_storage_buffer_offset_alignment: limits.min_storage_buffer_offset_alignment as u32,
+    }
+}
+
+fn is_match(properties: &vk::PhysicalDeviceProperties, options: &AdapterOptions) -> bool {
+    if let Some(device_type) = options.device_type {
+        if properties.device_type != device_type {
+            return false;
+        }
+    }
+    if let Some(vendor_id) = options.vendor_id {
+        if properties.vendor_id != vendor_id {
+            return false;
+        }
+    }
+    if let Some(name) = options.name.as_ref() {
+        let device_name = unsafe { CStr::from_ptr(properties.device_name.as_ptr()) }.to_string_lossy();
+        if !device_name.to_lowercase().contains(&name.to_lowercase()) {
+            return false;
+        }
+    }
+    true
+}
+
 impl AdapterOptions {
     pub fn new() -> AdapterOptions {
         AdapterOptions::default()
@@ -253,6 +463,21 @@ impl AdapterOptions {
         self.power_preference = PowerPreference::LowPower;
         self
     }
+
+    pub fn device_type(mut self, device_type: vk::PhysicalDeviceType) -> Self {
+        self.device_type = Some(device_type);
+        self
+    }
+
+    pub fn name<S: Into<Cow<'static, str>>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn vendor_id(mut self, vendor_id: u32) -> Self {
+        self.vendor_id = Some(vendor_id);
+        self
+    }
 }
 
 static VK_FORMATS: &'static [vk::Format] = &[
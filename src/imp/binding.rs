@@ -3,15 +3,18 @@ use ash::vk;
 
 use crate::error::Error;
 use crate::imp::fenced_deleter::DeleteWhenUnused;
-use crate::imp::{BindGroupInner, BindGroupLayoutInner, DeviceInner};
+use crate::imp::{debug, BindGroupInner, BindGroupLayoutInner, DeviceInner};
 use crate::{
     BindGroup, BindGroupBinding, BindGroupDescriptor, BindGroupLayout, BindGroupLayoutBinding,
-    BindGroupLayoutDescriptor, BindingResource, BindingType, ShaderStageFlags,
+    BindGroupLayoutDescriptor, BindingResource, BindingType, Limits, ShaderStageFlags,
 };
 
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 
+pub const MAX_BINDINGS_PER_GROUP: usize = 16;
+
 pub fn descriptor_type(binding_type: BindingType) -> vk::DescriptorType {
     match binding_type {
         BindingType::Sampler => vk::DescriptorType::SAMPLER,
@@ -38,24 +41,40 @@ pub fn shader_stage_flags(visibility: ShaderStageFlags) -> vk::ShaderStageFlags
     flags
 }
 
+pub fn create_descriptor_set_layout(
+    device: &DeviceInner,
+    bindings: &[BindGroupLayoutBinding],
+) -> Result<vk::DescriptorSetLayout, Error> {
+    let bindings: Vec<_> = bindings
+        .iter()
+        .map(|binding| vk::DescriptorSetLayoutBinding {
+            binding: binding.binding,
+            descriptor_type: descriptor_type(binding.binding_type),
+            stage_flags: shader_stage_flags(binding.visibility),
+            // TODO: Arrays?
+            descriptor_count: 1,
+            ..Default::default()
+        })
+        .collect();
+
+    let create_info = vk::DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+
+    Ok(unsafe { device.raw.create_descriptor_set_layout(&create_info, None)? })
+}
+
 impl BindGroupLayoutInner {
     pub fn new(device: Arc<DeviceInner>, descriptor: BindGroupLayoutDescriptor) -> Result<BindGroupLayoutInner, Error> {
-        let bindings: Vec<_> = descriptor
-            .bindings
-            .iter()
-            .map(|binding| vk::DescriptorSetLayoutBinding {
-                binding: binding.binding,
-                descriptor_type: descriptor_type(binding.binding_type),
-                stage_flags: shader_stage_flags(binding.visibility),
-                // TODO: Arrays?
-                descriptor_count: 1,
-                ..Default::default()
-            })
-            .collect();
-
-        let create_info = vk::DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+        let limits = &device.limits;
+        if descriptor.bindings.len() > limits.max_bindings_per_bind_group as usize {
+            log::error!(
+                "bind group layout binding count ({}) exceeds max_bindings_per_bind_group ({})",
+                descriptor.bindings.len(),
+                limits.max_bindings_per_bind_group
+            );
+            return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+        }
 
-        let handle = unsafe { device.raw.create_descriptor_set_layout(&create_info, None)? };
+        let handle = create_descriptor_set_layout(&device, &descriptor.bindings)?;
 
         Ok(BindGroupLayoutInner {
             handle,
@@ -99,6 +118,37 @@ pub fn find_layout_binding(
     })
 }
 
+fn validate_buffer_binding(limits: &Limits, binding_type: BindingType, range: &Range<usize>) -> Result<(), String> {
+    let (max_size, min_alignment) = match binding_type {
+        BindingType::UniformBuffer | BindingType::DynamicUniformBuffer => (
+            limits.max_uniform_buffer_binding_size,
+            limits.min_uniform_buffer_offset_alignment,
+        ),
+        _ => (
+            limits.max_storage_buffer_binding_size,
+            limits.min_storage_buffer_offset_alignment,
+        ),
+    };
+    if range.end < range.start {
+        return Err(format!("buffer binding range is inverted: {:?}", range));
+    }
+    if range.end - range.start > max_size as usize {
+        return Err(format!(
+            "buffer binding size ({}) exceeds the limit for {:?} ({})",
+            range.end - range.start,
+            binding_type,
+            max_size
+        ));
+    }
+    if range.start % min_alignment as usize != 0 {
+        return Err(format!(
+            "buffer binding offset ({}) is not a multiple of the {:?} offset alignment ({})",
+            range.start, binding_type, min_alignment
+        ));
+    }
+    Ok(())
+}
+
 impl BindGroupInner {
     pub fn new(descriptor: BindGroupDescriptor) -> Result<BindGroupInner, Error> {
         // TODO: DescriptorPool management. Dawn specifically calls out that this is inefficient
@@ -130,6 +180,7 @@ impl BindGroupInner {
             bindings: descriptor.bindings.to_vec(),
             descriptor_pool: vk::DescriptorPool::default(),
             handle: vk::DescriptorSet::default(),
+            label: debug::Label::default(),
         };
 
         unsafe {
@@ -162,8 +213,9 @@ impl BindGroupInner {
             }
         }
 
-        // TODO: Bind limits
-        const MAX_BINDINGS_PER_GROUP: usize = 16;
+        if let Some(label) = descriptor.label {
+            bind_group.label.set(&device, bind_group.handle, label);
+        }
 
         let mut writes = vec![vk::WriteDescriptorSet::default(); MAX_BINDINGS_PER_GROUP];
         let mut buffer_infos = vec![vk::DescriptorBufferInfo::default(); MAX_BINDINGS_PER_GROUP];
@@ -175,8 +227,8 @@ impl BindGroupInner {
         for (index, binding) in descriptor.bindings.iter().enumerate() {
             let layout_binding = find_layout_binding(index, binding.binding, &layout_bindings).ok_or_else(|| {
                 let msg = format!(
-                    "BindGroupLayout mismatch: BindGroupLayoutBinding not found (binding: {}, index: {})",
-                    binding.binding, index
+                    "BindGroupLayout mismatch: BindGroupLayoutBinding not found (binding: {}, index: {}, label: {:?})",
+                    binding.binding, index, descriptor.label
                 );
                 Error::from(msg)
             })?;
@@ -193,9 +245,19 @@ impl BindGroupInner {
                 | (&BindingResource::Buffer(ref buffer, ref range), BindingType::DynamicUniformBuffer)
                 | (&BindingResource::Buffer(ref buffer, ref range), BindingType::StorageBuffer)
                 | (&BindingResource::Buffer(ref buffer, ref range), BindingType::DynamicStorageBuffer) => {
+                    validate_buffer_binding(&device.limits, layout_binding.binding_type, range).map_err(|msg| {
+                        log::error!(
+                            "{} (binding: {}, index: {}, label: {:?})",
+                            msg,
+                            binding.binding,
+                            index,
+                            descriptor.label
+                        );
+                        Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT)
+                    })?;
                     buffer_infos[num_writes].buffer = buffer.inner.handle;
                     buffer_infos[num_writes].offset = range.start as u64;
-                    buffer_infos[num_writes].range = range.end as u64;
+                    buffer_infos[num_writes].range = (range.end - range.start) as u64;
                     write.p_buffer_info = &buffer_infos[num_writes];
                 }
                 (&BindingResource::Sampler(ref sampler), BindingType::Sampler) => {
@@ -219,8 +281,8 @@ impl BindGroupInner {
                         BindingResource::Buffer(_, _) => "Buffer",
                         BindingResource::BufferView(_) => "BufferView",
                     };
-                    let msg = format!("BindingType is not valid for the BindingResource (binding: {}, index: {}): BindingType: {:?}, BindingResource: {:?}",
-                          binding.binding, index, layout_binding.binding_type, resource_type);
+                    let msg = format!("BindingType is not valid for the BindingResource (binding: {}, index: {}, label: {:?}): BindingType: {:?}, BindingResource: {:?}",
+                          binding.binding, index, descriptor.label, layout_binding.binding_type, resource_type);
                     return Err(Error::from(msg));
                 }
             }
@@ -264,6 +326,16 @@ impl Into<BindGroup> for BindGroupInner {
 }
 
 impl BindGroup {
+    pub fn set_label(&self, label: &str) {
+        self.inner
+            .label
+            .set(&self.inner.layout.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+
     pub fn bindings(&self) -> &[BindGroupBinding] {
         &self.inner.bindings
     }
@@ -4,8 +4,10 @@ use ash::vk::{DependencyFlags, MemoryPropertyFlags};
 
 use vk_mem::{AllocationCreateFlags, AllocationCreateInfo, MemoryUsage};
 
+use crate::imp::debug::WithoutLabel;
 use crate::imp::fenced_deleter::DeleteWhenUnused;
-use crate::imp::{pipeline, texture, BufferInner, BufferState, BufferViewInner, DeviceInner};
+use crate::imp::pass_resource_usage::QueueResource;
+use crate::imp::{debug, pipeline, queue, texture, BufferInner, BufferState, BufferViewInner, DeviceInner, QueueInfo};
 use crate::{
     Buffer, BufferDescriptor, BufferUsageFlags, BufferView, BufferViewDescriptor, BufferViewFormat, Error,
     MappedBuffer, WriteData,
@@ -208,16 +210,25 @@ impl BufferInner {
 
         log::trace!("created buffer: {:?}, allocation_info: {:?}", buffer, allocation_info);
 
+        state
+            .memory_usage_mut()
+            .add_buffer(descriptor.usage, allocation_info.get_size());
+
         drop(state);
 
+        let label = debug::Label::new(&device, buffer, descriptor.label);
+        let queue_family_index = device.queue.queue_family_index;
+
         Ok(BufferInner {
-            descriptor,
+            descriptor: descriptor.without_label(),
             allocation,
             allocation_info,
             device,
             last_usage: Mutex::new(BufferUsageFlags::NONE),
+            queue_family_index: Mutex::new(queue_family_index),
             buffer_state: Mutex::new(BufferState::Unmapped),
             handle: buffer,
+            label,
         })
     }
 
@@ -248,11 +259,14 @@ impl BufferInner {
             return Ok(());
         }
 
-        let src_stage_mask = pipeline_stage(*last_usage);
-        let dst_stage_mask = pipeline_stage(usage);
+        // the buffer is owned by the queue family that the command buffer was allocated for
+        let queue_flags = self.device.queue_flags(*self.queue_family_index.lock());
 
-        let src_access_mask = access_flags(*last_usage);
-        let dst_access_mask = access_flags(usage);
+        let src_stage_mask = queue::supported_stages(queue_flags, pipeline_stage(*last_usage));
+        let dst_stage_mask = queue::supported_stages(queue_flags, pipeline_stage(usage));
+
+        let src_access_mask = queue::supported_access(queue_flags, access_flags(*last_usage));
+        let dst_access_mask = queue::supported_access(queue_flags, access_flags(usage));
 
         log::trace!(
             "usage: {:?}, last_usage: {:?}, src_stage_mask: {:?}, src_access_mask: {:?}",
@@ -302,11 +316,71 @@ impl BufferInner {
         Ok(())
     }
 
+    /// Records the release (on the source queue) or acquire (on the destination queue) half of a
+    /// queue family ownership transfer
+    pub fn transfer_queue_family(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        queue: &QueueInfo,
+        src_queue_family_index: u32,
+        dst_queue_family_index: u32,
+    ) {
+        let last_usage = *self.last_usage.lock();
+
+        let (src_stage_mask, src_access_mask, dst_stage_mask, dst_access_mask) =
+            if queue.queue_family_index == src_queue_family_index {
+                (
+                    queue::supported_stages(queue.queue_flags, pipeline_stage(last_usage)),
+                    queue::supported_access(queue.queue_flags, access_flags(last_usage)),
+                    vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                    vk::AccessFlags::empty(),
+                )
+            } else {
+                (
+                    vk::PipelineStageFlags::TOP_OF_PIPE,
+                    vk::AccessFlags::empty(),
+                    vk::PipelineStageFlags::ALL_COMMANDS,
+                    vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE,
+                )
+            };
+
+        log::trace!(
+            "transfer_queue_family buffer: {:?}, last_usage: {:?}, src_queue_family_index: {}, dst_queue_family_index: {}",
+            self.handle,
+            last_usage,
+            src_queue_family_index,
+            dst_queue_family_index
+        );
+
+        let buffer_memory_barrier = vk::BufferMemoryBarrier {
+            src_access_mask,
+            dst_access_mask,
+            src_queue_family_index,
+            dst_queue_family_index,
+            buffer: self.handle,
+            offset: 0,
+            size: self.descriptor.size as u64,
+            ..Default::default()
+        };
+
+        unsafe {
+            self.device.raw.cmd_pipeline_barrier(
+                command_buffer,
+                src_stage_mask,
+                dst_stage_mask,
+                DependencyFlags::empty(),
+                &[],
+                &[buffer_memory_barrier],
+                &[],
+            );
+        }
+    }
+
     pub unsafe fn get_mapped_ptr(&self) -> Result<*mut u8, Error> {
         let mut buffer_state = self.buffer_state.lock();
         match *buffer_state {
             BufferState::Mapped(_) => {
-                log::warn!("buffer already mapped: {:?}", self.handle);
+                log::warn!("buffer already mapped: {:?}, label: {:?}", self.handle, self.label);
                 // TODO: Validation
                 Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT))
             }
@@ -359,6 +433,9 @@ impl Drop for BufferInner {
             .map_err(|e| log::error!("failed to unmap_memory: {:?}", e))
             .ok();
         let mut state = self.device.state.lock();
+        state
+            .memory_usage_mut()
+            .remove_buffer(self.descriptor.usage, self.allocation_info.get_size());
         let serial = state.get_next_pending_serial();
         state
             .get_fenced_deleter()
@@ -378,15 +455,16 @@ impl MappedBuffer {
         let buffer_size = self.inner.descriptor.size as usize;
         let offset_bytes = element_size * element_offset;
         if !self.inner.descriptor.usage.intersects(flags) {
-            let msg = format!("missing required usage: {:?}", flags);
+            let msg = format!("missing required usage: {:?}, label: {:?}", flags, self.inner.label);
             return Err(Error::from(msg));
         }
         if buffer_size < offset_bytes + data_size {
             log::error!(
-                "mapping range exceeds buffer size: offset_bytes: {}, data_size: {}, buffer_size: {}",
+                "mapping range exceeds buffer size: offset_bytes: {}, data_size: {}, buffer_size: {}, label: {:?}",
                 offset_bytes,
                 data_size,
-                buffer_size
+                buffer_size,
+                self.inner.label
             );
             return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
         }
@@ -538,6 +616,14 @@ impl<'a, T> Drop for WriteData<'a, T> {
 }
 
 impl Buffer {
+    pub fn set_label(&self, label: &str) {
+        self.inner.label.set(&self.inner.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+
     /// Uploads all elements of `data` into the buffer. The buffer `offset` is in units of `T`.
     /// The buffer requires the `TRANSFER_DST` usage flag to be set.
     ///
@@ -562,17 +648,21 @@ impl Buffer {
         let buffer_size = self.inner.descriptor.size as usize;
         if offset_bytes + data_size > buffer_size {
             log::error!(
-                "set_sub_data range exceeds buffer size; offset: {}, data_size: {:?}, buffer_size: {:?}",
+                "set_sub_data range exceeds buffer size; offset: {}, data_size: {:?}, buffer_size: {:?}, label: {:?}",
                 offset,
                 data_size,
-                buffer_size
+                buffer_size,
+                self.inner.label
             );
             return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
         }
 
         let mut state = self.inner.device.state.lock();
 
-        let command_buffer = state.get_pending_command_buffer(&self.inner.device)?;
+        let device = &self.inner.device;
+        let command_buffer = state.get_pending_command_buffer(device)?;
+        let resources = [QueueResource::Buffer(self.inner.clone())];
+        state.transfer_ownership(device, &resources, &device.queue, command_buffer)?;
         if BufferUsageFlags::TRANSFER_DST != *self.inner.last_usage.lock() {
             self.inner
                 .transition_usage_now(command_buffer, BufferUsageFlags::TRANSFER_DST)?;
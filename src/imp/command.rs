@@ -1,16 +1,20 @@
 use std::sync::Arc;
 
+use ash::vk;
 use smallvec::SmallVec;
 
 use crate::imp::command_buffer::MAX_BIND_GROUPS;
 use crate::imp::command_encoder::{RenderPassColorAttachmentInfo, RenderPassDepthStencilAttachmentInfo};
-use crate::imp::{BindGroupInner, BufferInner, ComputePipelineInner, RenderPipelineInner, TextureInner};
+use crate::imp::{
+    BindGroupInner, BufferInner, ComputePipelineInner, PipelineLayoutInner, RenderPipelineInner, TextureInner,
+};
 use crate::{Buffer, Color, Extent3D, FilterMode, Origin3D, ShaderStageFlags};
 
 #[derive(Debug, Clone)]
 pub struct BufferCopy {
     pub buffer: Arc<BufferInner>,
     pub offset: usize,
+    /// Specified in texels
     pub row_length: u32,
     pub image_height: u32,
 }
@@ -20,6 +24,7 @@ pub struct TextureCopy {
     pub texture: Arc<TextureInner>,
     pub mip_level: u32,
     pub array_layer: u32,
+    pub array_layer_count: u32,
     pub origin_texels: Origin3D,
 }
 
@@ -112,6 +117,12 @@ pub enum Command {
     SetRenderPipeline {
         pipeline: Arc<RenderPipelineInner>,
     },
+    /// Binds a pipeline of the device's downsample pipeline cache, which outlives the command buffer.
+    /// `layout` must be compatible with the layout the pipeline was created with.
+    SetDownsamplePipeline {
+        pipeline: vk::Pipeline,
+        layout: Arc<PipelineLayoutInner>,
+    },
     SetPushConstants {
         stages: ShaderStageFlags,
         offset_bytes: u32,
@@ -5,10 +5,10 @@ use typed_arena::Arena;
 
 use crate::imp::command::{BufferCopy, Command, TextureBlit, TextureCopy};
 use crate::imp::fenced_deleter::DeleteWhenUnused;
-use crate::imp::pass_resource_usage::CommandBufferResourceUsage;
+use crate::imp::pass_resource_usage::{CommandBufferResourceUsage, QueueResource};
 use crate::imp::render_pass::{ColorInfo, DepthStencilInfo, RenderPassCacheQuery};
 use crate::imp::{binding, pipeline};
-use crate::imp::{render_pass, sampler, texture, util, DeviceInner, PipelineLayoutInner};
+use crate::imp::{render_pass, sampler, texture, util, DeviceInner, PipelineLayoutInner, QueueInfo};
 use crate::imp::{CommandBufferInner, RenderPipelineInner};
 use crate::{BufferUsageFlags, DrawIndirectCommand, Error, Extent3D, IndexFormat, ShaderStageFlags, TextureUsageFlags};
 
@@ -89,13 +89,13 @@ fn buffer_image_copy(
 ) -> vk::BufferImageCopy {
     vk::BufferImageCopy {
         buffer_offset: buffer_copy.offset as vk::DeviceSize,
-        buffer_row_length: buffer_copy.row_length, // TODO: row_pitch
+        buffer_row_length: buffer_copy.row_length,
         buffer_image_height: buffer_copy.image_height,
         image_subresource: vk::ImageSubresourceLayers {
             aspect_mask: texture::aspect_mask(texture_copy.texture.descriptor.format),
             mip_level: texture_copy.mip_level,
             base_array_layer: texture_copy.array_layer,
-            layer_count: 1,
+            layer_count: texture_copy.array_layer_count,
         },
         image_offset: vk::Offset3D {
             x: texture_copy.origin_texels.x,
@@ -110,13 +110,21 @@ fn buffer_image_copy(
     }
 }
 
+fn subresource_range(texture_copy: &TextureCopy) -> texture::SubresourceRange {
+    texture::SubresourceRange {
+        mip_level: texture_copy.mip_level,
+        base_array_layer: texture_copy.array_layer,
+        array_layer_count: texture_copy.array_layer_count,
+    }
+}
+
 fn image_copy(src: &TextureCopy, dst: &TextureCopy, size_texels: Extent3D) -> vk::ImageCopy {
     vk::ImageCopy {
         src_subresource: vk::ImageSubresourceLayers {
             aspect_mask: texture::aspect_mask(src.texture.descriptor.format),
             mip_level: src.mip_level,
             base_array_layer: src.array_layer,
-            layer_count: 1,
+            layer_count: src.array_layer_count,
         },
         src_offset: vk::Offset3D {
             x: src.origin_texels.x,
@@ -127,7 +135,7 @@ fn image_copy(src: &TextureCopy, dst: &TextureCopy, size_texels: Extent3D) -> vk
             aspect_mask: texture::aspect_mask(dst.texture.descriptor.format),
             mip_level: dst.mip_level,
             base_array_layer: dst.array_layer,
-            layer_count: 1,
+            layer_count: dst.array_layer_count,
         },
         dst_offset: vk::Offset3D {
             x: dst.origin_texels.x,
@@ -219,7 +227,47 @@ fn pop_debug_group(device: &DeviceInner, command_buffer: vk::CommandBuffer) {
 }
 
 impl CommandBufferInner {
+    /// Every buffer and texture that is used by the command buffer
+    pub fn queue_resources(&self) -> Vec<QueueResource> {
+        self.state.resource_usages.queue_resources()
+    }
+
+    /// Checks that the commands can be executed on `queue`
+    pub fn validate_queue(&self, queue: &QueueInfo) -> Result<(), Error> {
+        for command in self.state.iter() {
+            let required_queue_flags = match command {
+                Command::BeginRenderPass { .. } | Command::BlitTextureToTexture { .. } => vk::QueueFlags::GRAPHICS,
+                Command::BeginComputePass => vk::QueueFlags::COMPUTE,
+                _ => continue,
+            };
+            if !queue.queue_flags.contains(required_queue_flags) {
+                log::error!(
+                    "command buffer requires a queue with {:?}; queue_type: {:?}, queue_flags: {:?}, label: {:?}",
+                    required_queue_flags,
+                    queue.queue_type,
+                    queue.queue_flags,
+                    self.label
+                );
+                return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+            }
+        }
+        Ok(())
+    }
+
     pub fn record_commands(&self, command_buffer: vk::CommandBuffer, state: &mut DeviceState) -> Result<(), Error> {
+        match self.label.as_ref() {
+            Some(label) => {
+                // keep the label balanced even if recording fails
+                push_debug_group(&self.device, command_buffer, label);
+                let result = self.record(command_buffer, state);
+                pop_debug_group(&self.device, command_buffer);
+                result
+            }
+            None => self.record(command_buffer, state),
+        }
+    }
+
+    fn record(&self, command_buffer: vk::CommandBuffer, state: &mut DeviceState) -> Result<(), Error> {
         let mut pass = 0;
         let mut command_iter = self.state.iter();
         while let Some(command) = command_iter.next() {
@@ -246,8 +294,11 @@ impl CommandBufferInner {
                 Command::CopyBufferToTexture { src, dst, size_texels } => {
                     src.buffer
                         .transition_usage_now(command_buffer, BufferUsageFlags::TRANSFER_SRC)?;
-                    dst.texture
-                        .transition_usage_now(command_buffer, TextureUsageFlags::TRANSFER_DST, None)?;
+                    dst.texture.transition_usage_now(
+                        command_buffer,
+                        TextureUsageFlags::TRANSFER_DST,
+                        Some(subresource_range(dst)),
+                    )?;
                     let region = buffer_image_copy(src, dst, *size_texels);
                     unsafe {
                         self.device.raw.cmd_copy_buffer_to_image(
@@ -260,8 +311,11 @@ impl CommandBufferInner {
                     }
                 }
                 Command::CopyTextureToBuffer { src, dst, size_texels } => {
-                    src.texture
-                        .transition_usage_now(command_buffer, TextureUsageFlags::TRANSFER_SRC, None)?;
+                    src.texture.transition_usage_now(
+                        command_buffer,
+                        TextureUsageFlags::TRANSFER_SRC,
+                        Some(subresource_range(src)),
+                    )?;
                     dst.buffer
                         .transition_usage_now(command_buffer, BufferUsageFlags::TRANSFER_DST)?;
                     let region = buffer_image_copy(dst, src, *size_texels);
@@ -277,18 +331,12 @@ impl CommandBufferInner {
                 }
                 Command::CopyTextureToTexture { dst, src, size_texels } => {
                     let src_usage = TextureUsageFlags::TRANSFER_SRC;
-                    let src_subresource = Some(texture::Subresource {
-                        array_layer: src.array_layer,
-                        mip_level: src.mip_level,
-                    });
+                    let src_subresource = Some(subresource_range(src));
                     src.texture
                         .transition_usage_now(command_buffer, src_usage, src_subresource)?;
 
                     let dst_usage = TextureUsageFlags::TRANSFER_DST;
-                    let dst_subresource = Some(texture::Subresource {
-                        array_layer: dst.array_layer,
-                        mip_level: dst.mip_level,
-                    });
+                    let dst_subresource = Some(subresource_range(dst));
                     dst.texture
                         .transition_usage_now(command_buffer, dst_usage, dst_subresource)?;
 
@@ -307,17 +355,19 @@ impl CommandBufferInner {
                 }
                 Command::BlitTextureToTexture { src, dst, filter } => {
                     let src_usage = TextureUsageFlags::TRANSFER_SRC;
-                    let src_subresource = Some(texture::Subresource {
-                        array_layer: src.array_layer,
+                    let src_subresource = Some(texture::SubresourceRange {
                         mip_level: src.mip_level,
+                        base_array_layer: src.array_layer,
+                        array_layer_count: 1,
                     });
                     src.texture
                         .transition_usage_now(command_buffer, src_usage, src_subresource)?;
 
                     let dst_usage = TextureUsageFlags::TRANSFER_DST;
-                    let dst_subresource = Some(texture::Subresource {
-                        array_layer: dst.array_layer,
+                    let dst_subresource = Some(texture::SubresourceRange {
                         mip_level: dst.mip_level,
+                        base_array_layer: dst.array_layer,
+                        array_layer_count: 1,
                     });
                     dst.texture
                         .transition_usage_now(command_buffer, dst_usage, dst_subresource)?;
@@ -477,8 +527,6 @@ impl CommandBufferInner {
 
     fn record_render_pass_dynamic_state_defaults(&self, command_buffer: vk::CommandBuffer, width: u32, height: u32) {
         unsafe {
-            self.device.raw.cmd_set_line_width(command_buffer, 1.0);
-            self.device.raw.cmd_set_depth_bounds(command_buffer, 0.0, 1.0);
             self.device
                 .raw
                 .cmd_set_stencil_reference(command_buffer, vk::StencilFaceFlags::STENCIL_FRONT_AND_BACK, 0);
@@ -677,6 +725,14 @@ impl CommandBufferInner {
                     }
                     descriptor_sets.on_pipeline_layout_change(&pipeline.layout);
                 }
+                Command::SetDownsamplePipeline { pipeline, layout } => {
+                    last_pipeline = None;
+                    let bind_point = vk::PipelineBindPoint::GRAPHICS;
+                    unsafe {
+                        self.device.raw.cmd_bind_pipeline(command_buffer, bind_point, *pipeline);
+                    }
+                    descriptor_sets.on_pipeline_layout_change(layout);
+                }
                 Command::SetStencilReference { reference } => {
                     let front_face = vk::StencilFaceFlags::STENCIL_FRONT_AND_BACK;
                     unsafe {
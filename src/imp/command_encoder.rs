@@ -5,9 +5,10 @@ use std::convert::TryFrom;
 
 use crate::{
     BindGroup, BindingType, Buffer, BufferCopyView, BufferUsageFlags, Color, CommandBuffer, CommandEncoder,
-    ComputePassEncoder, ComputePipeline, Extent3D, FilterMode, LoadOp, RenderPassColorAttachmentDescriptor,
-    RenderPassDepthStencilAttachmentDescriptor, RenderPassDescriptor, RenderPassEncoder, RenderPipeline,
-    ShaderStageFlags, StoreOp, TextureBlitView, TextureCopyView, TextureUsageFlags,
+    CommandEncoderDescriptor, ComputePassEncoder, ComputePipeline, Extent3D, FilterMode, LoadOp,
+    RenderPassColorAttachmentDescriptor, RenderPassDepthStencilAttachmentDescriptor, RenderPassDescriptor,
+    RenderPassEncoder, RenderPipeline, ShaderStageFlags, StoreOp, Texture, TextureBlitView, TextureCopyView,
+    TextureSubresourceRange, TextureUsageFlags,
 };
 
 use std::sync::Arc;
@@ -15,7 +16,7 @@ use std::sync::Arc;
 use crate::imp::command::{BufferCopy, Command, TextureBlit, TextureCopy};
 use crate::imp::command_buffer::CommandBufferState;
 use crate::imp::pass_resource_usage::{CommandBufferResourceUsage, PassResourceUsageTracker};
-use crate::imp::{binding, pipeline};
+use crate::imp::{binding, mipmap, pipeline, texture};
 use crate::imp::{
     CommandBufferInner, CommandEncoderInner, ComputePassEncoderInner, DeviceInner, RenderPassEncoderInner,
     TextureViewInner,
@@ -101,15 +102,32 @@ impl CommandEncoderState {
 }
 
 impl CommandEncoderInner {
-    pub fn new(device: Arc<DeviceInner>) -> Result<CommandEncoderInner, Error> {
+    pub fn new(device: Arc<DeviceInner>, descriptor: CommandEncoderDescriptor) -> Result<CommandEncoderInner, Error> {
         let state = CommandEncoderState::new();
-        Ok(CommandEncoderInner { device, state })
+        let label = descriptor.label.map(String::from);
+        Ok(CommandEncoderInner {
+            device,
+            state,
+            label,
+            error: None,
+        })
     }
 
-    fn push(&mut self, command: Command) {
+    pub fn push(&mut self, command: Command) {
         self.state.push(command)
     }
 
+    /// Commands that fail validation are not recorded, and the first error is returned by `finish`
+    fn validate(&mut self, result: Result<(), Error>) -> bool {
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                self.error.get_or_insert(e);
+                false
+            }
+        }
+    }
+
     fn set_push_constants<T: Copy>(
         &mut self,
         stages: ShaderStageFlags,
@@ -221,6 +239,15 @@ impl Into<CommandEncoder> for CommandEncoderInner {
 }
 
 impl CommandEncoder {
+    /// Sets the debug label that encloses the recorded commands when submitted.
+    pub fn set_label(&mut self, label: &str) {
+        self.inner.label = Some(label.to_owned());
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.inner.label.as_deref()
+    }
+
     pub fn begin_render_pass<'a>(&'a mut self, descriptor: RenderPassDescriptor) -> RenderPassEncoder<'a> {
         RenderPassEncoder::begin_render_pass(&mut self.inner, descriptor)
     }
@@ -260,20 +287,27 @@ impl CommandEncoder {
         top_level_buffers.insert(dst.inner.clone());
     }
 
-    // TODO: row_pitch bytes vs texels
     pub fn copy_buffer_to_texture(&mut self, src: BufferCopyView, dst: TextureCopyView, copy_size: Extent3D) {
+        let format = dst.texture.inner.descriptor.format;
+        let valid = texture::validate_texture_copy(&dst, copy_size)
+            .and_then(|_| texture::validate_buffer_copy(&src, &dst, copy_size));
+        if !self.inner.validate(valid) {
+            return;
+        }
+
         self.inner.push(Command::CopyBufferToTexture {
             src: BufferCopy {
                 buffer: Arc::clone(&src.buffer.inner),
-                row_length: src.row_length,
-                image_height: src.image_height,
+                row_length: texture::buffer_row_length(format, src.bytes_per_row),
+                image_height: src.rows_per_image,
                 offset: src.offset,
             },
             dst: TextureCopy {
                 texture: Arc::clone(&dst.texture.inner),
                 mip_level: dst.mip_level,
                 origin_texels: dst.origin,
-                array_layer: dst.array_layer, // TODO: slice ?
+                array_layer: dst.array_layer,
+                array_layer_count: dst.array_layer_count,
             },
             size_texels: copy_size,
         });
@@ -285,20 +319,38 @@ impl CommandEncoder {
         top_level_textures.insert(dst.texture.inner.clone());
     }
 
-    // TODO: row_pitch bytes vs texels
     pub fn copy_texture_to_texture(&mut self, src: TextureCopyView, dst: TextureCopyView, copy_size: Extent3D) {
+        let valid = texture::validate_texture_copy(&src, copy_size)
+            .and_then(|_| texture::validate_texture_copy(&dst, copy_size))
+            .and_then(|_| {
+                if src.array_layer_count != dst.array_layer_count {
+                    log::error!(
+                        "texture copy source has {} layers and destination has {} layers",
+                        src.array_layer_count,
+                        dst.array_layer_count
+                    );
+                    return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+                }
+                Ok(())
+            });
+        if !self.inner.validate(valid) {
+            return;
+        }
+
         self.inner.push(Command::CopyTextureToTexture {
             src: TextureCopy {
                 texture: Arc::clone(&src.texture.inner),
                 mip_level: src.mip_level,
                 origin_texels: src.origin,
-                array_layer: src.array_layer, // TODO: slice ?
+                array_layer: src.array_layer,
+                array_layer_count: src.array_layer_count,
             },
             dst: TextureCopy {
                 texture: Arc::clone(&dst.texture.inner),
                 mip_level: dst.mip_level,
                 origin_texels: dst.origin,
-                array_layer: dst.array_layer, // TODO: slice ?
+                array_layer: dst.array_layer,
+                array_layer_count: dst.array_layer_count,
             },
             size_texels: copy_size,
         });
@@ -309,19 +361,26 @@ impl CommandEncoder {
         top_level_textures.insert(dst.texture.inner.clone());
     }
 
-    // TODO: row_pitch bytes vs texels
     pub fn copy_texture_to_buffer(&mut self, src: TextureCopyView, dst: BufferCopyView, copy_size: Extent3D) {
+        let format = src.texture.inner.descriptor.format;
+        let valid = texture::validate_texture_copy(&src, copy_size)
+            .and_then(|_| texture::validate_buffer_copy(&dst, &src, copy_size));
+        if !self.inner.validate(valid) {
+            return;
+        }
+
         self.inner.push(Command::CopyTextureToBuffer {
             src: TextureCopy {
                 texture: Arc::clone(&src.texture.inner),
                 mip_level: src.mip_level,
                 origin_texels: src.origin,
-                array_layer: src.array_layer, // TODO: slice ?
+                array_layer: src.array_layer,
+                array_layer_count: src.array_layer_count,
             },
             dst: BufferCopy {
                 buffer: Arc::clone(&dst.buffer.inner),
-                row_length: dst.row_length,
-                image_height: dst.image_height,
+                row_length: texture::buffer_row_length(format, dst.bytes_per_row),
+                image_height: dst.rows_per_image,
                 offset: dst.offset,
             },
             size_texels: copy_size,
@@ -357,6 +416,14 @@ impl CommandEncoder {
         top_level_textures.insert(dst.texture.inner.clone());
     }
 
+    /// Fills mip levels `base_mip_level + 1..base_mip_level + mip_level_count` of the layers in `range` by
+    /// downsampling `base_mip_level`. Formats that can not be blitted with linear filtering, like integer
+    /// formats, are downsampled with a render pipeline instead.
+    pub fn generate_mipmaps(&mut self, texture: &Texture, range: TextureSubresourceRange) {
+        let result = mipmap::generate_mipmaps(self, texture, range);
+        self.inner.validate(result);
+    }
+
     pub fn push_debug_group(&mut self, group_label: &str) {
         self.inner.push(Command::PushDebugGroup {
             group_label: group_label.into(),
@@ -374,11 +441,16 @@ impl CommandEncoder {
     }
 
     pub fn finish(self) -> Result<CommandBuffer, Error> {
-        // TODO: Validation?
+        if let Some(error) = self.inner.error {
+            return Err(error);
+        }
         let command_buffer = CommandBufferInner {
             state: self.inner.state.into(),
             device: self.inner.device,
+            label: self.inner.label,
         };
+        // e.g. render passes can not be recorded on a compute-only device
+        command_buffer.validate_queue(&command_buffer.device.queue)?;
         Ok(CommandBuffer { inner: command_buffer })
     }
 }
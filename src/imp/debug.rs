@@ -1,48 +1,282 @@
 use std::collections::HashMap;
-use std::ffi::{c_void, CStr};
+use std::ffi::{c_void, CStr, CString};
 use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
 use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 use ash::version::InstanceV1_0;
-use ash::vk;
+use ash::vk::{self, Handle};
 
 use parking_lot::Mutex;
 use std::fmt::{Debug, Display};
 
-use crate::Instance;
+use crate::imp::{DebugMessengerInner, DeviceInner, InstanceInner};
+use crate::{
+    BufferDescriptor, ComputePipelineDescriptor, DebugMessage, DebugMessageObject, DebugMessageSeverity,
+    DebugMessageTypeFlags, DebugMessenger, DebugMessengerDescriptor, Error, Instance, RenderPipelineDescriptor,
+    SamplerDescriptor, TextureDescriptor, TextureViewDescriptor,
+};
 use std::sync::atomic::Ordering;
 
-#[allow(dead_code)]
-#[allow(unused_variables)]
+pub type DebugMessageCallback = Box<dyn Fn(&DebugMessage) + Send + Sync>;
+
 pub unsafe extern "system" fn debug_utils_messenger_callback(
     severity: vk::DebugUtilsMessageSeverityFlagsEXT,
     ty: vk::DebugUtilsMessageTypeFlagsEXT,
-    callback: *const vk::DebugUtilsMessengerCallbackDataEXT,
+    callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
     userdata: *mut c_void,
 ) -> u32 {
-    if callback.is_null() {
+    if callback_data.is_null() {
         log::warn!("debug message callback was null");
+        return vk::FALSE;
+    }
+
+    let message = debug_message(severity, ty, &*callback_data);
+
+    if userdata.is_null() {
+        let level = match message.severity {
+            DebugMessageSeverity::Verbose => log::Level::Trace,
+            DebugMessageSeverity::Info => log::Level::Info,
+            DebugMessageSeverity::Warning => log::Level::Warn,
+            DebugMessageSeverity::Error => log::Level::Error,
+        };
+        log::log!(level, "[{:?}] {}", ty, message.message);
     } else {
-        let callback = *callback;
+        let callback = &*(userdata as *const DebugMessageCallback);
+        // unwinding into the driver is undefined behavior
+        let result = panic::catch_unwind(AssertUnwindSafe(|| callback(&message)));
+        if result.is_err() {
+            log::error!("debug message callback panicked: {}", message.message);
+        }
+    }
+
+    vk::FALSE
+}
 
-        let message_id = callback.message_id_number;
-        let message_id_name = CStr::from_ptr(callback.p_message_id_name).to_string_lossy();
+unsafe fn string_from_ptr(ptr: *const libc::c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr).to_string_lossy().into_owned())
+    }
+}
 
-        let message = CStr::from_ptr(callback.p_message).to_string_lossy();
+unsafe fn labels(labels: *const vk::DebugUtilsLabelEXT, count: u32) -> Vec<String> {
+    if labels.is_null() {
+        return Vec::new();
+    }
+    slice::from_raw_parts(labels, count as usize)
+        .iter()
+        .filter_map(|label| string_from_ptr(label.p_label_name))
+        .collect()
+}
 
-        let level = match severity {
-            vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE => log::Level::Trace,
-            vk::DebugUtilsMessageSeverityFlagsEXT::WARNING => log::Level::Warn,
-            vk::DebugUtilsMessageSeverityFlagsEXT::INFO => log::Level::Info,
-            vk::DebugUtilsMessageSeverityFlagsEXT::ERROR => log::Level::Error,
-            _ => log::Level::Debug,
+unsafe fn debug_message(
+    severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    ty: vk::DebugUtilsMessageTypeFlagsEXT,
+    data: &vk::DebugUtilsMessengerCallbackDataEXT,
+) -> DebugMessage {
+    let severity = if severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR) {
+        DebugMessageSeverity::Error
+    } else if severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::WARNING) {
+        DebugMessageSeverity::Warning
+    } else if severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::INFO) {
+        DebugMessageSeverity::Info
+    } else {
+        DebugMessageSeverity::Verbose
+    };
+
+    let objects = if data.p_objects.is_null() {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(data.p_objects, data.object_count as usize)
+            .iter()
+            .map(|object| DebugMessageObject {
+                object_type: object.object_type,
+                handle: object.object_handle,
+                name: string_from_ptr(object.p_object_name),
+            })
+            .collect()
+    };
+
+    DebugMessage {
+        severity,
+        message_type: DebugMessageTypeFlags::from_bits_truncate(ty.as_raw()),
+        message_id_name: string_from_ptr(data.p_message_id_name),
+        message_id_number: data.message_id_number,
+        message: string_from_ptr(data.p_message).unwrap_or_default(),
+        queue_labels: labels(data.p_queue_labels, data.queue_label_count),
+        command_buffer_labels: labels(data.p_cmd_buf_labels, data.cmd_buf_label_count),
+        objects,
+    }
+}
+
+fn message_severity(min_severity: DebugMessageSeverity) -> vk::DebugUtilsMessageSeverityFlagsEXT {
+    let mut flags = vk::DebugUtilsMessageSeverityFlagsEXT::ERROR;
+    if min_severity <= DebugMessageSeverity::Warning {
+        flags |= vk::DebugUtilsMessageSeverityFlagsEXT::WARNING;
+    }
+    if min_severity <= DebugMessageSeverity::Info {
+        flags |= vk::DebugUtilsMessageSeverityFlagsEXT::INFO;
+    }
+    if min_severity <= DebugMessageSeverity::Verbose {
+        flags |= vk::DebugUtilsMessageSeverityFlagsEXT::VERBOSE;
+    }
+    flags
+}
+
+impl DebugMessengerInner {
+    pub fn new(
+        instance: Arc<InstanceInner>,
+        descriptor: DebugMessengerDescriptor,
+        callback: DebugMessageCallback,
+    ) -> Result<DebugMessengerInner, Error> {
+        if !instance.has_extension("VK_EXT_debug_utils") {
+            log::error!("debug messenger requires VK_EXT_debug_utils");
+            return Err(Error::from(vk::Result::ERROR_EXTENSION_NOT_PRESENT));
+        }
+
+        let callback = Box::new(callback);
+        let userdata = &*callback as *const DebugMessageCallback as *mut c_void;
+
+        let create_info = vk::DebugUtilsMessengerCreateInfoEXT::builder()
+            .message_severity(message_severity(descriptor.min_severity))
+            .message_type(vk::DebugUtilsMessageTypeFlagsEXT::from_raw(
+                descriptor.message_type.bits(),
+            ))
+            .pfn_user_callback(Some(debug_utils_messenger_callback))
+            .user_data(userdata);
+
+        let handle = unsafe {
+            instance
+                .raw_ext
+                .debug_utils
+                .create_debug_utils_messenger(&create_info, None)?
         };
 
-        log::log!(level, "[{:?}] {}", ty, message);
+        Ok(DebugMessengerInner {
+            handle,
+            instance,
+            callback,
+        })
+    }
+}
+
+impl Into<DebugMessenger> for DebugMessengerInner {
+    fn into(self) -> DebugMessenger {
+        DebugMessenger { inner: Arc::new(self) }
+    }
+}
+
+impl Debug for DebugMessengerInner {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "DebugMessengerInner {{ handle: {:?} }}", self.handle)
+    }
+}
+
+impl Drop for DebugMessengerInner {
+    fn drop(&mut self) {
+        unsafe {
+            self.instance
+                .raw_ext
+                .debug_utils
+                .destroy_debug_utils_messenger(self.handle, None);
+        }
+    }
+}
+
+/// The debug name of an object. The name is forwarded to `vkSetDebugUtilsObjectNameEXT`
+/// when the extension is available.
+#[derive(Default)]
+pub struct Label(Mutex<Option<String>>);
+
+impl Label {
+    pub fn new<H: Handle>(device: &DeviceInner, handle: H, label: Option<&str>) -> Label {
+        let this = Label(Mutex::new(None));
+        if let Some(label) = label {
+            this.set(device, handle, label);
+        }
+        this
+    }
+
+    pub fn set<H: Handle>(&self, device: &DeviceInner, handle: H, label: &str) {
+        set_object_name(device, handle, label);
+        *self.0.lock() = Some(label.to_owned());
     }
 
-    unimplemented!()
-    //vk::FALSE
+    pub fn get(&self) -> Option<String> {
+        self.0.lock().clone()
+    }
+}
+
+impl Debug for Label {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &*self.0.lock() {
+            Some(label) => write!(f, "{:?}", label),
+            None => write!(f, "None"),
+        }
+    }
+}
+
+/// Objects keep their label in a `Label` and store their descriptor without it, so that the
+/// stored descriptor does not borrow the caller's label.
+pub trait WithoutLabel {
+    type Unlabeled;
+
+    fn without_label(self) -> Self::Unlabeled;
+}
+
+macro_rules! without_label {
+    ($($Descriptor:ident),*) => {
+        $(
+            impl<'a> WithoutLabel for $Descriptor<'a> {
+                type Unlabeled = $Descriptor<'static>;
+
+                fn without_label(self) -> $Descriptor<'static> {
+                    $Descriptor { label: None, ..self }
+                }
+            }
+        )*
+    };
+}
+
+without_label!(
+    TextureDescriptor,
+    TextureViewDescriptor,
+    BufferDescriptor,
+    SamplerDescriptor,
+    ComputePipelineDescriptor,
+    RenderPipelineDescriptor
+);
+
+pub fn set_object_name<H: Handle>(device: &DeviceInner, handle: H, name: &str) {
+    if !device.adapter.instance.has_extension("VK_EXT_debug_utils") {
+        return;
+    }
+    let name = match CString::new(name) {
+        Ok(name) => name,
+        Err(e) => {
+            log::warn!("invalid object name: {:?}", e);
+            return;
+        }
+    };
+    let name_info = vk::DebugUtilsObjectNameInfoEXT::builder()
+        .object_type(H::TYPE)
+        .object_handle(handle.as_raw())
+        .object_name(&name);
+    unsafe {
+        let result = device
+            .adapter
+            .instance
+            .raw_ext
+            .debug_utils
+            .debug_utils_set_object_name(device.raw.handle(), &name_info);
+        if let Err(e) = result {
+            log::warn!("failed to set object name: {:?}", e);
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -136,6 +370,12 @@ lazy_static::lazy_static! {
     pub static ref TEST_VALIDATION_HOOK: AtomicBool = AtomicBool::new(false);
 }
 
+/// Instance handles can be reused, so errors of instances that were not checked by `validate`
+/// must not be attributed to a later instance
+pub fn forget_validation_errors(instance: vk::Instance) {
+    ERRORS.lock().remove(&instance);
+}
+
 pub fn validate<F>(f: F)
 where
     F: FnOnce() -> Result<Instance, Box<dyn std::error::Error>>,
@@ -2,38 +2,46 @@ use ash::extensions::khr;
 use ash::version::{DeviceV1_0, InstanceV1_0};
 use ash::vk;
 use parking_lot::Mutex;
+use smallvec::SmallVec;
 use vk_mem::{Allocator, AllocatorCreateInfo};
 
 use crate::error::Error;
 
 use crate::imp::fenced_deleter::{DeleteWhenUnused, FencedDeleter};
+use crate::imp::memory::MemoryUsageTracker;
+use crate::imp::mipmap::DownsamplePipelineCache;
+use crate::imp::pass_resource_usage::QueueResource;
 use crate::imp::render_pass::{RenderPassCache, RenderPassCacheQuery};
 use crate::imp::serial::{Serial, SerialQueue};
 use crate::imp::{swapchain, texture};
 
 use crate::imp::{
     AdapterInner, BindGroupInner, BindGroupLayoutInner, BufferInner, CommandEncoderInner, ComputePipelineInner,
-    DeviceExt, DeviceInner, PipelineLayoutInner, QueueInfo, QueueInner, RenderPipelineInner, SamplerInner,
-    ShaderModuleInner, SurfaceInner, SwapchainInner, TextureInner,
+    DeviceExt, DeviceInner, PipelineLayoutInner, QueueInfo, QueueInner, ReloadableComputePipelineInner,
+    ReloadableRenderPipelineInner, RenderPipelineInner, SamplerInner, ShaderModuleInner, SurfaceInner, SwapchainInner,
+    TextureInner,
 };
 
 use crate::{
     Adapter, BindGroup, BindGroupDescriptor, BindGroupLayout, BindGroupLayoutDescriptor, Buffer, BufferDescriptor,
-    CommandEncoder, ComputePipeline, ComputePipelineDescriptor, Device, DeviceDescriptor, Limits, MappedBuffer,
-    PipelineLayout, PipelineLayoutDescriptor, Queue, RenderPipeline, RenderPipelineDescriptor, Sampler,
-    SamplerDescriptor, ShaderModule, ShaderModuleDescriptor, Surface, Swapchain, SwapchainDescriptor, Texture,
+    CommandEncoder, CommandEncoderDescriptor, ComputePipeline, ComputePipelineDescriptor, Device, DeviceDescriptor,
+    Extensions, Limits, Maintain, MappedBuffer, MemoryReport, PipelineLayout, PipelineLayoutDescriptor, Queue,
+    QueueType, ReloadableComputePipeline, ReloadableComputePipelineDescriptor, ReloadableRenderPipeline,
+    ReloadableRenderPipelineDescriptor, RenderPipeline, RenderPipelineDescriptor, Sampler, SamplerDescriptor,
+    ShaderModule, ShaderModuleDescriptor, ShaderStageFlags, Surface, Swapchain, SwapchainDescriptor, Texture,
     TextureDescriptor, TextureFormat,
 };
 
 use std::fmt::{self, Debug};
 use std::mem::ManuallyDrop;
+use std::path::Path;
 use std::sync::Arc;
 
 pub struct DeviceState {
-    // the fences in flight for our single queue
+    // the fences in flight for all queues, serials are shared between queues
     fences_in_flight: SerialQueue<vk::Fence>,
 
-    // commands in flight for our single queue
+    // commands in flight for all queues
     commands_in_flight: SerialQueue<CommandPoolAndBuffer>,
 
     wait_semaphores: Vec<vk::Semaphore>,
@@ -47,18 +55,53 @@ pub struct DeviceState {
 
     fenced_deleter: FencedDeleter,
 
+    // callbacks registered with `Queue::on_submitted_work_done`
+    work_done_callbacks: SerialQueue<WorkDoneCallback>,
+    // callbacks whose work has completed, to be called once the state is unlocked
+    completed_callbacks: Vec<WorkDoneCallback>,
+
     allocator: ManuallyDrop<Allocator>,
+    memory_usage: MemoryUsageTracker,
 
     renderpass_cache: RenderPassCache,
+    downsample_pipeline_cache: DownsamplePipelineCache,
 }
 
+pub type WorkDoneCallback = Box<dyn FnOnce() + Send>;
+
 #[derive(Copy, Clone, Debug, Default)]
 struct CommandPoolAndBuffer {
     pool: vk::CommandPool,
     command_buffer: vk::CommandBuffer,
+    queue_family_index: u32,
 }
 
 impl Device {
+    pub fn limits(&self) -> Limits {
+        self.inner.limits
+    }
+
+    /// The features that were enabled when the device was created
+    pub fn extensions(&self) -> &Extensions {
+        &self.inner.extensions
+    }
+
+    /// Returns the contents of the device's pipeline cache. The data can be saved and passed to
+    /// `DeviceDescriptor::pipeline_cache_data` to speed up pipeline creation on the next run.
+    pub fn pipeline_cache_data(&self) -> Result<Vec<u8>, Error> {
+        let data = unsafe { self.inner.raw.get_pipeline_cache_data(self.inner.pipeline_cache)? };
+        Ok(data)
+    }
+
+    /// Returns the memory usage of the device, per heap and per kind of resource.
+    ///
+    /// Resources that were dropped but are still in use by the GPU are not included in the breakdown
+    /// by resource kind, but their memory is still counted in the heap statistics.
+    pub fn memory_report(&self) -> Result<MemoryReport, Error> {
+        let state = self.inner.state.lock();
+        state.memory_usage.report(&self.inner.adapter, &state.allocator)
+    }
+
     pub fn create_swapchain(
         &self,
         descriptor: SwapchainDescriptor,
@@ -71,6 +114,21 @@ impl Device {
         Ok(swapchain.into())
     }
 
+    /// Reclaims the resources of completed work and calls the callbacks registered with
+    /// `Queue::on_submitted_work_done` for it. Pending commands are submitted first.
+    ///
+    /// Returns the serial of the last completed submission.
+    pub fn poll(&self, maintain: Maintain) -> Result<u64, Error> {
+        if maintain == Maintain::Wait {
+            let mut state = self.inner.state.lock();
+            state.submit_pending_commands(&self.inner)?;
+            state.wait_for_fences_in_flight(&self.inner)?;
+        }
+        self.inner.tick()?;
+        let state = self.inner.state.lock();
+        Ok(state.get_last_completed_serial().get())
+    }
+
     pub fn get_supported_swapchain_formats(&self, surface: &Surface) -> Result<Vec<TextureFormat>, Error> {
         let physical_device = self.inner.adapter.physical_device;
         let formats = surface
@@ -79,17 +137,20 @@ impl Device {
             .iter()
             .cloned()
             .filter(|format| format.color_space == swapchain::COLOR_SPACE)
-            .map(|format| texture::texture_format(format.format))
+            .filter_map(|format| texture::texture_format(format.format))
             .collect();
 
         Ok(formats)
     }
 
-    pub fn get_queue(&self) -> Queue {
+    /// Returns a queue of the requested type. Compute and transfer queues come from dedicated queue
+    /// families when they were requested with `DeviceDescriptor::extra_queues` and the adapter
+    /// supports them, otherwise the device's main queue is returned.
+    pub fn get_queue(&self, queue_type: QueueType) -> Queue {
         Queue {
             inner: QueueInner {
                 device: Arc::clone(&self.inner),
-                queue: self.inner.queue,
+                queue: self.inner.get_queue_info(queue_type),
             },
         }
     }
@@ -139,6 +200,35 @@ impl Device {
         Ok(shader_module.into())
     }
 
+    /// Compiles GLSL or WGSL source to SPIR-V and creates a shader module from it. Compile errors are
+    /// reported with the line and column of the source.
+    #[cfg(feature = "naga")]
+    pub fn create_shader_module_from_source(
+        &self,
+        descriptor: crate::ShaderSourceDescriptor,
+    ) -> Result<ShaderModule, Error> {
+        let code = crate::imp::shader_compiler::compile(&descriptor)?;
+        let shader_module = ShaderModuleInner::new(
+            self.inner.clone(),
+            ShaderModuleDescriptor {
+                code: &code,
+                label: descriptor.label,
+            },
+        )?;
+        Ok(shader_module.into())
+    }
+
+    /// Loads a `.spv` file, or compiles a GLSL or `.wgsl` file with the `naga` feature. `stage` is
+    /// only used by GLSL sources.
+    pub fn create_shader_module_from_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        stage: ShaderStageFlags,
+    ) -> Result<ShaderModule, Error> {
+        let shader_module = ShaderModuleInner::from_file(self.inner.clone(), path.as_ref(), stage)?;
+        Ok(shader_module.into())
+    }
+
     pub fn create_pipeline_layout(&self, descriptor: PipelineLayoutDescriptor) -> Result<PipelineLayout, Error> {
         let pipeline_layout = PipelineLayoutInner::new(self.inner.clone(), descriptor)?;
         Ok(pipeline_layout.into())
@@ -154,49 +244,153 @@ impl Device {
         Ok(render_pipeline.into())
     }
 
+    pub fn create_reloadable_compute_pipeline(
+        &self,
+        descriptor: ReloadableComputePipelineDescriptor,
+    ) -> Result<ReloadableComputePipeline, Error> {
+        let compute_pipeline = ReloadableComputePipelineInner::new(self.inner.clone(), descriptor)?;
+        Ok(compute_pipeline.into())
+    }
+
+    pub fn create_reloadable_render_pipeline(
+        &self,
+        descriptor: ReloadableRenderPipelineDescriptor,
+    ) -> Result<ReloadableRenderPipeline, Error> {
+        let render_pipeline = ReloadableRenderPipelineInner::new(self.inner.clone(), descriptor)?;
+        Ok(render_pipeline.into())
+    }
+
     pub fn create_command_encoder(&self) -> Result<CommandEncoder, Error> {
-        let command_encoder = CommandEncoderInner::new(self.inner.clone())?;
+        self.create_command_encoder_with_descriptor(CommandEncoderDescriptor::default())
+    }
+
+    pub fn create_command_encoder_with_descriptor(
+        &self,
+        descriptor: CommandEncoderDescriptor,
+    ) -> Result<CommandEncoder, Error> {
+        let command_encoder = CommandEncoderInner::new(self.inner.clone(), descriptor)?;
         Ok(command_encoder.into())
     }
 }
 
 impl DeviceInner {
     pub fn new(adapter: Arc<AdapterInner>, descriptor: DeviceDescriptor) -> Result<DeviceInner, Error> {
-        let extension_names = if descriptor.surface_support.is_some() {
+        let limits = match descriptor.required_limits {
+            Some(required_limits) => {
+                validate_required_limits(&required_limits, &adapter.limits)?;
+                required_limits
+            }
+            None => adapter.limits,
+        };
+
+        validate_extensions(&descriptor.extensions, &adapter.extensions)?;
+        let enabled_features = enabled_features(&descriptor.extensions);
+
+        let mut extension_names = if descriptor.surface_support.is_some() {
             vec![c_str!("VK_KHR_swapchain")]
         } else {
             vec![]
         };
+        if adapter.supports_memory_budget() {
+            extension_names.push(c_str!("VK_EXT_memory_budget"));
+        }
+        if descriptor.extensions.conservative_rasterization {
+            extension_names.push(c_str!("VK_EXT_conservative_rasterization"));
+        }
 
         let surface = descriptor.surface_support.map(|v| v.inner.as_ref());
-        let queue_flags = vk::QueueFlags::COMPUTE | vk::QueueFlags::GRAPHICS | vk::QueueFlags::TRANSFER;
+        let queue_flags = match descriptor.queue_type {
+            QueueType::Graphics => vk::QueueFlags::COMPUTE | vk::QueueFlags::GRAPHICS | vk::QueueFlags::TRANSFER,
+            QueueType::Compute => vk::QueueFlags::COMPUTE | vk::QueueFlags::TRANSFER,
+            QueueType::Transfer => vk::QueueFlags::TRANSFER,
+        };
         let queue_family_index = select_queue_family_index(&adapter, queue_flags, surface)?;
 
+        let mut compute_queue_family_index = None;
+        let mut transfer_queue_family_index = None;
+        for queue_type in descriptor.extra_queues.iter() {
+            match queue_type {
+                QueueType::Graphics => {}
+                QueueType::Compute => {
+                    compute_queue_family_index = select_dedicated_queue_family_index(
+                        &adapter,
+                        vk::QueueFlags::COMPUTE,
+                        vk::QueueFlags::GRAPHICS,
+                    );
+                }
+                QueueType::Transfer => {
+                    transfer_queue_family_index = select_dedicated_queue_family_index(
+                        &adapter,
+                        vk::QueueFlags::TRANSFER,
+                        vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE,
+                    );
+                }
+            }
+            log::debug!(
+                "extra queue: {:?}, compute family: {:?}, transfer family: {:?}",
+                queue_type,
+                compute_queue_family_index,
+                transfer_queue_family_index
+            );
+        }
+
+        // on devices without graphics the main queue may already be from the dedicated family
+        let compute_queue_family_index = compute_queue_family_index.filter(|index| *index != queue_family_index);
+        let transfer_queue_family_index = transfer_queue_family_index.filter(|index| *index != queue_family_index);
+
         unsafe {
             assert!(adapter.queue_family_properties[queue_family_index as usize].queue_count > 0);
             let queue_priorities = [1.0];
-            let queue_create_infos = [vk::DeviceQueueCreateInfo::builder()
-                .queue_family_index(queue_family_index)
-                .queue_priorities(&queue_priorities)
-                .build()];
+            let queue_create_infos: Vec<_> = Some(queue_family_index)
+                .into_iter()
+                .chain(compute_queue_family_index)
+                .chain(transfer_queue_family_index)
+                .map(|queue_family_index| {
+                    vk::DeviceQueueCreateInfo::builder()
+                        .queue_family_index(queue_family_index)
+                        .queue_priorities(&queue_priorities)
+                        .build()
+                })
+                .collect();
 
             let create_info = vk::DeviceCreateInfo::builder()
                 .queue_create_infos(&queue_create_infos)
-                .enabled_extension_names(&extension_names);
+                .enabled_extension_names(&extension_names)
+                .enabled_features(&enabled_features);
 
             let raw = adapter
                 .instance
                 .raw
                 .create_device(adapter.physical_device, &create_info, None)?;
 
-            let limits = Limits { max_bind_groups: 0 };
-            let extensions = descriptor.extensions.clone();
+            let extensions = descriptor.extensions;
 
-            let queue_index = 0;
-            let queue = QueueInfo {
-                handle: raw.get_device_queue(queue_family_index, queue_index),
-                queue_index,
-                queue_family_index,
+            let queue_info = |queue_family_index: u32, queue_flags: vk::QueueFlags, queue_type: QueueType| {
+                let queue_index = 0;
+                QueueInfo {
+                    handle: raw.get_device_queue(queue_family_index, queue_index),
+                    queue_index,
+                    queue_family_index,
+                    queue_flags,
+                    queue_type,
+                }
+            };
+
+            let family_queue_flags = |index: u32| adapter.queue_family_properties[index as usize].queue_flags;
+
+            // the main queue is limited to what was requested, even if the family supports more
+            let queue = queue_info(queue_family_index, queue_flags, descriptor.queue_type);
+            let compute_queue = compute_queue_family_index
+                .map(|index| queue_info(index, family_queue_flags(index), QueueType::Compute));
+            let transfer_queue = transfer_queue_family_index
+                .map(|index| queue_info(index, family_queue_flags(index), QueueType::Transfer));
+
+            let pipeline_cache = match create_pipeline_cache(&raw, &adapter, descriptor.pipeline_cache_data) {
+                Ok(pipeline_cache) => pipeline_cache,
+                Err(e) => {
+                    raw.destroy_device(None);
+                    return Err(e);
+                }
             };
 
             let swapchain = khr::Swapchain::new(&adapter.instance.raw, &raw);
@@ -222,8 +416,12 @@ impl DeviceInner {
                 pending_commands: None,
                 unused_commands: Vec::new(),
                 fenced_deleter: FencedDeleter::default(),
+                work_done_callbacks: SerialQueue::default(),
+                completed_callbacks: Vec::new(),
                 renderpass_cache: RenderPassCache::default(),
+                downsample_pipeline_cache: DownsamplePipelineCache::default(),
                 allocator: ManuallyDrop::new(allocator),
+                memory_usage: MemoryUsageTracker::default(),
             };
 
             let state = Mutex::new(state);
@@ -235,6 +433,9 @@ impl DeviceInner {
                 limits,
                 adapter,
                 queue,
+                compute_queue,
+                transfer_queue,
+                pipeline_cache,
                 state,
             };
 
@@ -243,10 +444,41 @@ impl DeviceInner {
     }
 
     pub fn tick(&self) -> Result<(), Error> {
-        let mut state = self.state.lock();
-        state.tick(self)?;
+        let completed_callbacks = {
+            let mut state = self.state.lock();
+            state.tick(self)?;
+            std::mem::take(&mut state.completed_callbacks)
+        };
+        // called without holding the lock, the callbacks may use the device
+        for callback in completed_callbacks {
+            callback();
+        }
         Ok(())
     }
+
+    pub fn get_queue_info(&self, queue_type: QueueType) -> QueueInfo {
+        let queue = match queue_type {
+            QueueType::Graphics => None,
+            QueueType::Compute => self.compute_queue,
+            QueueType::Transfer => self.transfer_queue,
+        };
+        queue.unwrap_or(self.queue)
+    }
+
+    /// Returns the capabilities of the given queue family
+    pub fn queue_flags(&self, queue_family_index: u32) -> vk::QueueFlags {
+        self.adapter.queue_family_properties[queue_family_index as usize].queue_flags
+    }
+
+    /// Returns the queue created for the given queue family
+    pub fn get_queue_info_for_family(&self, queue_family_index: u32) -> QueueInfo {
+        Some(self.queue)
+            .into_iter()
+            .chain(self.compute_queue)
+            .chain(self.transfer_queue)
+            .find(|queue| queue.queue_family_index == queue_family_index)
+            .unwrap_or(self.queue)
+    }
 }
 
 impl Debug for DeviceInner {
@@ -334,6 +566,9 @@ impl Drop for DeviceInner {
             }
 
             state.renderpass_cache.drain(&self);
+            state.downsample_pipeline_cache.drain(self);
+
+            self.raw.destroy_pipeline_cache(self.pipeline_cache, None);
 
             ManuallyDrop::drop(&mut state.allocator);
 
@@ -358,11 +593,13 @@ impl DeviceState {
         }
         self.check_passed_fences(device)?;
         self.recycle_completed_commands(device)?;
+        for (callback, _) in self.work_done_callbacks.drain_up_to(self.last_completed_serial) {
+            self.completed_callbacks.push(callback);
+        }
         // TODO: maprequest/uploader/allocator ticks
         self.fenced_deleter
             .tick(self.last_completed_serial, device, &mut self.allocator);
-        let queue = &device.queue;
-        self.submit_pending_commands(device, &queue)?;
+        self.submit_pending_commands(device)?;
 
         Ok(())
     }
@@ -422,18 +659,25 @@ impl DeviceState {
         Ok(())
     }
 
-    pub fn get_pending_command_buffer(&mut self, device: &DeviceInner) -> Result<vk::CommandBuffer, Error> {
-        if self.pending_commands.is_none() {
-            let pending_commands = self.get_unused_commands(device)?;
-            let begin_info = vk::CommandBufferBeginInfo {
-                flags: vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT,
-                ..Default::default()
-            };
+    fn wait_for_fences_in_flight(&mut self, device: &DeviceInner) -> Result<(), Error> {
+        let fences: Vec<vk::Fence> = self.fences_in_flight.iter().map(|(fence, _)| *fence).collect();
+        if !fences.is_empty() {
             unsafe {
-                device
-                    .raw
-                    .begin_command_buffer(pending_commands.command_buffer, &begin_info)?;
+                device.raw.wait_for_fences(&fences, true, u64::MAX)?;
             }
+        }
+        Ok(())
+    }
+
+    /// Registers a callback that is called once all work submitted so far has completed
+    pub fn add_work_done_callback(&mut self, callback: WorkDoneCallback) {
+        let serial = self.last_submitted_serial;
+        self.work_done_callbacks.enqueue(callback, serial);
+    }
+
+    pub fn get_pending_command_buffer(&mut self, device: &DeviceInner) -> Result<vk::CommandBuffer, Error> {
+        if self.pending_commands.is_none() {
+            let pending_commands = self.begin_commands(device, device.queue.queue_family_index)?;
             self.pending_commands = Some(pending_commands);
         }
 
@@ -446,7 +690,16 @@ impl DeviceState {
         &mut self.fenced_deleter
     }
 
-    pub fn submit_pending_commands(&mut self, device: &DeviceInner, queue: &QueueInfo) -> Result<(), Error> {
+    /// Submits the pending commands to the graphics queue
+    pub fn submit_pending_commands(&mut self, device: &DeviceInner) -> Result<(), Error> {
+        self.submit_pending_commands_with_signal(device, &[])
+    }
+
+    fn submit_pending_commands_with_signal(
+        &mut self,
+        device: &DeviceInner,
+        signal_semaphores: &[vk::Semaphore],
+    ) -> Result<(), Error> {
         let pending_commands = match self.pending_commands.take() {
             None => {
                 // If there are no pending commands and everything in flight has resolved,
@@ -465,31 +718,190 @@ impl DeviceState {
             Some(pending_commands) => pending_commands,
         };
 
+        let wait_semaphores = std::mem::take(&mut self.wait_semaphores);
+        let result = self.submit_commands(
+            device,
+            &device.queue,
+            pending_commands,
+            &wait_semaphores,
+            signal_semaphores,
+        );
+        self.wait_semaphores = wait_semaphores;
+        result?;
+
+        self.delete_when_unused_wait_semaphores();
+
+        Ok(())
+    }
+
+    fn begin_commands(&mut self, device: &DeviceInner, queue_family_index: u32) -> Result<CommandPoolAndBuffer, Error> {
+        let commands = self.get_unused_commands(device, queue_family_index)?;
+        let begin_info = vk::CommandBufferBeginInfo {
+            flags: vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+            ..Default::default()
+        };
+        unsafe {
+            device.raw.begin_command_buffer(commands.command_buffer, &begin_info)?;
+        }
+        Ok(commands)
+    }
+
+    fn submit_commands(
+        &mut self,
+        device: &DeviceInner,
+        queue: &QueueInfo,
+        commands: CommandPoolAndBuffer,
+        wait_semaphores: &[vk::Semaphore],
+        signal_semaphores: &[vk::Semaphore],
+    ) -> Result<(), Error> {
+        debug_assert_eq!(queue.queue_family_index, commands.queue_family_index);
+
         unsafe {
-            device.raw.end_command_buffer(pending_commands.command_buffer)?;
+            device.raw.end_command_buffer(commands.command_buffer)?;
         }
 
-        let wait_dst_stage_masks = vec![vk::PipelineStageFlags::ALL_COMMANDS; self.wait_semaphores.len()];
-        let pending_command_buffers = [pending_commands.command_buffer];
+        let wait_dst_stage_masks = vec![vk::PipelineStageFlags::ALL_COMMANDS; wait_semaphores.len()];
+        let command_buffers = [commands.command_buffer];
 
         let fence = self.get_unused_fence(device)?;
 
         let submit_info = vk::SubmitInfo::builder()
-            .wait_semaphores(&self.wait_semaphores)
+            .wait_semaphores(wait_semaphores)
             .wait_dst_stage_mask(&wait_dst_stage_masks)
-            .command_buffers(&pending_command_buffers);
+            .signal_semaphores(signal_semaphores)
+            .command_buffers(&command_buffers);
 
         let serial = self.last_submitted_serial.increment();
 
-        log::trace!("queue_submit: {:?}", self.last_submitted_serial);
+        log::trace!("queue_submit: {:?}, queue: {:?}", self.last_submitted_serial, queue);
         unsafe {
             device.raw.queue_submit(queue.handle, &[*submit_info], fence)?;
         }
 
         self.fences_in_flight.enqueue(fence, serial);
-        self.commands_in_flight.enqueue(pending_commands, serial);
+        self.commands_in_flight.enqueue(commands, serial);
 
-        self.delete_when_unused_wait_semaphores();
+        Ok(())
+    }
+
+    /// Records commands with `record` and submits them to `queue`. The pending commands are used
+    /// for the graphics queue, other queues get a command buffer of their own that is submitted
+    /// immediately.
+    pub fn submit_to_queue<F>(&mut self, device: &DeviceInner, queue: &QueueInfo, record: F) -> Result<(), Error>
+    where
+        F: FnOnce(vk::CommandBuffer, &mut DeviceState) -> Result<(), Error>,
+    {
+        if queue.queue_family_index == device.queue.queue_family_index {
+            let command_buffer = self.get_pending_command_buffer(device)?;
+            record(command_buffer, self)?;
+            return self.submit_pending_commands(device);
+        }
+
+        // flush the graphics work (and its wait semaphores) so that any semaphore added while
+        // recording is one that this submission has to wait on
+        self.submit_pending_commands(device)?;
+
+        let commands = self.begin_commands(device, queue.queue_family_index)?;
+        let result = record(commands.command_buffer, self);
+
+        let wait_semaphores = std::mem::take(&mut self.wait_semaphores);
+        let result = result.and_then(|_| self.submit_commands(device, queue, commands, &wait_semaphores, &[]));
+        if result.is_err() {
+            // the commands were never submitted and can be reused
+            unsafe {
+                device
+                    .raw
+                    .reset_command_pool(commands.pool, vk::CommandPoolResetFlags::empty())?;
+            }
+            self.unused_commands.push(commands);
+        }
+        let next_pending_serial = self.get_next_pending_serial();
+        for semaphore in wait_semaphores.iter().cloned() {
+            self.fenced_deleter.delete_when_unused(semaphore, next_pending_serial);
+        }
+        result
+    }
+
+    /// Moves the ownership of `resources` to the queue family of `queue`, recording the acquire
+    /// barriers into `command_buffer`.
+    ///
+    /// Resources are created with `VK_SHARING_MODE_EXCLUSIVE`. A resource that was last used on a
+    /// different queue family is released on the queue of that family, which signals a semaphore
+    /// that the destination queue waits on before the resource is acquired. Resources without
+    /// contents are taken over without a transfer.
+    pub fn transfer_ownership(
+        &mut self,
+        device: &DeviceInner,
+        resources: &[QueueResource],
+        queue: &QueueInfo,
+        command_buffer: vk::CommandBuffer,
+    ) -> Result<(), Error> {
+        let mut src_queue_family_indices = SmallVec::<[u32; 2]>::new();
+        for resource in resources.iter() {
+            let src_queue_family_index = resource.queue_family_index();
+            if src_queue_family_index == queue.queue_family_index {
+                continue;
+            }
+            if resource.is_unused() {
+                resource.set_queue_family_index(queue.queue_family_index);
+                continue;
+            }
+            if !src_queue_family_indices.contains(&src_queue_family_index) {
+                src_queue_family_indices.push(src_queue_family_index);
+            }
+        }
+
+        let mut semaphores = SmallVec::<[vk::Semaphore; 2]>::new();
+
+        for src_queue_family_index in src_queue_family_indices.iter().cloned() {
+            let src_queue = device.get_queue_info_for_family(src_queue_family_index);
+            let released = resources
+                .iter()
+                .filter(|resource| resource.queue_family_index() == src_queue_family_index);
+
+            let semaphore = unsafe {
+                let create_info = vk::SemaphoreCreateInfo::builder();
+                device.raw.create_semaphore(&create_info, None)?
+            };
+
+            if src_queue.queue_family_index == device.queue.queue_family_index {
+                let release_command_buffer = self.get_pending_command_buffer(device)?;
+                for resource in released {
+                    resource.transfer_queue_family(
+                        release_command_buffer,
+                        &src_queue,
+                        src_queue_family_index,
+                        queue.queue_family_index,
+                    );
+                }
+                self.submit_pending_commands_with_signal(device, &[semaphore])?;
+            } else {
+                let commands = self.begin_commands(device, src_queue_family_index)?;
+                for resource in released {
+                    resource.transfer_queue_family(
+                        commands.command_buffer,
+                        &src_queue,
+                        src_queue_family_index,
+                        queue.queue_family_index,
+                    );
+                }
+                self.submit_commands(device, &src_queue, commands, &[], &[semaphore])?;
+            }
+
+            semaphores.push(semaphore);
+
+            for resource in resources
+                .iter()
+                .filter(|resource| resource.queue_family_index() == src_queue_family_index)
+            {
+                resource.transfer_queue_family(command_buffer, queue, src_queue_family_index, queue.queue_family_index);
+                resource.set_queue_family_index(queue.queue_family_index);
+            }
+        }
+
+        for semaphore in semaphores.into_iter() {
+            self.add_wait_semaphore(semaphore);
+        }
 
         Ok(())
     }
@@ -507,19 +919,28 @@ impl DeviceState {
         self.wait_semaphores.clear();
     }
 
-    fn get_unused_commands(&mut self, device: &DeviceInner) -> Result<CommandPoolAndBuffer, Error> {
-        if !self.unused_commands.is_empty() {
-            return self.unused_commands.pop().ok_or_else(|| unreachable!());
+    fn get_unused_commands(
+        &mut self,
+        device: &DeviceInner,
+        queue_family_index: u32,
+    ) -> Result<CommandPoolAndBuffer, Error> {
+        let unused = self
+            .unused_commands
+            .iter()
+            .rposition(|commands| commands.queue_family_index == queue_family_index);
+        if let Some(index) = unused {
+            return Ok(self.unused_commands.swap_remove(index));
         }
 
         let mut commands = CommandPoolAndBuffer {
             pool: vk::CommandPool::null(),
             command_buffer: vk::CommandBuffer::null(),
+            queue_family_index,
         };
 
         let create_info = vk::CommandPoolCreateInfo {
             flags: vk::CommandPoolCreateFlags::TRANSIENT,
-            queue_family_index: device.queue.queue_family_index,
+            queue_family_index,
             ..Default::default()
         };
 
@@ -574,6 +995,10 @@ impl DeviceState {
         self.last_submitted_serial.next()
     }
 
+    pub fn memory_usage_mut(&mut self) -> &mut MemoryUsageTracker {
+        &mut self.memory_usage
+    }
+
     pub fn allocator_mut(&mut self) -> &mut Allocator {
         &mut self.allocator
     }
@@ -589,6 +1014,223 @@ impl DeviceState {
     ) -> Result<vk::RenderPass, Error> {
         self.renderpass_cache.get_render_pass(query, device)
     }
+
+    pub fn downsample_pipeline_cache_mut(&mut self) -> &mut DownsamplePipelineCache {
+        &mut self.downsample_pipeline_cache
+    }
+}
+
+// VkPipelineCacheHeaderVersionOne: length, version, vendor id, device id and pipeline cache uuid
+const PIPELINE_CACHE_HEADER_SIZE: usize = 16 + vk::UUID_SIZE;
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+/// Checks that the pipeline cache data was created by the same driver and device. Drivers are
+/// supposed to ignore incompatible data, but not all of them do.
+fn validate_pipeline_cache_data(data: &[u8], properties: &vk::PhysicalDeviceProperties) -> Result<(), String> {
+    if data.len() < PIPELINE_CACHE_HEADER_SIZE {
+        return Err(format!("data too small for the header: {} bytes", data.len()));
+    }
+    let header_length = read_u32(data, 0) as usize;
+    if header_length < PIPELINE_CACHE_HEADER_SIZE || header_length > data.len() {
+        return Err(format!("invalid header length: {}", header_length));
+    }
+    let header_version = read_u32(data, 4);
+    if header_version != vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32 {
+        return Err(format!("unsupported header version: {}", header_version));
+    }
+    let vendor_id = read_u32(data, 8);
+    let device_id = read_u32(data, 12);
+    if vendor_id != properties.vendor_id || device_id != properties.device_id {
+        return Err(format!(
+            "created for vendor_id: {:#x}, device_id: {:#x}; expected vendor_id: {:#x}, device_id: {:#x}",
+            vendor_id, device_id, properties.vendor_id, properties.device_id
+        ));
+    }
+    if data[16..PIPELINE_CACHE_HEADER_SIZE] != properties.pipeline_cache_uuid[..] {
+        return Err("pipeline cache uuid mismatch".to_owned());
+    }
+    Ok(())
+}
+
+/// Creates the pipeline cache, seeded with `data` if it is compatible with the device
+fn create_pipeline_cache(
+    raw: &ash::Device,
+    adapter: &AdapterInner,
+    data: Option<&[u8]>,
+) -> Result<vk::PipelineCache, Error> {
+    let data = data.and_then(
+        |data| match validate_pipeline_cache_data(data, &adapter.physical_device_properties) {
+            Ok(()) => Some(data),
+            Err(reason) => {
+                log::warn!("discarding pipeline cache data; {}", reason);
+                None
+            }
+        },
+    );
+
+    if let Some(data) = data {
+        let create_info = vk::PipelineCacheCreateInfo::builder().initial_data(data);
+        match unsafe { raw.create_pipeline_cache(&create_info, None) } {
+            Ok(pipeline_cache) => return Ok(pipeline_cache),
+            Err(e) => log::warn!("discarding pipeline cache data; create_pipeline_cache: {:?}", e),
+        }
+    }
+
+    let create_info = vk::PipelineCacheCreateInfo::builder();
+    let pipeline_cache = unsafe { raw.create_pipeline_cache(&create_info, None)? };
+    Ok(pipeline_cache)
+}
+
+/// Graphics and compute queue families support transfer operations even if they do not report
+/// `VK_QUEUE_TRANSFER_BIT`
+fn supports_queue_flags(family_queue_flags: vk::QueueFlags, queue_flags: vk::QueueFlags) -> bool {
+    let mut family_queue_flags = family_queue_flags;
+    if family_queue_flags.intersects(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE) {
+        family_queue_flags |= vk::QueueFlags::TRANSFER;
+    }
+    family_queue_flags.contains(queue_flags)
+}
+
+/// Selects a queue family that supports `queue_flags` but none of the `excluded_queue_flags`
+fn select_dedicated_queue_family_index(
+    adapter: &AdapterInner,
+    queue_flags: vk::QueueFlags,
+    excluded_queue_flags: vk::QueueFlags,
+) -> Option<u32> {
+    adapter
+        .queue_family_properties
+        .iter()
+        .position(|queue_family| {
+            queue_family.queue_flags.contains(queue_flags)
+                && !queue_family.queue_flags.intersects(excluded_queue_flags)
+                && queue_family.queue_count > 0
+        })
+        .map(|queue_family_index| queue_family_index as u32)
+}
+
+fn extension_list(extensions: &Extensions) -> [(&'static str, bool); 15] {
+    [
+        ("depth_clamp", extensions.depth_clamp),
+        ("fill_mode_non_solid", extensions.fill_mode_non_solid),
+        ("wide_lines", extensions.wide_lines),
+        ("multi_draw_indirect", extensions.multi_draw_indirect),
+        ("sampler_anisotropy", extensions.sampler_anisotropy),
+        ("texture_compression_bc", extensions.texture_compression_bc),
+        ("texture_compression_etc2", extensions.texture_compression_etc2),
+        ("texture_compression_astc", extensions.texture_compression_astc),
+        ("independent_blend", extensions.independent_blend),
+        ("shader_float64", extensions.shader_float64),
+        ("robust_buffer_access", extensions.robust_buffer_access),
+        ("depth_bounds", extensions.depth_bounds),
+        ("sample_rate_shading", extensions.sample_rate_shading),
+        ("texture_cube_array", extensions.texture_cube_array),
+        ("conservative_rasterization", extensions.conservative_rasterization),
+    ]
+}
+
+fn validate_extensions(requested: &Extensions, supported: &Extensions) -> Result<(), Error> {
+    let missing: Vec<&str> = extension_list(requested)
+        .iter()
+        .zip(extension_list(supported).iter())
+        .filter(|((_, requested), (_, supported))| *requested && !*supported)
+        .map(|((name, _), _)| *name)
+        .collect();
+    if !missing.is_empty() {
+        log::error!("requested extensions are not supported by the adapter: {:?}", missing);
+        return Err(Error::from(format!(
+            "Requested extensions are not supported: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+fn enabled_features(extensions: &Extensions) -> vk::PhysicalDeviceFeatures {
+    let flag = |enabled: bool| if enabled { vk::TRUE } else { vk::FALSE };
+    vk::PhysicalDeviceFeatures {
+        depth_clamp: flag(extensions.depth_clamp),
+        fill_mode_non_solid: flag(extensions.fill_mode_non_solid),
+        wide_lines: flag(extensions.wide_lines),
+        multi_draw_indirect: flag(extensions.multi_draw_indirect),
+        sampler_anisotropy: flag(extensions.sampler_anisotropy),
+        texture_compression_bc: flag(extensions.texture_compression_bc),
+        texture_compression_etc2: flag(extensions.texture_compression_etc2),
+        texture_compression_astc_ldr: flag(extensions.texture_compression_astc),
+        independent_blend: flag(extensions.independent_blend),
+        shader_float64: flag(extensions.shader_float64),
+        robust_buffer_access: flag(extensions.robust_buffer_access),
+        depth_bounds: flag(extensions.depth_bounds),
+        sample_rate_shading: flag(extensions.sample_rate_shading),
+        image_cube_array: flag(extensions.texture_cube_array),
+        ..Default::default()
+    }
+}
+
+fn validate_required_limits(required: &Limits, supported: &Limits) -> Result<(), Error> {
+    macro_rules! check_max {
+        ($($field:ident),*) => {
+            $(
+                if required.$field > supported.$field {
+                    log::error!(
+                        "required limit {} ({}) exceeds the adapter limit ({})",
+                        stringify!($field),
+                        required.$field,
+                        supported.$field
+                    );
+                    return Err(Error::from(format!(
+                        "Required limit is not supported: {} (required: {}, supported: {})",
+                        stringify!($field),
+                        required.$field,
+                        supported.$field
+                    )));
+                }
+            )*
+        };
+    }
+
+    macro_rules! check_alignment {
+        ($($field:ident),*) => {
+            $(
+                if !required.$field.is_power_of_two() || required.$field < supported.$field {
+                    log::error!(
+                        "required limit {} ({}) must be a power of two and at least the adapter limit ({})",
+                        stringify!($field),
+                        required.$field,
+                        supported.$field
+                    );
+                    return Err(Error::from(format!(
+                        "Required limit is not supported: {} (required: {}, supported: {})",
+                        stringify!($field),
+                        required.$field,
+                        supported.$field
+                    )));
+                }
+            )*
+        };
+    }
+
+    check_max!(
+        max_bind_groups,
+        max_bindings_per_bind_group,
+        max_dynamic_uniform_buffers_per_pipeline_layout,
+        max_dynamic_storage_buffers_per_pipeline_layout,
+        max_uniform_buffer_binding_size,
+        max_storage_buffer_binding_size,
+        max_texture_dimension_1d,
+        max_texture_dimension_2d,
+        max_texture_dimension_3d,
+        max_texture_array_layers,
+        max_push_constant_size
+    );
+
+    check_alignment!(min_uniform_buffer_offset_alignment, min_storage_buffer_offset_alignment);
+
+    Ok(())
 }
 
 /// Recipe: _Selecting a queue family that supports presentation to a given surface_ (page `81`)
@@ -606,7 +1248,7 @@ pub fn select_queue_family_index(
                 continue;
             }
         }
-        if queue_family.queue_flags.contains(queue_flags) && queue_family.queue_count > 0 {
+        if supports_queue_flags(queue_family.queue_flags, queue_flags) && queue_family.queue_count > 0 {
             return Ok(queue_family_index);
         }
     }
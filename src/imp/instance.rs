@@ -5,18 +5,23 @@ use ash::{self, vk};
 use lazy_static::lazy_static;
 use parking_lot::{RwLock, RwLockReadGuard};
 
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::fmt;
 use std::mem;
 use std::sync::Arc;
 
-use crate::imp::{debug, AdapterInner, InstanceExt, InstanceInner, SurfaceInner};
-use crate::{Adapter, AdapterOptions, Error, Instance, Surface, SurfaceDescriptor};
+use crate::imp::{debug, AdapterInner, DebugMessengerInner, InstanceExt, InstanceInner, SurfaceInner};
+use crate::{
+    Adapter, AdapterOptions, DebugMessage, DebugMessenger, DebugMessengerDescriptor, Error, Instance,
+    InstanceDescriptor, InstanceError, Surface, SurfaceDescriptor,
+};
 
 use raw_window_handle::HasRawWindowHandle;
 use std::fmt::Debug;
 use std::sync::atomic::Ordering;
 
+static VALIDATION_LAYERS: &[&str] = &["VK_LAYER_KHRONOS_validation", "VK_LAYER_LUNARG_standard_validation"];
+
 lazy_static! {
     static ref ENTRY: RwLock<Result<ash::Entry, Error>> = {
         unsafe {
@@ -31,8 +36,13 @@ lazy_static! {
 }
 
 impl Instance {
-    pub fn new() -> Result<Instance, Error> {
-        let inner = InstanceInner::new()?;
+    pub fn new() -> Result<Instance, InstanceError> {
+        let inner = InstanceInner::new(InstanceDescriptor::default())?;
+        Ok(inner.into())
+    }
+
+    pub fn with_descriptor(descriptor: InstanceDescriptor) -> Result<Instance, InstanceError> {
+        let inner = InstanceInner::new(descriptor)?;
         Ok(inner.into())
     }
 
@@ -41,11 +51,34 @@ impl Instance {
         Ok(adapter.into())
     }
 
+    /// Returns every adapter (physical device) available to the instance.
+    pub fn enumerate_adapters(&self) -> Result<Vec<Adapter>, Error> {
+        let adapters = AdapterInner::enumerate(self.inner.clone())?;
+        Ok(adapters.into_iter().map(Into::into).collect())
+    }
+
     pub fn create_surface(&self, descriptor: &SurfaceDescriptor) -> Result<Surface, Error> {
         let surface = SurfaceInner::new(self.inner.clone(), descriptor)?;
         Ok(surface.into())
     }
 
+    /// Registers a callback that receives `VK_EXT_debug_utils` messages, such as validation
+    /// layer output, for as long as the returned messenger is alive.
+    ///
+    /// The callback may be invoked from any thread. To receive messages on a channel, move a
+    /// `Mutex<Sender<DebugMessage>>` into the callback.
+    pub fn create_debug_messenger<F>(
+        &self,
+        descriptor: DebugMessengerDescriptor,
+        callback: F,
+    ) -> Result<DebugMessenger, Error>
+    where
+        F: Fn(&DebugMessage) + Send + Sync + 'static,
+    {
+        let messenger = DebugMessengerInner::new(self.inner.clone(), descriptor, Box::new(callback))?;
+        Ok(messenger.into())
+    }
+
     pub fn create_surface_raw<W: HasRawWindowHandle>(&self, window: &W) -> Result<Surface, Error> {
         let surface = SurfaceInner::from_raw_window_handle(self.inner.clone(), window.raw_window_handle())?;
         Ok(surface.into())
@@ -54,13 +87,27 @@ impl Instance {
 
 impl InstanceInner {
     #[rustfmt::skip]
-    fn new() -> Result<InstanceInner, Error> {
+    fn new(descriptor: InstanceDescriptor) -> Result<InstanceInner, InstanceError> {
         let init_debug_report = debug::TEST_VALIDATION_HOOK.load(Ordering::Acquire);
 
         unsafe {
             let entry_guard: RwLockReadGuard<Result<ash::Entry, Error>> = ENTRY.read();
             let entry: &ash::Entry = entry_guard.as_ref()?;
 
+            let (major, minor, patch) = descriptor.api_version;
+            let api_version = ash::vk_make_version!(major, minor, patch);
+
+            // Vulkan 1.0 loaders do not export vkEnumerateInstanceVersion
+            let instance_version = entry.try_enumerate_instance_version()?.unwrap_or(ash::vk_make_version!(1, 0, 0));
+            let available = (
+                ash::vk_version_major!(instance_version),
+                ash::vk_version_minor!(instance_version),
+                ash::vk_version_patch!(instance_version),
+            );
+            if (major, minor) > (available.0, available.1) {
+                return Err(InstanceError::UnsupportedApiVersion { requested: descriptor.api_version, available });
+            }
+
             let mut extension_names = vec![];
 
             let extension_properties = entry.enumerate_instance_extension_properties()?;
@@ -79,12 +126,25 @@ impl InstanceInner {
                 if name_cow == "VK_EXT_debug_utils" {
                     include_extension = true;
                 }
+                // Needed by VK_EXT_memory_budget on Vulkan 1.0 instances
+                if name_cow == "VK_KHR_get_physical_device_properties2" {
+                    include_extension = true;
+                }
+                if descriptor.extra_extensions.contains(&name_cow) {
+                    include_extension = true;
+                }
                 if include_extension {
                     log::debug!("requesting extension support: {}", name_cow);
                     extension_names.push(name.to_owned());
                 }
             }
 
+            for extra in descriptor.extra_extensions.iter() {
+                if !extension_names.iter().any(|name| name.to_string_lossy() == *extra) {
+                    return Err(InstanceError::MissingExtension(extra.to_string()));
+                }
+            }
+
             let instance_layer_properties = entry.enumerate_instance_layer_properties()?;
 
             for p in instance_layer_properties.iter() {
@@ -92,31 +152,52 @@ impl InstanceInner {
                 log::trace!("found instance layer: {}", name.to_string_lossy());
             }
 
-            let app_info = vk::ApplicationInfo::builder()
-                .api_version(ash::vk_make_version!(1, 0, 0));
+            let is_layer_available = |layer_name: &str| {
+                instance_layer_properties.iter().any(|p| {
+                    let name = CStr::from_ptr(p.layer_name.as_ptr());
+                    name.to_string_lossy() == layer_name
+                })
+            };
 
-            let layer_names = vec![
-                #[cfg(debug_assertions)]
-                c_str!("VK_LAYER_LUNARG_standard_validation")
-            ];
+            let mut layer_names = vec![];
+
+            if descriptor.validation.unwrap_or(cfg!(debug_assertions)) {
+                // Older SDKs only ship the LunarG meta layer
+                match VALIDATION_LAYERS.iter().find(|name| is_layer_available(name)) {
+                    Some(validation_layer) => {
+                        log::debug!("requesting layer: {}", validation_layer);
+                        layer_names.push(CString::new(*validation_layer).unwrap());
+                    }
+                    None if descriptor.validation.is_none() => {
+                        log::warn!("validation layer not present, continuing without validation: {}", VALIDATION_LAYERS[0]);
+                    }
+                    None => return Err(InstanceError::MissingLayer(VALIDATION_LAYERS[0].to_owned())),
+                }
+            }
 
-            for layer_name in layer_names.iter() {
-                let requested_layer_name = CStr::from_ptr(*layer_name);
-                let is_available = instance_layer_properties.iter().any(|p| {
-                    let name = CStr::from_ptr(p.layer_name.as_ptr());
-                    name == requested_layer_name
-                });
-                if !is_available {
-                    log::error!("requested layer unavailable: {:?}", requested_layer_name.to_string_lossy());
+            for extra in descriptor.extra_layers.iter() {
+                if !is_layer_available(extra) {
+                    return Err(InstanceError::MissingLayer(extra.to_string()));
                 }
+                log::debug!("requesting layer: {}", extra);
+                layer_names.push(CString::new(extra.as_bytes()).map_err(|_| InstanceError::MissingLayer(extra.to_string()))?);
             }
 
+            let app_name = CString::new(descriptor.app_name.as_bytes()).map_err(|e| Error::from(e.to_string()))?;
+            let engine_name = CString::new(descriptor.engine_name.as_bytes()).map_err(|e| Error::from(e.to_string()))?;
+
+            let app_info = vk::ApplicationInfo::builder()
+                .application_name(&app_name)
+                .engine_name(&engine_name)
+                .api_version(api_version);
+
+            let layer_names_ptrs: Vec<_> = layer_names.iter().map(|name| name.as_ptr()).collect();
             let extension_names_ptrs: Vec<_> = extension_names.iter().map(|name| name.as_ptr()).collect();
 
             let create_info = vk::InstanceCreateInfo::builder()
                 .application_info(&app_info)
                 .enabled_extension_names(&extension_names_ptrs)
-                .enabled_layer_names(&layer_names);
+                .enabled_layer_names(&layer_names_ptrs);
 
             let raw = entry.create_instance(&create_info, None)?;
 
@@ -139,6 +220,17 @@ impl InstanceInner {
 
             let debug_utils = ext::DebugUtils::new(entry, &raw);
             let debug_report = ext::DebugReport::new(entry, &raw);
+
+            // ash only loads the core 1.1 entry point, which is not available on 1.0 instances
+            let properties2_name = vk::KhrGetPhysicalDeviceProperties2Fn::name();
+            let get_physical_device_memory_properties2_khr = if extension_names.iter().any(|name| name.as_c_str() == properties2_name) {
+                entry
+                    .get_instance_proc_addr(raw.handle(), c_str!("vkGetPhysicalDeviceMemoryProperties2KHR"))
+                    .map(|f| mem::transmute::<_, vk::PFN_vkGetPhysicalDeviceMemoryProperties2>(f))
+            } else {
+                None
+            };
+
             let debug_report_callback = if init_debug_report {
                 let debug_report_create_info = vk::DebugReportCallbackCreateInfoEXT::builder()
                     .flags(vk::DebugReportFlagsEXT::ERROR | vk::DebugReportFlagsEXT::WARNING | vk::DebugReportFlagsEXT::PERFORMANCE_WARNING)
@@ -169,9 +261,10 @@ impl InstanceInner {
 
                 debug_utils,
                 debug_report,
+                get_physical_device_memory_properties2_khr,
             };
 
-            Ok(InstanceInner { raw, raw_ext, extension_properties, debug_report_callback })
+            Ok(InstanceInner { raw, raw_ext, api_version, extension_properties, debug_report_callback })
         }
     }
 
@@ -205,6 +298,7 @@ impl Drop for InstanceInner {
                 self.raw_ext
                     .debug_report
                     .destroy_debug_report_callback(debug_report_callback, None);
+                debug::forget_validation_errors(self.raw.handle());
             }
             self.raw.destroy_instance(None);
         }
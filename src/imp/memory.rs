@@ -0,0 +1,121 @@
+use ash::version::{InstanceV1_0, InstanceV1_1};
+use ash::vk;
+use vk_mem::Allocator;
+
+use crate::imp::AdapterInner;
+use crate::{
+    BufferMemoryUsage, BufferUsageFlags, Error, MemoryHeapReport, MemoryReport, TextureMemoryUsage, TextureUsageFlags,
+};
+
+use std::collections::HashMap;
+
+/// Tracks the memory used by live buffers and textures, grouped by their usage flags.
+///
+/// VMA's statistics only know about memory types and heaps, so the breakdown by resource kind is
+/// kept alongside the allocator.
+#[derive(Debug, Default)]
+pub struct MemoryUsageTracker {
+    buffers: HashMap<BufferUsageFlags, BufferMemoryUsage>,
+    textures: HashMap<TextureUsageFlags, TextureMemoryUsage>,
+}
+
+impl MemoryUsageTracker {
+    pub fn add_buffer(&mut self, usage: BufferUsageFlags, size_bytes: usize) {
+        let entry = self.buffers.entry(usage).or_insert(BufferMemoryUsage {
+            usage,
+            count: 0,
+            size_bytes: 0,
+        });
+        entry.count += 1;
+        entry.size_bytes += size_bytes as u64;
+    }
+
+    pub fn remove_buffer(&mut self, usage: BufferUsageFlags, size_bytes: usize) {
+        if let Some(entry) = self.buffers.get_mut(&usage) {
+            entry.count -= 1;
+            entry.size_bytes -= size_bytes as u64;
+            if entry.count == 0 {
+                self.buffers.remove(&usage);
+            }
+        }
+    }
+
+    pub fn add_texture(&mut self, usage: TextureUsageFlags, size_bytes: usize) {
+        let entry = self.textures.entry(usage).or_insert(TextureMemoryUsage {
+            usage,
+            count: 0,
+            size_bytes: 0,
+        });
+        entry.count += 1;
+        entry.size_bytes += size_bytes as u64;
+    }
+
+    pub fn remove_texture(&mut self, usage: TextureUsageFlags, size_bytes: usize) {
+        if let Some(entry) = self.textures.get_mut(&usage) {
+            entry.count -= 1;
+            entry.size_bytes -= size_bytes as u64;
+            if entry.count == 0 {
+                self.textures.remove(&usage);
+            }
+        }
+    }
+
+    pub fn report(&self, adapter: &AdapterInner, allocator: &Allocator) -> Result<MemoryReport, Error> {
+        let stats = allocator.calculate_stats()?;
+        let (memory_properties, heap_budgets) = unsafe {
+            if adapter.supports_memory_budget() {
+                let mut budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+                let mut memory_properties2 =
+                    vk::PhysicalDeviceMemoryProperties2::builder().push_next(&mut budget_properties);
+                match adapter.instance.raw_ext.get_physical_device_memory_properties2_khr {
+                    Some(get_physical_device_memory_properties2_khr) => {
+                        get_physical_device_memory_properties2_khr(adapter.physical_device, &mut *memory_properties2);
+                    }
+                    None => adapter
+                        .instance
+                        .raw
+                        .get_physical_device_memory_properties2(adapter.physical_device, &mut memory_properties2),
+                }
+                let memory_properties = memory_properties2.memory_properties;
+                (memory_properties, Some(budget_properties.heap_budget))
+            } else {
+                let memory_properties = adapter
+                    .instance
+                    .raw
+                    .get_physical_device_memory_properties(adapter.physical_device);
+                (memory_properties, None)
+            }
+        };
+
+        let heaps = memory_properties.memory_heaps[..memory_properties.memory_heap_count as usize]
+            .iter()
+            .enumerate()
+            .zip(stats.memoryHeap.iter())
+            .map(|((index, heap), stat_info)| MemoryHeapReport {
+                size: heap.size,
+                device_local: heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL),
+                budget: heap_budgets.map(|budgets| budgets[index]),
+                used_bytes: stat_info.usedBytes,
+                unused_bytes: stat_info.unusedBytes,
+                block_count: stat_info.blockCount,
+                allocation_count: stat_info.allocationCount,
+            })
+            .collect();
+
+        let mut buffers: Vec<BufferMemoryUsage> = self.buffers.values().cloned().collect();
+        buffers.sort_by_key(|usage| usage.usage);
+
+        let mut textures: Vec<TextureMemoryUsage> = self.textures.values().cloned().collect();
+        textures.sort_by_key(|usage| usage.usage);
+
+        Ok(MemoryReport {
+            heaps,
+            used_bytes: stats.total.usedBytes,
+            unused_bytes: stats.total.unusedBytes,
+            block_count: stats.total.blockCount,
+            allocation_count: stats.total.allocationCount,
+            buffers,
+            textures,
+        })
+    }
+}
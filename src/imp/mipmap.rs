@@ -0,0 +1,467 @@
+//! Fills the mip levels of a texture from its base level
+
+use ash::version::DeviceV1_0;
+use ash::vk;
+
+use crate::imp::command::Command;
+use crate::imp::fenced_deleter::DeleteWhenUnused;
+use crate::imp::DeviceInner;
+use crate::imp::{binding, pipeline, texture};
+use crate::{
+    BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor, BindingResource,
+    BindingType, Color, ColorStateDescriptor, ColorWriteFlags, CommandEncoder, ConservativeRasterizationMode, CullMode,
+    Device, Error, Extent3D, FilterMode, FrontFace, IndexFormat, InputStateDescriptor, LoadOp,
+    MultisampleStateDescriptor, Origin3D, PipelineLayoutDescriptor, PipelineStageDescriptor, PolygonMode,
+    PrimitiveTopology, RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor, RenderPassDescriptor,
+    RenderPipelineDescriptor, ScalarType, ShaderModuleDescriptor, ShaderStageFlags, StoreOp, Texture,
+    TextureAspectFlags, TextureBlitView, TextureCopyView, TextureDescriptor, TextureDimension, TextureFormat,
+    TextureSubresourceRange, TextureUsageFlags, TextureViewDescriptor, TextureViewDimension,
+};
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The handles of the downsample pipeline for a texture format
+#[derive(Clone, Copy, Debug)]
+pub struct DownsamplePipeline {
+    pipeline: vk::Pipeline,
+    pipeline_layout: vk::PipelineLayout,
+    bind_group_layout: vk::DescriptorSetLayout,
+}
+
+/// Caches the downsample pipelines by texture format.
+///
+/// Like the render pass cache, only the handles are kept, because a `RenderPipeline` holds a
+/// reference to the device that owns the cache. They are destroyed together with the device.
+#[derive(Debug, Default)]
+pub struct DownsamplePipelineCache {
+    cache: HashMap<TextureFormat, DownsamplePipeline>,
+}
+
+impl DownsamplePipelineCache {
+    pub fn get(&self, format: TextureFormat) -> Option<DownsamplePipeline> {
+        self.cache.get(&format).cloned()
+    }
+
+    /// Returns the cached pipeline, or `pipeline` if none was cached for `format`
+    pub fn insert(&mut self, format: TextureFormat, pipeline: DownsamplePipeline) -> DownsamplePipeline {
+        *self.cache.entry(format).or_insert(pipeline)
+    }
+
+    pub fn drain(&mut self, device: &DeviceInner) {
+        for (format, pipeline) in self.cache.drain() {
+            unsafe {
+                log::trace!("destroying downsample pipeline: {:?}, format: {:?}", pipeline, format);
+                device.raw.destroy_pipeline(pipeline.pipeline, None);
+                device.raw.destroy_pipeline_layout(pipeline.pipeline_layout, None);
+                device
+                    .raw
+                    .destroy_descriptor_set_layout(pipeline.bind_group_layout, None);
+            }
+        }
+    }
+}
+
+impl Drop for DownsamplePipelineCache {
+    fn drop(&mut self) {
+        if !self.cache.is_empty() {
+            log::error!("DownsamplePipelineCache dropped without being drained")
+        }
+    }
+}
+
+fn mip_extent(texture: &Texture, mip_level: u32) -> Extent3D {
+    let size = texture.inner.descriptor.size;
+    let depth = match texture.inner.descriptor.dimension {
+        TextureDimension::D3 => (size.depth >> mip_level).max(1),
+        _ => 1,
+    };
+    Extent3D {
+        width: (size.width >> mip_level).max(1),
+        height: (size.height >> mip_level).max(1),
+        depth,
+    }
+}
+
+fn validate_range(texture: &Texture, range: TextureSubresourceRange) -> Result<(), Error> {
+    let descriptor = &texture.inner.descriptor;
+    if range.mip_level_count == 0
+        || range.base_mip_level + range.mip_level_count > descriptor.mip_level_count
+        || range.array_layer_count == 0
+        || range.base_array_layer + range.array_layer_count > descriptor.array_layer_count
+    {
+        log::error!(
+            "mipmap range {:?} is outside of the {} mip levels and {} layers of the texture (label: {:?})",
+            range,
+            descriptor.mip_level_count,
+            descriptor.array_layer_count,
+            texture.inner.label.get()
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    Ok(())
+}
+
+/// Linear blits need the format to support blitting and linear filtering
+fn supports_linear_blit(texture: &Texture) -> bool {
+    let descriptor = &texture.inner.descriptor;
+    let features = texture
+        .inner
+        .device
+        .adapter
+        .format_properties(texture::image_format(descriptor.format))
+        .optimal_tiling_features;
+    let required_features = vk::FormatFeatureFlags::BLIT_SRC
+        | vk::FormatFeatureFlags::BLIT_DST
+        | vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR;
+    features.contains(required_features)
+        && descriptor
+            .usage
+            .contains(TextureUsageFlags::TRANSFER_SRC | TextureUsageFlags::TRANSFER_DST)
+}
+
+fn blit_bounds(extent: Extent3D) -> [Origin3D; 2] {
+    [
+        Origin3D { x: 0, y: 0, z: 0 },
+        Origin3D {
+            x: extent.width as i32,
+            y: extent.height as i32,
+            z: extent.depth as i32,
+        },
+    ]
+}
+
+fn blit_mipmaps(encoder: &mut CommandEncoder, texture: &Texture, range: TextureSubresourceRange) {
+    let mip_levels = range.base_mip_level + 1..range.base_mip_level + range.mip_level_count;
+    for mip_level in mip_levels {
+        let src_bounds = blit_bounds(mip_extent(texture, mip_level - 1));
+        let dst_bounds = blit_bounds(mip_extent(texture, mip_level));
+        for array_layer in range.base_array_layer..range.base_array_layer + range.array_layer_count {
+            let src = TextureBlitView {
+                texture,
+                mip_level: mip_level - 1,
+                array_layer,
+                bounds: src_bounds,
+            };
+            let dst = TextureBlitView {
+                texture,
+                mip_level,
+                array_layer,
+                bounds: dst_bounds,
+            };
+            encoder.blit_texture_to_texture(src, dst, FilterMode::Linear);
+        }
+    }
+}
+
+/// The downsample pipeline renders to formats that can not be blitted, e.g. integer formats
+fn validate_render_fallback(texture: &Texture) -> Result<(), Error> {
+    let descriptor = &texture.inner.descriptor;
+    let features = texture
+        .inner
+        .device
+        .adapter
+        .format_properties(texture::image_format(descriptor.format))
+        .optimal_tiling_features;
+    let required_features = vk::FormatFeatureFlags::COLOR_ATTACHMENT | vk::FormatFeatureFlags::SAMPLED_IMAGE;
+    let required_usage = TextureUsageFlags::TRANSFER_SRC | TextureUsageFlags::OUTPUT_ATTACHMENT;
+    if descriptor.dimension != TextureDimension::D2
+        || descriptor.sample_count != 1
+        || !features.contains(required_features)
+        || !descriptor.usage.contains(required_usage)
+    {
+        log::error!(
+            concat!(
+                "mipmaps of {:?} can not be blitted. Rendering them requires a single sampled 2D texture with ",
+                "{:?} usage and a format that supports sampling and color attachments (label: {:?})"
+            ),
+            descriptor.format,
+            required_usage,
+            texture.inner.label.get()
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    Ok(())
+}
+
+fn bind_group_layout_descriptor() -> BindGroupLayoutDescriptor {
+    BindGroupLayoutDescriptor {
+        bindings: vec![BindGroupLayoutBinding {
+            binding: 0,
+            visibility: ShaderStageFlags::FRAGMENT,
+            binding_type: BindingType::SampledTexture,
+        }],
+    }
+}
+
+/// Creates the downsample pipeline for `format` with layouts that are owned by the cache
+fn create_downsample_pipeline(device: &Arc<DeviceInner>, format: TextureFormat) -> Result<DownsamplePipeline, Error> {
+    let bind_group_layout = binding::create_descriptor_set_layout(device, &bind_group_layout_descriptor().bindings)?;
+    let set_layouts = [bind_group_layout];
+    let create_info = vk::PipelineLayoutCreateInfo::builder().set_layouts(&set_layouts);
+    let pipeline_layout = match unsafe { device.raw.create_pipeline_layout(&create_info, None) } {
+        Ok(pipeline_layout) => pipeline_layout,
+        Err(e) => {
+            unsafe { device.raw.destroy_descriptor_set_layout(bind_group_layout, None) };
+            return Err(e.into());
+        }
+    };
+    let pipeline = create_downsample_render_pipeline(device, format, pipeline_layout);
+    let pipeline = match pipeline {
+        Ok(pipeline) => pipeline,
+        Err(e) => {
+            unsafe {
+                device.raw.destroy_pipeline_layout(pipeline_layout, None);
+                device.raw.destroy_descriptor_set_layout(bind_group_layout, None);
+            }
+            return Err(e);
+        }
+    };
+    Ok(DownsamplePipeline {
+        pipeline,
+        pipeline_layout,
+        bind_group_layout,
+    })
+}
+
+fn create_downsample_render_pipeline(
+    device: &Arc<DeviceInner>,
+    format: TextureFormat,
+    layout: vk::PipelineLayout,
+) -> Result<vk::Pipeline, Error> {
+    let device = Device {
+        inner: Arc::clone(device),
+    };
+    let fragment_code: &[u8] = match texture::scalar_type(format) {
+        ScalarType::Uint => include_bytes!("../shaders/mipmap_uint.frag.spv"),
+        ScalarType::Sint => include_bytes!("../shaders/mipmap_sint.frag.spv"),
+        _ => include_bytes!("../shaders/mipmap.frag.spv"),
+    };
+    let vertex_shader = device.create_shader_module(ShaderModuleDescriptor {
+        code: include_bytes!("../shaders/mipmap.vert.spv"),
+        label: Some("mipmap vertex shader"),
+    })?;
+    let fragment_shader = device.create_shader_module(ShaderModuleDescriptor {
+        code: fragment_code,
+        label: Some("mipmap fragment shader"),
+    })?;
+
+    let descriptor = RenderPipelineDescriptor {
+        layout: None,
+        vertex_stage: PipelineStageDescriptor {
+            module: vertex_shader,
+            entry_point: Cow::Borrowed("main"),
+            specialization_constants: Default::default(),
+        },
+        fragment_stage: PipelineStageDescriptor {
+            module: fragment_shader,
+            entry_point: Cow::Borrowed("main"),
+            specialization_constants: Default::default(),
+        },
+        primitive_topology: PrimitiveTopology::TriangleList,
+        rasterization_state: RasterizationStateDescriptor {
+            front_face: FrontFace::Ccw,
+            cull_mode: CullMode::None,
+            polygon_mode: PolygonMode::Fill,
+            depth_clamp: false,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+            line_width: 1.0,
+            conservative_rasterization: ConservativeRasterizationMode::Disabled,
+        },
+        color_states: vec![ColorStateDescriptor {
+            format,
+            color_blend: Default::default(),
+            alpha_blend: Default::default(),
+            write_mask: ColorWriteFlags::ALL,
+        }],
+        depth_stencil_state: None,
+        input_state: InputStateDescriptor {
+            index_format: IndexFormat::U16,
+            vertex_buffers: vec![],
+        },
+        sample_count: 1,
+        multisample_state: MultisampleStateDescriptor::default(),
+        label: Some("mipmap pipeline"),
+    };
+    pipeline::create_graphics_pipeline(&device.inner, &descriptor, layout)
+}
+
+fn downsample_pipeline(device: &Arc<DeviceInner>, format: TextureFormat) -> Result<DownsamplePipeline, Error> {
+    if let Some(pipeline) = device.state.lock().downsample_pipeline_cache_mut().get(format) {
+        return Ok(pipeline);
+    }
+    let created = create_downsample_pipeline(device, format)?;
+    let mut state = device.state.lock();
+    let pipeline = state.downsample_pipeline_cache_mut().insert(format, created);
+    if pipeline.pipeline != created.pipeline {
+        // another thread created the pipeline first
+        let serial = state.get_next_pending_serial();
+        let deleter = state.get_fenced_deleter();
+        deleter.delete_when_unused(created.pipeline, serial);
+        deleter.delete_when_unused(created.pipeline_layout, serial);
+        deleter.delete_when_unused(created.bind_group_layout, serial);
+    }
+    Ok(pipeline)
+}
+
+/// Renders every mip level from a copy of the previous level, since a texture can not be sampled
+/// and rendered to in the same pass.
+fn render_mipmaps(
+    encoder: &mut CommandEncoder,
+    texture: &Texture,
+    range: TextureSubresourceRange,
+) -> Result<(), Error> {
+    validate_render_fallback(texture)?;
+
+    let device = Device {
+        inner: encoder.inner.device.clone(),
+    };
+    let format = texture.inner.descriptor.format;
+    let pipeline = downsample_pipeline(&device.inner, format)?.pipeline;
+
+    // The bind groups need layouts of their own. They are identically defined to the cached ones,
+    // which makes them compatible with the cached pipeline.
+    let bind_group_layout = device.create_bind_group_layout(bind_group_layout_descriptor())?;
+    let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
+        bind_group_layouts: vec![bind_group_layout.clone()],
+        push_constant_ranges: vec![],
+        label: Some("mipmap pipeline layout"),
+    })?;
+
+    // holds the source levels, from the base level up to the second to last level
+    let sources = device.create_texture(TextureDescriptor {
+        size: mip_extent(texture, range.base_mip_level),
+        array_layer_count: range.array_layer_count,
+        mip_level_count: range.mip_level_count - 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage: TextureUsageFlags::SAMPLED | TextureUsageFlags::TRANSFER_DST,
+        label: Some("mipmap sources"),
+    })?;
+
+    for source_level in 0..range.mip_level_count - 1 {
+        let mip_level = range.base_mip_level + source_level;
+        encoder.copy_texture_to_texture(
+            TextureCopyView {
+                texture,
+                mip_level,
+                array_layer: range.base_array_layer,
+                array_layer_count: range.array_layer_count,
+                origin: Origin3D { x: 0, y: 0, z: 0 },
+            },
+            TextureCopyView {
+                texture: &sources,
+                mip_level: source_level,
+                array_layer: 0,
+                array_layer_count: range.array_layer_count,
+                origin: Origin3D { x: 0, y: 0, z: 0 },
+            },
+            mip_extent(texture, mip_level),
+        );
+
+        for layer in 0..range.array_layer_count {
+            let view_descriptor = TextureViewDescriptor {
+                dimension: TextureViewDimension::D2,
+                aspect: TextureAspectFlags::COLOR,
+                base_mip_level: source_level,
+                mip_level_count: 1,
+                base_array_layer: layer,
+                array_layer_count: 1,
+                format,
+                label: None,
+            };
+            let source = sources.create_view(view_descriptor)?;
+            let target = texture.create_view(TextureViewDescriptor {
+                base_mip_level: mip_level + 1,
+                base_array_layer: range.base_array_layer + layer,
+                ..view_descriptor
+            })?;
+
+            let bind_group = device.create_bind_group(BindGroupDescriptor {
+                layout: bind_group_layout.clone(),
+                bindings: vec![BindGroupBinding {
+                    binding: 0,
+                    resource: BindingResource::TextureView(source),
+                }],
+                label: None,
+            })?;
+
+            let mut render_pass = encoder.begin_render_pass(RenderPassDescriptor {
+                color_attachments: &[RenderPassColorAttachmentDescriptor {
+                    attachment: &target,
+                    resolve_target: None,
+                    load_op: LoadOp::Clear,
+                    store_op: StoreOp::Store,
+                    clear_color: Color {
+                        r: 0.0,
+                        g: 0.0,
+                        b: 0.0,
+                        a: 0.0,
+                    },
+                }],
+                depth_stencil_attachment: None,
+            });
+            render_pass
+                .inner
+                .top_level_encoder
+                .push(Command::SetDownsamplePipeline {
+                    pipeline,
+                    layout: Arc::clone(&pipeline_layout.inner),
+                });
+            render_pass.set_bind_group(0, &bind_group, None);
+            render_pass.draw(3, 1, 0, 0);
+            render_pass.end_pass();
+        }
+    }
+
+    Ok(())
+}
+
+pub fn generate_mipmaps(
+    encoder: &mut CommandEncoder,
+    texture: &Texture,
+    range: TextureSubresourceRange,
+) -> Result<(), Error> {
+    validate_range(texture, range)?;
+    if range.mip_level_count == 1 {
+        return Ok(());
+    }
+    if supports_linear_blit(texture) {
+        blit_mipmaps(encoder, texture, range);
+        Ok(())
+    } else {
+        render_mipmaps(encoder, texture, range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ScalarType, ShaderReflection, ShaderStageFlags};
+
+    #[test]
+    fn downsample_shaders() {
+        let vertex = ShaderReflection::new(include_bytes!("../shaders/mipmap.vert.spv")).unwrap();
+        let main = vertex.entry_point("main").unwrap();
+        assert_eq!(ShaderStageFlags::VERTEX, main.stage);
+        assert!(main.inputs.is_empty());
+
+        let fragment_shaders: [(&[u8], ScalarType); 3] = [
+            (include_bytes!("../shaders/mipmap.frag.spv"), ScalarType::Float),
+            (include_bytes!("../shaders/mipmap_uint.frag.spv"), ScalarType::Uint),
+            (include_bytes!("../shaders/mipmap_sint.frag.spv"), ScalarType::Sint),
+        ];
+        for (code, scalar_type) in fragment_shaders.iter() {
+            let fragment = ShaderReflection::new(code).unwrap();
+            let main = fragment.entry_point("main").unwrap();
+            assert_eq!(ShaderStageFlags::FRAGMENT, main.stage);
+            assert!(main.inputs.is_empty());
+            assert_eq!(1, main.bindings.len());
+            assert_eq!(1, main.outputs.len());
+            assert_eq!(*scalar_type, main.outputs[0].scalar_type);
+        }
+    }
+}
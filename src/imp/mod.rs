@@ -17,13 +17,19 @@ mod device;
 mod fence;
 mod fenced_deleter;
 mod instance;
+mod memory;
+mod mipmap;
 mod pass_resource_usage;
 mod pipeline;
 mod queue;
+mod reload;
 mod render_pass;
 mod sampler;
 mod serial;
 mod shader;
+#[cfg(feature = "naga")]
+mod shader_compiler;
+mod spirv;
 mod surface;
 mod swapchain;
 mod texture;
@@ -32,11 +38,14 @@ mod util;
 mod cookbook;
 mod vec;
 
+pub use crate::imp::adapter::AdapterProperties;
 pub use crate::imp::debug::validate;
 
 use crate::{
     AdapterOptions, BindGroupBinding, BindGroupLayout, BindGroupLayoutBinding, BufferDescriptor, BufferUsageFlags,
-    Extensions, IndexFormat, Limits, SamplerDescriptor, TextureDescriptor, TextureViewDescriptor,
+    ComputePipeline, ComputePipelineDescriptor, Error, Extensions, IndexFormat, Limits, PushConstantRange, QueueType,
+    RenderPipeline, RenderPipelineDescriptor, SamplerDescriptor, ShaderReflection, TextureDescriptor,
+    TextureViewDescriptor,
 };
 
 use std::collections::HashMap;
@@ -76,6 +85,7 @@ macro_rules! handle_traits {
 pub struct InstanceInner {
     raw: ash::Instance,
     raw_ext: InstanceExt,
+    api_version: u32,
     extension_properties: Vec<vk::ExtensionProperties>,
     debug_report_callback: Option<vk::DebugReportCallbackEXT>,
 }
@@ -94,6 +104,15 @@ impl Hash for InstanceInner {
     }
 }
 
+pub struct DebugMessengerInner {
+    handle: vk::DebugUtilsMessengerEXT,
+    instance: Arc<InstanceInner>,
+    // Double boxed so that the user data pointer handed to the driver is thin and stable
+    callback: Box<debug::DebugMessageCallback>,
+}
+
+handle_traits!(DebugMessengerInner);
+
 /// Instance extension functions
 struct InstanceExt {
     surface: khr::Surface,
@@ -115,6 +134,7 @@ struct InstanceExt {
 
     debug_utils: ext::DebugUtils,
     debug_report: ext::DebugReport,
+    get_physical_device_memory_properties2_khr: Option<vk::PFN_vkGetPhysicalDeviceMemoryProperties2>,
 }
 
 #[allow(dead_code)]
@@ -125,8 +145,10 @@ pub struct AdapterInner {
     physical_device_properties: vk::PhysicalDeviceProperties,
     physical_device_format_properties: Vec<(vk::Format, vk::FormatProperties)>,
     queue_family_properties: Vec<vk::QueueFamilyProperties>,
+    extension_properties: Vec<vk::ExtensionProperties>,
     name: String,
     extensions: Extensions,
+    limits: Limits,
     options: AdapterOptions,
 }
 
@@ -152,6 +174,9 @@ pub struct DeviceInner {
     extensions: Extensions,
     limits: Limits,
     queue: QueueInfo,
+    compute_queue: Option<QueueInfo>,
+    transfer_queue: Option<QueueInfo>,
+    pipeline_cache: vk::PipelineCache,
     state: Mutex<device::DeviceState>,
 }
 
@@ -206,17 +231,21 @@ pub struct QueueInfo {
     handle: vk::Queue,
     queue_index: u32,
     queue_family_index: u32,
+    queue_flags: vk::QueueFlags,
+    queue_type: QueueType,
 }
 
 #[derive(Debug)]
 pub struct TextureInner {
     handle: vk::Image,
     device: Arc<DeviceInner>,
-    descriptor: TextureDescriptor,
+    descriptor: TextureDescriptor<'static>,
     subresource_usage: Mutex<texture::SubresourceUsageTracker>,
+    queue_family_index: Mutex<u32>,
     // if the allocation is None, the image is owned by the swapchain
     allocation: Option<Allocation>,
     allocation_info: Option<AllocationInfo>,
+    label: debug::Label,
 }
 
 handle_traits!(TextureInner);
@@ -225,7 +254,8 @@ handle_traits!(TextureInner);
 pub struct TextureViewInner {
     handle: vk::ImageView,
     texture: Arc<TextureInner>,
-    descriptor: TextureViewDescriptor,
+    descriptor: TextureViewDescriptor<'static>,
+    label: debug::Label,
 }
 
 handle_traits!(TextureViewInner);
@@ -240,11 +270,13 @@ pub enum BufferState {
 pub struct BufferInner {
     handle: vk::Buffer,
     device: Arc<DeviceInner>,
-    descriptor: BufferDescriptor,
+    descriptor: BufferDescriptor<'static>,
     allocation: Allocation,
     allocation_info: AllocationInfo,
     last_usage: Mutex<BufferUsageFlags>,
+    queue_family_index: Mutex<u32>,
     buffer_state: Mutex<BufferState>,
+    label: debug::Label,
 }
 
 handle_traits!(BufferInner);
@@ -261,7 +293,8 @@ handle_traits!(BufferViewInner);
 pub struct SamplerInner {
     handle: vk::Sampler,
     device: Arc<DeviceInner>,
-    descriptor: SamplerDescriptor,
+    descriptor: SamplerDescriptor<'static>,
+    label: debug::Label,
 }
 
 handle_traits!(SamplerInner);
@@ -282,6 +315,7 @@ pub struct BindGroupInner {
     layout: Arc<BindGroupLayoutInner>,
     // Keep the resources alive as long as the bind group exists
     bindings: Vec<BindGroupBinding>,
+    label: debug::Label,
 }
 
 handle_traits!(BindGroupInner);
@@ -290,6 +324,8 @@ handle_traits!(BindGroupInner);
 pub struct ShaderModuleInner {
     handle: vk::ShaderModule,
     device: Arc<DeviceInner>,
+    label: debug::Label,
+    reflection: ShaderReflection,
 }
 
 handle_traits!(ShaderModuleInner);
@@ -299,6 +335,8 @@ pub struct PipelineLayoutInner {
     handle: vk::PipelineLayout,
     device: Arc<DeviceInner>,
     bind_group_layouts: Vec<BindGroupLayout>,
+    push_constant_ranges: Vec<PushConstantRange>,
+    label: debug::Label,
 }
 
 handle_traits!(PipelineLayoutInner);
@@ -307,6 +345,7 @@ handle_traits!(PipelineLayoutInner);
 pub struct ComputePipelineInner {
     handle: vk::Pipeline,
     layout: Arc<PipelineLayoutInner>,
+    label: debug::Label,
 }
 
 handle_traits!(ComputePipelineInner);
@@ -316,20 +355,37 @@ pub struct RenderPipelineInner {
     handle: vk::Pipeline,
     layout: Arc<PipelineLayoutInner>,
     index_format: IndexFormat,
+    label: debug::Label,
 }
 
 handle_traits!(RenderPipelineInner);
 
+#[derive(Debug)]
+pub struct ReloadableComputePipelineInner {
+    device: Arc<DeviceInner>,
+    state: Mutex<reload::ReloadState<ComputePipelineDescriptor<'static>, ComputePipeline>>,
+}
+
+#[derive(Debug)]
+pub struct ReloadableRenderPipelineInner {
+    device: Arc<DeviceInner>,
+    state: Mutex<reload::ReloadState<RenderPipelineDescriptor<'static>, RenderPipeline>>,
+}
+
 #[derive(Debug)]
 pub struct CommandEncoderInner {
     state: command_encoder::CommandEncoderState,
     device: Arc<DeviceInner>,
+    label: Option<String>,
+    // the first validation error of a command, reported by `finish`
+    error: Option<Error>,
 }
 
 #[derive(Debug)]
 pub struct CommandBufferInner {
     state: command_buffer::CommandBufferState,
     device: Arc<DeviceInner>,
+    label: Option<String>,
 }
 
 #[derive(Debug)]
@@ -1,4 +1,4 @@
-use crate::imp::{BufferInner, TextureInner};
+use crate::imp::{BufferInner, QueueInfo, TextureInner};
 use crate::{BufferUsageFlags, Error, TextureUsageFlags};
 
 use ash::vk;
@@ -31,6 +31,79 @@ pub struct CommandBufferResourceUsage {
     pub top_level_textures: HashSet<Arc<TextureInner>>,
 }
 
+impl CommandBufferResourceUsage {
+    /// Every buffer and texture used by the command buffer, without duplicates
+    pub fn queue_resources(&self) -> Vec<QueueResource> {
+        let mut buffers: Vec<&Arc<BufferInner>> = self.top_level_buffers.iter().collect();
+        let mut textures: Vec<&Arc<TextureInner>> = self.top_level_textures.iter().collect();
+        for pass in self.per_pass.iter() {
+            buffers.extend(pass.buffers.iter().map(|(buffer, _)| buffer));
+            textures.extend(pass.textures.iter().map(|(texture, _)| texture));
+        }
+        buffers.sort();
+        buffers.dedup();
+        textures.sort();
+        textures.dedup();
+        buffers
+            .into_iter()
+            .map(|buffer| QueueResource::Buffer(buffer.clone()))
+            .chain(
+                textures
+                    .into_iter()
+                    .map(|texture| QueueResource::Texture(texture.clone())),
+            )
+            .collect()
+    }
+}
+
+/// A resource that is owned by a single queue family at a time
+#[derive(Debug, Clone)]
+pub enum QueueResource {
+    Buffer(Arc<BufferInner>),
+    Texture(Arc<TextureInner>),
+}
+
+impl QueueResource {
+    pub fn queue_family_index(&self) -> u32 {
+        match self {
+            QueueResource::Buffer(buffer) => *buffer.queue_family_index.lock(),
+            QueueResource::Texture(texture) => *texture.queue_family_index.lock(),
+        }
+    }
+
+    pub fn set_queue_family_index(&self, queue_family_index: u32) {
+        match self {
+            QueueResource::Buffer(buffer) => *buffer.queue_family_index.lock() = queue_family_index,
+            QueueResource::Texture(texture) => *texture.queue_family_index.lock() = queue_family_index,
+        }
+    }
+
+    /// Returns true if the resource has never been used, so it has no contents to transfer
+    pub fn is_unused(&self) -> bool {
+        match self {
+            QueueResource::Buffer(buffer) => *buffer.last_usage.lock() == BufferUsageFlags::NONE,
+            QueueResource::Texture(texture) => texture.is_unused(),
+        }
+    }
+
+    pub fn transfer_queue_family(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        queue: &QueueInfo,
+        src_queue_family_index: u32,
+        dst_queue_family_index: u32,
+    ) {
+        match self {
+            QueueResource::Buffer(buffer) => {
+                buffer.transfer_queue_family(command_buffer, queue, src_queue_family_index, dst_queue_family_index)
+            }
+            QueueResource::Texture(texture) => {
+                texture.transfer_queue_family(command_buffer, queue, src_queue_family_index, dst_queue_family_index)
+            }
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum PassType {
     Render,
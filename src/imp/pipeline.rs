@@ -2,25 +2,87 @@ use ash::version::DeviceV1_0;
 use ash::vk;
 
 use std::convert::TryFrom;
-use std::ffi::CString;
+use std::ffi::{c_void, CString};
+use std::mem;
 use std::sync::Arc;
 
 use crate::imp::fenced_deleter::DeleteWhenUnused;
 use crate::imp::render_pass::{self, ColorInfo, DepthStencilInfo, RenderPassCacheQuery};
-use crate::imp::{binding, sampler};
-use crate::imp::{ComputePipelineInner, DeviceInner, PipelineLayoutInner, RenderPipelineInner};
+use crate::imp::{binding, debug, sampler, texture};
+use crate::imp::{BindGroupLayoutInner, ComputePipelineInner, DeviceInner, PipelineLayoutInner, RenderPipelineInner};
 use crate::{
-    BlendFactor, BlendOperation, ColorStateDescriptor, ColorWriteFlags, CompareFunction, ComputePipeline,
-    ComputePipelineDescriptor, CullMode, DepthStencilStateDescriptor, Error, FrontFace, InputStepMode, LoadOp,
-    PipelineLayout, PipelineLayoutDescriptor, PrimitiveTopology, RasterizationStateDescriptor, RenderPipeline,
-    RenderPipelineDescriptor, StencilOperation, StencilStateFaceDescriptor, TextureFormat, VertexAttributeDescriptor,
-    VertexBufferDescriptor, VertexFormat,
+    BindGroupLayout, BindGroupLayoutBinding, BindGroupLayoutDescriptor, BindingReflection, BindingType, BlendFactor,
+    BlendOperation, ColorStateDescriptor, ColorWriteFlags, CompareFunction, ComputePipeline, ComputePipelineDescriptor,
+    ConservativeRasterizationMode, CullMode, DepthStencilStateDescriptor, EntryPointReflection, Error, FrontFace,
+    InputStepMode, Limits, LoadOp, PipelineError, PipelineLayout, PipelineLayoutDescriptor, PipelineStageDescriptor,
+    PolygonMode, PrimitiveTopology, PushConstantRange, RasterizationStateDescriptor, RenderPipeline,
+    RenderPipelineDescriptor, ScalarType, ShaderBindingType, ShaderStageFlags, StencilOperation,
+    StencilStateFaceDescriptor, TextureFormat, VertexAttributeDescriptor, VertexBufferDescriptor, VertexFormat,
 };
 
 pub const MAX_PUSH_CONSTANTS_SIZE: usize = 128;
 
+fn validate_pipeline_layout_limits(limits: &Limits, descriptor: &PipelineLayoutDescriptor) -> Result<(), Error> {
+    if descriptor.bind_group_layouts.len() > limits.max_bind_groups as usize {
+        log::error!(
+            "pipeline layout bind group count ({}) exceeds max_bind_groups ({}) (label: {:?})",
+            descriptor.bind_group_layouts.len(),
+            limits.max_bind_groups,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    let count_bindings = |binding_type: BindingType| {
+        descriptor
+            .bind_group_layouts
+            .iter()
+            .flat_map(|layout| layout.inner.layout_bindings.iter())
+            .filter(|binding| binding.binding_type == binding_type)
+            .count()
+    };
+
+    let dynamic_uniform_buffers = count_bindings(BindingType::DynamicUniformBuffer);
+    if dynamic_uniform_buffers > limits.max_dynamic_uniform_buffers_per_pipeline_layout as usize {
+        log::error!(
+            "pipeline layout dynamic uniform buffer count ({}) exceeds max_dynamic_uniform_buffers_per_pipeline_layout ({}) (label: {:?})",
+            dynamic_uniform_buffers,
+            limits.max_dynamic_uniform_buffers_per_pipeline_layout,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    let dynamic_storage_buffers = count_bindings(BindingType::DynamicStorageBuffer);
+    if dynamic_storage_buffers > limits.max_dynamic_storage_buffers_per_pipeline_layout as usize {
+        log::error!(
+            "pipeline layout dynamic storage buffer count ({}) exceeds max_dynamic_storage_buffers_per_pipeline_layout ({}) (label: {:?})",
+            dynamic_storage_buffers,
+            limits.max_dynamic_storage_buffers_per_pipeline_layout,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    for range in descriptor.push_constant_ranges.iter() {
+        if range.offset + range.size > limits.max_push_constant_size as usize {
+            log::error!(
+                "push constant range {:?} exceeds max_push_constant_size ({}) (label: {:?})",
+                range,
+                limits.max_push_constant_size,
+                descriptor.label
+            );
+            return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+        }
+    }
+
+    Ok(())
+}
+
 impl PipelineLayoutInner {
     pub fn new(device: Arc<DeviceInner>, descriptor: PipelineLayoutDescriptor) -> Result<PipelineLayoutInner, Error> {
+        validate_pipeline_layout_limits(&device.limits, &descriptor)?;
+
         let push_constant_ranges: Vec<_> = descriptor
             .push_constant_ranges
             .iter()
@@ -44,14 +106,318 @@ impl PipelineLayoutInner {
 
         let handle = unsafe { device.raw.create_pipeline_layout(&create_info, None)? };
 
+        let label = debug::Label::new(&device, handle, descriptor.label);
+
         Ok(PipelineLayoutInner {
             handle,
             device,
             bind_group_layouts: descriptor.bind_group_layouts.to_vec(),
+            push_constant_ranges: descriptor.push_constant_ranges.to_vec(),
+            label,
         })
     }
 }
 
+/// The layout binding type of a reflected binding. Only single bindings of the types that
+/// `BindingType` can express are derived.
+fn derived_binding_type(reflected: &BindingReflection) -> Result<BindingType, PipelineError> {
+    let binding_type = match reflected.binding_type {
+        ShaderBindingType::UniformBuffer => Some(BindingType::UniformBuffer),
+        ShaderBindingType::StorageBuffer => Some(BindingType::StorageBuffer),
+        ShaderBindingType::Sampler => Some(BindingType::Sampler),
+        ShaderBindingType::SampledTexture => Some(BindingType::SampledTexture),
+        ShaderBindingType::StorageTexelBuffer => Some(BindingType::StorageTexelBuffer),
+        ShaderBindingType::StorageTexture
+        | ShaderBindingType::CombinedTextureSampler
+        | ShaderBindingType::UniformTexelBuffer
+        | ShaderBindingType::InputAttachment => None,
+    };
+    match binding_type {
+        Some(binding_type) if reflected.count == 1 => Ok(binding_type),
+        _ => Err(PipelineError::UnsupportedBinding {
+            set: reflected.set,
+            binding: reflected.binding,
+            shader_type: reflected.binding_type,
+            count: reflected.count,
+        }),
+    }
+}
+
+fn is_compatible_binding_type(layout_type: BindingType, shader_type: ShaderBindingType) -> bool {
+    match layout_type {
+        BindingType::UniformBuffer | BindingType::DynamicUniformBuffer => {
+            shader_type == ShaderBindingType::UniformBuffer
+        }
+        BindingType::StorageBuffer | BindingType::DynamicStorageBuffer => {
+            shader_type == ShaderBindingType::StorageBuffer
+        }
+        BindingType::Sampler => shader_type == ShaderBindingType::Sampler,
+        BindingType::SampledTexture => shader_type == ShaderBindingType::SampledTexture,
+        BindingType::StorageTexelBuffer => shader_type == ShaderBindingType::StorageTexelBuffer,
+    }
+}
+
+pub fn vertex_format_scalar_type(format: VertexFormat) -> ScalarType {
+    match format {
+        VertexFormat::UChar2 | VertexFormat::UChar4 => ScalarType::Uint,
+        VertexFormat::Char2 | VertexFormat::Char4 => ScalarType::Sint,
+        VertexFormat::UShort2 | VertexFormat::UShort4 => ScalarType::Uint,
+        VertexFormat::Short2 | VertexFormat::Short4 => ScalarType::Sint,
+        VertexFormat::UInt | VertexFormat::UInt2 | VertexFormat::UInt3 | VertexFormat::UInt4 => ScalarType::Uint,
+        VertexFormat::Int | VertexFormat::Int2 | VertexFormat::Int3 | VertexFormat::Int4 => ScalarType::Sint,
+        _ => ScalarType::Float,
+    }
+}
+
+/// The specialization constants of a stage, packed as 32-bit values
+struct SpecializationData {
+    map_entries: Vec<vk::SpecializationMapEntry>,
+    data: Vec<u32>,
+}
+
+impl SpecializationData {
+    fn new(descriptor: &PipelineStageDescriptor) -> SpecializationData {
+        let (map_entries, data) = descriptor
+            .specialization_constants
+            .iter()
+            .enumerate()
+            .map(|(index, (constant_id, value))| {
+                let map_entry = vk::SpecializationMapEntry {
+                    constant_id: *constant_id,
+                    offset: (index * mem::size_of::<u32>()) as u32,
+                    size: mem::size_of::<u32>(),
+                };
+                (map_entry, value.bits())
+            })
+            .unzip();
+        SpecializationData { map_entries, data }
+    }
+
+    /// The returned info points into `self`
+    fn info(&self) -> vk::SpecializationInfo {
+        vk::SpecializationInfo {
+            map_entry_count: self.map_entries.len() as u32,
+            p_map_entries: self.map_entries.as_ptr(),
+            data_size: self.data.len() * mem::size_of::<u32>(),
+            p_data: self.data.as_ptr() as *const _,
+        }
+    }
+}
+
+/// Looks up the reflected entry point of a pipeline stage and checks its execution model
+fn stage_entry_point(
+    stage: ShaderStageFlags,
+    descriptor: &PipelineStageDescriptor,
+) -> Result<&EntryPointReflection, PipelineError> {
+    let entry_point = descriptor
+        .module
+        .inner
+        .reflection
+        .entry_point(&descriptor.entry_point)
+        .ok_or_else(|| PipelineError::EntryPointNotFound {
+            stage,
+            entry_point: descriptor.entry_point.to_string(),
+        })?;
+
+    if entry_point.stage != stage {
+        return Err(PipelineError::EntryPointStageMismatch {
+            entry_point: entry_point.name.clone(),
+            expected: stage,
+            found: entry_point.stage,
+        });
+    }
+
+    Ok(entry_point)
+}
+
+/// Builds a pipeline layout from the reflected interface of the pipeline stages. Bindings that are
+/// shared between stages are merged; buffers are never derived as dynamic.
+fn derive_pipeline_layout(
+    device: &Arc<DeviceInner>,
+    stages: &[(ShaderStageFlags, &EntryPointReflection)],
+    label: Option<&str>,
+) -> Result<PipelineLayoutInner, PipelineError> {
+    let mut bind_groups: Vec<Vec<BindGroupLayoutBinding>> = Vec::new();
+    let mut push_constant_range: Option<PushConstantRange> = None;
+
+    for (stage, entry_point) in stages.iter().cloned() {
+        for reflected in entry_point.bindings.iter() {
+            let binding_type = derived_binding_type(reflected)?;
+
+            let set = reflected.set as usize;
+            if bind_groups.len() <= set {
+                bind_groups.resize_with(set + 1, Vec::new);
+            }
+
+            let bindings = &mut bind_groups[set];
+            match bindings.iter_mut().find(|b| b.binding == reflected.binding) {
+                Some(binding) if binding.binding_type == binding_type => binding.visibility |= stage,
+                Some(binding) => {
+                    return Err(PipelineError::BindingTypeMismatch {
+                        stage,
+                        set: reflected.set,
+                        binding: reflected.binding,
+                        layout_type: binding.binding_type,
+                        shader_type: reflected.binding_type,
+                    });
+                }
+                None => bindings.push(BindGroupLayoutBinding {
+                    binding: reflected.binding,
+                    visibility: stage,
+                    binding_type,
+                }),
+            }
+        }
+
+        if let Some(ref push_constants) = entry_point.push_constants {
+            let range = push_constant_range.get_or_insert(PushConstantRange {
+                stages: ShaderStageFlags::NONE,
+                offset: 0,
+                size: 0,
+            });
+            range.stages |= stage;
+            range.size = range.size.max((push_constants.offset + push_constants.size) as usize);
+        }
+    }
+
+    let bind_group_layouts = bind_groups
+        .into_iter()
+        .map(|mut bindings| {
+            bindings.sort_by_key(|binding| binding.binding);
+            let descriptor = BindGroupLayoutDescriptor { bindings };
+            BindGroupLayoutInner::new(device.clone(), descriptor).map(Into::into)
+        })
+        .collect::<Result<Vec<BindGroupLayout>, Error>>()?;
+
+    let descriptor = PipelineLayoutDescriptor {
+        bind_group_layouts,
+        push_constant_ranges: push_constant_range.into_iter().collect(),
+        label,
+    };
+
+    Ok(PipelineLayoutInner::new(device.clone(), descriptor)?)
+}
+
+/// Checks that an explicit pipeline layout provides the bindings and push constants of a stage
+fn validate_pipeline_layout(
+    layout: &PipelineLayoutInner,
+    stage: ShaderStageFlags,
+    entry_point: &EntryPointReflection,
+) -> Result<(), PipelineError> {
+    for reflected in entry_point.bindings.iter() {
+        let layout_binding = layout
+            .bind_group_layouts
+            .get(reflected.set as usize)
+            .and_then(|bind_group_layout| {
+                bind_group_layout
+                    .inner
+                    .layout_bindings
+                    .iter()
+                    .find(|binding| binding.binding == reflected.binding)
+            })
+            .ok_or(PipelineError::MissingBinding {
+                stage,
+                set: reflected.set,
+                binding: reflected.binding,
+            })?;
+
+        if !is_compatible_binding_type(layout_binding.binding_type, reflected.binding_type) {
+            return Err(PipelineError::BindingTypeMismatch {
+                stage,
+                set: reflected.set,
+                binding: reflected.binding,
+                layout_type: layout_binding.binding_type,
+                shader_type: reflected.binding_type,
+            });
+        }
+
+        if !layout_binding.visibility.contains(stage) {
+            return Err(PipelineError::BindingNotVisible {
+                stage,
+                set: reflected.set,
+                binding: reflected.binding,
+            });
+        }
+    }
+
+    if let Some(ref push_constants) = entry_point.push_constants {
+        let offset = push_constants.offset as usize;
+        let size = push_constants.size as usize;
+        let covered = layout.push_constant_ranges.iter().any(|range| {
+            range.stages.contains(stage) && range.offset <= offset && range.offset + range.size >= offset + size
+        });
+        if !covered {
+            return Err(PipelineError::MissingPushConstantRange {
+                stage,
+                offset: push_constants.offset,
+                size: push_constants.size,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the vertex attributes, the interface between the stages and the color states
+fn validate_render_interface(
+    descriptor: &RenderPipelineDescriptor,
+    vertex: &EntryPointReflection,
+    fragment: &EntryPointReflection,
+) -> Result<(), PipelineError> {
+    for input in vertex.inputs.iter() {
+        let attribute = descriptor
+            .input_state
+            .vertex_buffers
+            .iter()
+            .flat_map(|vertex_buffer| vertex_buffer.attributes.iter())
+            .find(|attribute| attribute.shader_location == input.location)
+            .ok_or(PipelineError::MissingVertexAttribute {
+                location: input.location,
+            })?;
+
+        if vertex_format_scalar_type(attribute.format) != input.scalar_type {
+            return Err(PipelineError::VertexAttributeTypeMismatch {
+                location: input.location,
+                format: attribute.format,
+                shader_type: input.scalar_type,
+            });
+        }
+    }
+
+    for input in fragment.inputs.iter() {
+        let compatible = vertex.outputs.iter().any(|output| {
+            output.location == input.location
+                && output.scalar_type == input.scalar_type
+                && output.components >= input.components
+        });
+        if !compatible {
+            return Err(PipelineError::StageInterfaceMismatch {
+                location: input.location,
+            });
+        }
+    }
+
+    for output in fragment.outputs.iter() {
+        let color_state =
+            descriptor
+                .color_states
+                .get(output.location as usize)
+                .ok_or(PipelineError::MissingColorState {
+                    location: output.location,
+                })?;
+
+        if texture::scalar_type(color_state.format) != output.scalar_type {
+            return Err(PipelineError::ColorStateTypeMismatch {
+                location: output.location,
+                format: color_state.format,
+                shader_type: output.scalar_type,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 impl Into<PipelineLayout> for PipelineLayoutInner {
     fn into(self) -> PipelineLayout {
         PipelineLayout { inner: Arc::new(self) }
@@ -66,28 +432,64 @@ impl Drop for PipelineLayoutInner {
     }
 }
 
+impl PipelineLayout {
+    pub fn set_label(&self, label: &str) {
+        self.inner.label.set(&self.inner.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+}
+
 impl ComputePipelineInner {
-    pub fn new(device: Arc<DeviceInner>, descriptor: ComputePipelineDescriptor) -> Result<ComputePipelineInner, Error> {
-        // TODO: inspect push constants
+    pub fn new(
+        device: Arc<DeviceInner>,
+        descriptor: ComputePipelineDescriptor,
+    ) -> Result<ComputePipelineInner, PipelineError> {
+        if !device.queue.queue_flags.contains(vk::QueueFlags::COMPUTE) {
+            log::error!(
+                "compute pipelines require a device with compute support; label: {:?}",
+                descriptor.label
+            );
+            return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT).into());
+        }
+
+        let compute_entry_point = stage_entry_point(ShaderStageFlags::COMPUTE, &descriptor.compute_stage)?;
 
         let entry_point = CString::new(&*descriptor.compute_stage.entry_point).map_err(|e| {
-            log::error!("invalid entry point: {:?}", e);
+            log::error!("invalid entry point: {:?}, label: {:?}", e, descriptor.label);
             vk::Result::ERROR_VALIDATION_FAILED_EXT
         })?;
 
+        let layout = match descriptor.layout {
+            Some(ref layout) => {
+                validate_pipeline_layout(&layout.inner, ShaderStageFlags::COMPUTE, compute_entry_point)?;
+                layout.inner.clone()
+            }
+            None => {
+                let stages = &[(ShaderStageFlags::COMPUTE, compute_entry_point)];
+                Arc::new(derive_pipeline_layout(&device, stages, descriptor.label)?)
+            }
+        };
+
+        let specialization_data = SpecializationData::new(&descriptor.compute_stage);
+        let specialization_info = specialization_data.info();
+
         let create_info = vk::ComputePipelineCreateInfo {
-            layout: descriptor.layout.inner.handle,
+            layout: layout.handle,
             base_pipeline_handle: vk::Pipeline::null(),
             base_pipeline_index: -1,
             stage: vk::PipelineShaderStageCreateInfo::builder()
                 .name(entry_point.as_c_str())
                 .stage(vk::ShaderStageFlags::COMPUTE)
                 .module(descriptor.compute_stage.module.inner.handle)
+                .specialization_info(&specialization_info)
                 .build(),
             ..Default::default()
         };
 
-        let pipeline_cache = vk::PipelineCache::null();
+        let pipeline_cache = device.pipeline_cache;
         let mut handle = vk::Pipeline::null();
 
         unsafe {
@@ -101,9 +503,9 @@ impl ComputePipelineInner {
             )
         };
 
-        let layout = descriptor.layout.inner.clone();
+        let label = debug::Label::new(&device, handle, descriptor.label);
 
-        Ok(ComputePipelineInner { handle, layout })
+        Ok(ComputePipelineInner { handle, layout, label })
     }
 }
 
@@ -121,6 +523,28 @@ impl Drop for ComputePipelineInner {
     }
 }
 
+impl ComputePipeline {
+    /// Returns the bind group layout at `index` of the pipeline layout, which may have been derived
+    /// from the shader.
+    ///
+    /// Layouts can only be derived for bindings that `BindingType` can express. Storage textures,
+    /// combined texture samplers, uniform texel buffers, input attachments and arrays need an
+    /// explicit layout; deriving one fails with `PipelineError::UnsupportedBinding`.
+    pub fn get_bind_group_layout(&self, index: usize) -> Option<BindGroupLayout> {
+        self.inner.layout.bind_group_layouts.get(index).cloned()
+    }
+
+    pub fn set_label(&self, label: &str) {
+        self.inner
+            .label
+            .set(&self.inner.layout.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+}
+
 pub fn primitive_topology(primitive: PrimitiveTopology) -> vk::PrimitiveTopology {
     match primitive {
         PrimitiveTopology::LineList => vk::PrimitiveTopology::LINE_LIST,
@@ -241,6 +665,7 @@ pub fn disable_depth_stencil_test() -> DepthStencilStateDescriptor {
         },
         stencil_read_mask: 0,
         stencil_write_mask: 0,
+        depth_bounds: None,
     }
 }
 
@@ -254,9 +679,9 @@ pub fn depth_stencil_state_create_info(
         depth_test_enable: depth_test_enabled(&descriptor) as vk::Bool32,
         depth_write_enable: descriptor.depth_write_enabled as vk::Bool32,
         depth_compare_op: sampler::compare_op(descriptor.depth_compare),
-        depth_bounds_test_enable: vk::FALSE,
-        min_depth_bounds: 0.0,
-        max_depth_bounds: 1.0,
+        depth_bounds_test_enable: descriptor.depth_bounds.is_some() as vk::Bool32,
+        min_depth_bounds: descriptor.depth_bounds.map_or(0.0, |(min, _)| min),
+        max_depth_bounds: descriptor.depth_bounds.map_or(1.0, |(_, max)| max),
         stencil_test_enable: stencil_test_enabled(&descriptor) as vk::Bool32,
         front: vk::StencilOpState {
             fail_op: stencil_op(descriptor.stencil_front.fail_op),
@@ -286,19 +711,116 @@ pub fn rasterization_state_create_info(
         s_type: vk::StructureType::PIPELINE_RASTERIZATION_STATE_CREATE_INFO,
         p_next: std::ptr::null(),
         flags: vk::PipelineRasterizationStateCreateFlags::empty(),
-        depth_clamp_enable: vk::FALSE,
+        depth_clamp_enable: descriptor.depth_clamp as vk::Bool32,
         rasterizer_discard_enable: vk::FALSE,
-        polygon_mode: vk::PolygonMode::FILL,
+        polygon_mode: polygon_mode(descriptor.polygon_mode),
         cull_mode: cull_mode(descriptor.cull_mode),
         front_face: front_face(descriptor.front_face),
         depth_bias_enable: (descriptor.depth_bias != 0) as vk::Bool32,
         depth_bias_clamp: descriptor.depth_bias_clamp,
         depth_bias_slope_factor: descriptor.depth_bias_slope_scale,
         depth_bias_constant_factor: descriptor.depth_bias as f32,
-        line_width: 1.0,
+        line_width: descriptor.line_width,
     }
 }
 
+pub fn conservative_rasterization_mode(mode: ConservativeRasterizationMode) -> vk::ConservativeRasterizationModeEXT {
+    match mode {
+        ConservativeRasterizationMode::Disabled => vk::ConservativeRasterizationModeEXT::DISABLED,
+        ConservativeRasterizationMode::Overestimate => vk::ConservativeRasterizationModeEXT::OVERESTIMATE,
+        ConservativeRasterizationMode::Underestimate => vk::ConservativeRasterizationModeEXT::UNDERESTIMATE,
+    }
+}
+
+pub fn polygon_mode(mode: PolygonMode) -> vk::PolygonMode {
+    match mode {
+        PolygonMode::Fill => vk::PolygonMode::FILL,
+        PolygonMode::Line => vk::PolygonMode::LINE,
+        PolygonMode::Point => vk::PolygonMode::POINT,
+    }
+}
+
+/// Checks that the rasterization, depth stencil and multisample options are enabled on the device
+fn validate_fixed_function_state(device: &DeviceInner, descriptor: &RenderPipelineDescriptor) -> Result<(), Error> {
+    let extensions = &device.extensions;
+    let rasterization_state = &descriptor.rasterization_state;
+    let mut missing = Vec::new();
+    if rasterization_state.polygon_mode != PolygonMode::Fill && !extensions.fill_mode_non_solid {
+        missing.push("fill_mode_non_solid");
+    }
+    if rasterization_state.depth_clamp && !extensions.depth_clamp {
+        missing.push("depth_clamp");
+    }
+    if rasterization_state.line_width != 1.0 && !extensions.wide_lines {
+        missing.push("wide_lines");
+    }
+    let conservative_rasterization = rasterization_state.conservative_rasterization;
+    if conservative_rasterization != ConservativeRasterizationMode::Disabled && !extensions.conservative_rasterization {
+        missing.push("conservative_rasterization");
+    }
+    let depth_bounds = descriptor.depth_stencil_state.and_then(|state| state.depth_bounds);
+    if depth_bounds.is_some() && !extensions.depth_bounds {
+        missing.push("depth_bounds");
+    }
+    let multisample_state = &descriptor.multisample_state;
+    if multisample_state.min_sample_shading.is_some() && !extensions.sample_rate_shading {
+        missing.push("sample_rate_shading");
+    }
+    if !missing.is_empty() {
+        log::error!(
+            "render pipeline requires extensions that are not enabled: {:?}, label: {:?}",
+            missing,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    let [min_line_width, max_line_width] = device.adapter.physical_device_properties.limits.line_width_range;
+    if !(rasterization_state.line_width >= min_line_width && rasterization_state.line_width <= max_line_width) {
+        log::error!(
+            "line width {} is outside the supported range [{}, {}], label: {:?}",
+            rasterization_state.line_width,
+            min_line_width,
+            max_line_width,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    if multisample_state.alpha_to_coverage_enabled && descriptor.sample_count == 1 {
+        log::error!(
+            "alpha to coverage requires a sample count greater than 1, label: {:?}",
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    if let Some(min_sample_shading) = multisample_state.min_sample_shading {
+        if !(0.0..=1.0).contains(&min_sample_shading) {
+            log::error!(
+                "min sample shading {} must be between 0.0 and 1.0, label: {:?}",
+                min_sample_shading,
+                descriptor.label
+            );
+            return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+        }
+    }
+
+    if let Some((min, max)) = depth_bounds {
+        if !(0.0 <= min && min <= max && max <= 1.0) {
+            log::error!(
+                "depth bounds ({}, {}) must satisfy 0.0 <= min <= max <= 1.0, label: {:?}",
+                min,
+                max,
+                descriptor.label
+            );
+            return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+        }
+    }
+
+    Ok(())
+}
+
 pub fn cull_mode(mode: CullMode) -> vk::CullModeFlags {
     match mode {
         CullMode::Back => vk::CullModeFlags::BACK,
@@ -393,201 +915,284 @@ pub fn vertex_input_binding_description(descriptor: &VertexBufferDescriptor) ->
 }
 
 impl RenderPipelineInner {
-    pub fn new(device: Arc<DeviceInner>, descriptor: RenderPipelineDescriptor) -> Result<RenderPipelineInner, Error> {
-        // TODO: inspect push constants
-
-        let vertex_entry_point = CString::new(&*descriptor.vertex_stage.entry_point).map_err(|e| {
-            log::error!("invalid vertex entry point: {:?}", e);
-            vk::Result::ERROR_VALIDATION_FAILED_EXT
-        })?;
-
-        let fragment_entry_point = CString::new(&*descriptor.fragment_stage.entry_point).map_err(|e| {
-            log::error!("invalid fragment entry point: {:?}", e);
-            vk::Result::ERROR_VALIDATION_FAILED_EXT
-        })?;
-
-        let shader_stages_create_info = &[
-            vk::PipelineShaderStageCreateInfo {
-                stage: vk::ShaderStageFlags::VERTEX,
-                module: descriptor.vertex_stage.module.inner.handle,
-                p_name: vertex_entry_point.as_ptr(),
-                ..Default::default()
-            },
-            vk::PipelineShaderStageCreateInfo {
-                stage: vk::ShaderStageFlags::FRAGMENT,
-                module: descriptor.fragment_stage.module.inner.handle,
-                p_name: fragment_entry_point.as_ptr(),
-                ..Default::default()
-            },
-        ];
-
-        let input_assembly_state_create_info = vk::PipelineInputAssemblyStateCreateInfo {
-            topology: primitive_topology(descriptor.primitive_topology),
-            // Dawn notes that this must always be enabled because of Metal, but Vulkan forbids
-            // it for 'list' topologies.
-            primitive_restart_enable: match descriptor.primitive_topology {
-                PrimitiveTopology::LineStrip | PrimitiveTopology::TriangleStrip => vk::TRUE,
-                PrimitiveTopology::TriangleList | PrimitiveTopology::PointList | PrimitiveTopology::LineList => {
-                    vk::FALSE
-                }
-            },
-            ..Default::default()
-        };
-
-        let viewport = vk::Viewport {
-            x: 0.0,
-            y: 0.0,
-            width: 1.0,
-            height: 1.0,
-            min_depth: 0.0,
-            max_depth: 1.0,
-        };
-
-        let scissor = vk::Rect2D {
-            offset: vk::Offset2D { x: 0, y: 0 },
-            extent: vk::Extent2D { width: 1, height: 1 },
-        };
-
-        let viewport_state_create_info = vk::PipelineViewportStateCreateInfo::builder()
-            .scissors(&[scissor])
-            .viewports(&[viewport])
-            .build();
-
-        let multisample_state_create_info = vk::PipelineMultisampleStateCreateInfo::builder()
-            .rasterization_samples(render_pass::sample_count_flags(descriptor.sample_count)?)
-            .build();
-
-        let depth_stencil_state_create_info = depth_stencil_state_create_info(
-            descriptor
-                .depth_stencil_state
-                .unwrap_or_else(disable_depth_stencil_test),
-        );
-
-        let rasterization_state_create_info = rasterization_state_create_info(&descriptor.rasterization_state);
-
-        let color_blend_attachment_states: Vec<vk::PipelineColorBlendAttachmentState> = descriptor
-            .color_states
-            .iter()
-            .map(color_blend_attachment_state)
-            .collect();
-
-        let color_blend_state_create_info = vk::PipelineColorBlendStateCreateInfo {
-            logic_op_enable: vk::FALSE,
-            logic_op: vk::LogicOp::CLEAR,
-            p_attachments: color_blend_attachment_states.as_ptr(),
-            attachment_count: color_blend_attachment_states.len() as u32,
-            blend_constants: [0.0, 0.0, 0.0, 0.0], // dummy values
-            ..Default::default()
-        };
-
-        let vertex_attribute_descriptions: Vec<vk::VertexInputAttributeDescription> = descriptor
-            .input_state
-            .vertex_buffers
-            .iter()
-            .flat_map(|vb| vb.attributes.iter().map(move |a| (vb.input_slot, a)))
-            .map(|(input_slot, a)| vertex_input_attribute_description(input_slot, a))
-            .collect();
-
-        let vertex_binding_descriptions: Vec<vk::VertexInputBindingDescription> = descriptor
-            .input_state
-            .vertex_buffers
-            .iter()
-            .map(vertex_input_binding_description)
-            .collect();
-
-        let vertex_input_state_create_info = vk::PipelineVertexInputStateCreateInfo::builder()
-            .vertex_attribute_descriptions(&vertex_attribute_descriptions)
-            .vertex_binding_descriptions(&vertex_binding_descriptions)
-            .build();
-
-        let dynamic_states = &[
-            vk::DynamicState::VIEWPORT,
-            vk::DynamicState::SCISSOR,
-            vk::DynamicState::LINE_WIDTH,
-            vk::DynamicState::DEPTH_BIAS,
-            vk::DynamicState::BLEND_CONSTANTS,
-            vk::DynamicState::DEPTH_BOUNDS,
-            vk::DynamicState::STENCIL_REFERENCE,
-        ];
-
-        let dynamic_state_create_info = vk::PipelineDynamicStateCreateInfo::builder()
-            .dynamic_states(dynamic_states)
-            .build();
-
-        let mut query = RenderPassCacheQuery::new();
-
-        query.set_sample_count(descriptor.sample_count);
-
-        for color_state_info in descriptor.color_states.iter() {
-            query.add_color(ColorInfo {
-                load_op: LoadOp::Load,
-                format: color_state_info.format,
-                // TODO: Should has_resolve_target default to true when sample_count > 1?
-                // https://www.khronos.org/registry/vulkan/specs/1.1/html/chap7.html#renderpass-compatibility
-                // Dawn sets this to `false`, presumably because render passes are still considered compatible
-                // when they have differing numbers of attachments, as long as the corresponding attachments
-                // are compatible.
-                has_resolve_target: false,
-            });
+    pub fn new(
+        device: Arc<DeviceInner>,
+        descriptor: RenderPipelineDescriptor,
+    ) -> Result<RenderPipelineInner, PipelineError> {
+        if !device.queue.queue_flags.contains(vk::QueueFlags::GRAPHICS) {
+            log::error!(
+                "render pipelines require a device with graphics support; label: {:?}",
+                descriptor.label
+            );
+            return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT).into());
         }
 
-        if let Some(ref depth_stencil_state) = descriptor.depth_stencil_state {
-            query.set_depth_stencil(DepthStencilInfo {
-                format: depth_stencil_state.format,
-                depth_load_op: LoadOp::Load,
-                stencil_load_op: LoadOp::Load,
-            });
-        }
-
-        let render_pass = { device.state.lock().get_render_pass(query, &device)? };
-
-        let create_info = vk::GraphicsPipelineCreateInfo::builder()
-            .layout(descriptor.layout.inner.handle)
-            .render_pass(render_pass)
-            .stages(shader_stages_create_info)
-            .vertex_input_state(&vertex_input_state_create_info)
-            .input_assembly_state(&input_assembly_state_create_info)
-            .viewport_state(&viewport_state_create_info)
-            .rasterization_state(&rasterization_state_create_info)
-            .multisample_state(&multisample_state_create_info)
-            .depth_stencil_state(&depth_stencil_state_create_info)
-            .color_blend_state(&color_blend_state_create_info)
-            .dynamic_state(&dynamic_state_create_info)
-            .base_pipeline_handle(vk::Pipeline::null())
-            .base_pipeline_index(-1)
-            .build();
-
-        let pipeline_cache = vk::PipelineCache::null();
-
-        let mut handle = vk::Pipeline::null();
-
-        unsafe {
-            device.raw.fp_v1_0().create_graphics_pipelines(
-                device.raw.handle(),
-                pipeline_cache,
-                1,
-                &create_info,
-                std::ptr::null(),
-                &mut handle,
-            )
+        validate_fixed_function_state(&device, &descriptor)?;
+
+        let vertex_reflection = stage_entry_point(ShaderStageFlags::VERTEX, &descriptor.vertex_stage)?;
+        let fragment_reflection = stage_entry_point(ShaderStageFlags::FRAGMENT, &descriptor.fragment_stage)?;
+        validate_render_interface(&descriptor, vertex_reflection, fragment_reflection)?;
+
+        let layout = match descriptor.layout {
+            Some(ref layout) => {
+                validate_pipeline_layout(&layout.inner, ShaderStageFlags::VERTEX, vertex_reflection)?;
+                validate_pipeline_layout(&layout.inner, ShaderStageFlags::FRAGMENT, fragment_reflection)?;
+                layout.inner.clone()
+            }
+            None => {
+                let stages = &[
+                    (ShaderStageFlags::VERTEX, vertex_reflection),
+                    (ShaderStageFlags::FRAGMENT, fragment_reflection),
+                ];
+                Arc::new(derive_pipeline_layout(&device, stages, descriptor.label)?)
+            }
         };
 
-        let layout = descriptor.layout.inner.clone();
+        let handle = create_graphics_pipeline(&device, &descriptor, layout.handle)?;
+
+        let label = debug::Label::new(&device, handle, descriptor.label);
 
         Ok(RenderPipelineInner {
             handle,
             layout,
             index_format: descriptor.input_state.index_format,
+            label,
         })
     }
 }
 
+/// Creates the graphics pipeline of a validated descriptor, which must be compatible with `layout`
+pub fn create_graphics_pipeline(
+    device: &DeviceInner,
+    descriptor: &RenderPipelineDescriptor,
+    layout: vk::PipelineLayout,
+) -> Result<vk::Pipeline, Error> {
+    let vertex_entry_point = CString::new(&*descriptor.vertex_stage.entry_point).map_err(|e| {
+        log::error!("invalid vertex entry point: {:?}, label: {:?}", e, descriptor.label);
+        vk::Result::ERROR_VALIDATION_FAILED_EXT
+    })?;
+
+    let fragment_entry_point = CString::new(&*descriptor.fragment_stage.entry_point).map_err(|e| {
+        log::error!("invalid fragment entry point: {:?}, label: {:?}", e, descriptor.label);
+        vk::Result::ERROR_VALIDATION_FAILED_EXT
+    })?;
+
+    let vertex_specialization_data = SpecializationData::new(&descriptor.vertex_stage);
+    let vertex_specialization_info = vertex_specialization_data.info();
+    let fragment_specialization_data = SpecializationData::new(&descriptor.fragment_stage);
+    let fragment_specialization_info = fragment_specialization_data.info();
+
+    let shader_stages_create_info = &[
+        vk::PipelineShaderStageCreateInfo {
+            stage: vk::ShaderStageFlags::VERTEX,
+            module: descriptor.vertex_stage.module.inner.handle,
+            p_name: vertex_entry_point.as_ptr(),
+            p_specialization_info: &vertex_specialization_info,
+            ..Default::default()
+        },
+        vk::PipelineShaderStageCreateInfo {
+            stage: vk::ShaderStageFlags::FRAGMENT,
+            module: descriptor.fragment_stage.module.inner.handle,
+            p_name: fragment_entry_point.as_ptr(),
+            p_specialization_info: &fragment_specialization_info,
+            ..Default::default()
+        },
+    ];
+
+    let input_assembly_state_create_info = vk::PipelineInputAssemblyStateCreateInfo {
+        topology: primitive_topology(descriptor.primitive_topology),
+        // Dawn notes that this must always be enabled because of Metal, but Vulkan forbids
+        // it for 'list' topologies.
+        primitive_restart_enable: match descriptor.primitive_topology {
+            PrimitiveTopology::LineStrip | PrimitiveTopology::TriangleStrip => vk::TRUE,
+            PrimitiveTopology::TriangleList | PrimitiveTopology::PointList | PrimitiveTopology::LineList => vk::FALSE,
+        },
+        ..Default::default()
+    };
+
+    let viewport = vk::Viewport {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+        min_depth: 0.0,
+        max_depth: 1.0,
+    };
+
+    let scissor = vk::Rect2D {
+        offset: vk::Offset2D { x: 0, y: 0 },
+        extent: vk::Extent2D { width: 1, height: 1 },
+    };
+
+    let viewport_state_create_info = vk::PipelineViewportStateCreateInfo::builder()
+        .scissors(&[scissor])
+        .viewports(&[viewport])
+        .build();
+
+    let multisample_state = &descriptor.multisample_state;
+    // the mask has one word per 32 samples
+    let sample_mask = [multisample_state.sample_mask, !0];
+    let multisample_state_create_info = vk::PipelineMultisampleStateCreateInfo {
+        rasterization_samples: render_pass::sample_count_flags(descriptor.sample_count)?,
+        sample_shading_enable: multisample_state.min_sample_shading.is_some() as vk::Bool32,
+        min_sample_shading: multisample_state.min_sample_shading.unwrap_or(0.0),
+        p_sample_mask: sample_mask.as_ptr(),
+        alpha_to_coverage_enable: multisample_state.alpha_to_coverage_enabled as vk::Bool32,
+        ..Default::default()
+    };
+
+    let depth_stencil_state_create_info = depth_stencil_state_create_info(
+        descriptor
+            .depth_stencil_state
+            .unwrap_or_else(disable_depth_stencil_test),
+    );
+
+    let mut rasterization_state_create_info = rasterization_state_create_info(&descriptor.rasterization_state);
+
+    let conservative_state_create_info = vk::PipelineRasterizationConservativeStateCreateInfoEXT::builder()
+        .conservative_rasterization_mode(conservative_rasterization_mode(
+            descriptor.rasterization_state.conservative_rasterization,
+        ))
+        .build();
+
+    if descriptor.rasterization_state.conservative_rasterization != ConservativeRasterizationMode::Disabled {
+        rasterization_state_create_info.p_next = &conservative_state_create_info as *const _ as *const c_void;
+    }
+
+    let color_blend_attachment_states: Vec<vk::PipelineColorBlendAttachmentState> = descriptor
+        .color_states
+        .iter()
+        .map(color_blend_attachment_state)
+        .collect();
+
+    let color_blend_state_create_info = vk::PipelineColorBlendStateCreateInfo {
+        logic_op_enable: vk::FALSE,
+        logic_op: vk::LogicOp::CLEAR,
+        p_attachments: color_blend_attachment_states.as_ptr(),
+        attachment_count: color_blend_attachment_states.len() as u32,
+        blend_constants: [0.0, 0.0, 0.0, 0.0], // dummy values
+        ..Default::default()
+    };
+
+    let vertex_attribute_descriptions: Vec<vk::VertexInputAttributeDescription> = descriptor
+        .input_state
+        .vertex_buffers
+        .iter()
+        .flat_map(|vb| vb.attributes.iter().map(move |a| (vb.input_slot, a)))
+        .map(|(input_slot, a)| vertex_input_attribute_description(input_slot, a))
+        .collect();
+
+    let vertex_binding_descriptions: Vec<vk::VertexInputBindingDescription> = descriptor
+        .input_state
+        .vertex_buffers
+        .iter()
+        .map(vertex_input_binding_description)
+        .collect();
+
+    let vertex_input_state_create_info = vk::PipelineVertexInputStateCreateInfo::builder()
+        .vertex_attribute_descriptions(&vertex_attribute_descriptions)
+        .vertex_binding_descriptions(&vertex_binding_descriptions)
+        .build();
+
+    let dynamic_states = &[
+        vk::DynamicState::VIEWPORT,
+        vk::DynamicState::SCISSOR,
+        vk::DynamicState::BLEND_CONSTANTS,
+        vk::DynamicState::STENCIL_REFERENCE,
+    ];
+
+    let dynamic_state_create_info = vk::PipelineDynamicStateCreateInfo::builder()
+        .dynamic_states(dynamic_states)
+        .build();
+
+    let mut query = RenderPassCacheQuery::new();
+
+    query.set_sample_count(descriptor.sample_count);
+
+    for color_state_info in descriptor.color_states.iter() {
+        query.add_color(ColorInfo {
+            load_op: LoadOp::Load,
+            format: color_state_info.format,
+            // TODO: Should has_resolve_target default to true when sample_count > 1?
+            // https://www.khronos.org/registry/vulkan/specs/1.1/html/chap7.html#renderpass-compatibility
+            // Dawn sets this to `false`, presumably because render passes are still considered compatible
+            // when they have differing numbers of attachments, as long as the corresponding attachments
+            // are compatible.
+            has_resolve_target: false,
+        });
+    }
+
+    if let Some(ref depth_stencil_state) = descriptor.depth_stencil_state {
+        query.set_depth_stencil(DepthStencilInfo {
+            format: depth_stencil_state.format,
+            depth_load_op: LoadOp::Load,
+            stencil_load_op: LoadOp::Load,
+        });
+    }
+
+    let render_pass = { device.state.lock().get_render_pass(query, device)? };
+
+    let create_info = vk::GraphicsPipelineCreateInfo::builder()
+        .layout(layout)
+        .render_pass(render_pass)
+        .stages(shader_stages_create_info)
+        .vertex_input_state(&vertex_input_state_create_info)
+        .input_assembly_state(&input_assembly_state_create_info)
+        .viewport_state(&viewport_state_create_info)
+        .rasterization_state(&rasterization_state_create_info)
+        .multisample_state(&multisample_state_create_info)
+        .depth_stencil_state(&depth_stencil_state_create_info)
+        .color_blend_state(&color_blend_state_create_info)
+        .dynamic_state(&dynamic_state_create_info)
+        .base_pipeline_handle(vk::Pipeline::null())
+        .base_pipeline_index(-1)
+        .build();
+
+    let pipeline_cache = device.pipeline_cache;
+
+    let mut handle = vk::Pipeline::null();
+
+    unsafe {
+        device.raw.fp_v1_0().create_graphics_pipelines(
+            device.raw.handle(),
+            pipeline_cache,
+            1,
+            &create_info,
+            std::ptr::null(),
+            &mut handle,
+        )
+    };
+
+    Ok(handle)
+}
+
 impl Into<RenderPipeline> for RenderPipelineInner {
     fn into(self) -> RenderPipeline {
         RenderPipeline { inner: Arc::new(self) }
     }
 }
 
+impl RenderPipeline {
+    /// Returns the bind group layout at `index` of the pipeline layout, which may have been derived
+    /// from the shaders.
+    ///
+    /// Layouts can only be derived for bindings that `BindingType` can express, see
+    /// `ComputePipeline::get_bind_group_layout`.
+    pub fn get_bind_group_layout(&self, index: usize) -> Option<BindGroupLayout> {
+        self.inner.layout.bind_group_layouts.get(index).cloned()
+    }
+
+    pub fn set_label(&self, label: &str) {
+        self.inner
+            .label
+            .set(&self.inner.layout.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+}
+
 impl Drop for RenderPipelineInner {
     fn drop(&mut self) {
         let mut state = self.layout.device.state.lock();
@@ -596,3 +1201,51 @@ impl Drop for RenderPipelineInner {
         // TODO: What about the render pass?
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::derived_binding_type;
+    use crate::{BindingReflection, BindingType, PipelineError, ShaderBindingType};
+
+    #[test]
+    fn derive_binding_types() {
+        let reflected = |binding_type, count| BindingReflection {
+            set: 1,
+            binding: 2,
+            binding_type,
+            count,
+            name: None,
+        };
+        assert_eq!(
+            Ok(BindingType::SampledTexture),
+            derived_binding_type(&reflected(ShaderBindingType::SampledTexture, 1))
+        );
+        for &shader_type in [
+            ShaderBindingType::StorageTexture,
+            ShaderBindingType::CombinedTextureSampler,
+            ShaderBindingType::UniformTexelBuffer,
+            ShaderBindingType::InputAttachment,
+        ]
+        .iter()
+        {
+            assert_eq!(
+                Err(PipelineError::UnsupportedBinding {
+                    set: 1,
+                    binding: 2,
+                    shader_type,
+                    count: 1,
+                }),
+                derived_binding_type(&reflected(shader_type, 1))
+            );
+        }
+        assert_eq!(
+            Err(PipelineError::UnsupportedBinding {
+                set: 1,
+                binding: 2,
+                shader_type: ShaderBindingType::UniformBuffer,
+                count: 4,
+            }),
+            derived_binding_type(&reflected(ShaderBindingType::UniformBuffer, 4))
+        );
+    }
+}
@@ -1,17 +1,72 @@
 use ash::vk;
 
+use crate::imp::pass_resource_usage::QueueResource;
 use crate::imp::FenceInner;
-use crate::{CommandBuffer, Error, Fence, Queue, SwapchainError, SwapchainImage};
+use crate::{CommandBuffer, Error, Fence, Queue, QueueType, SwapchainError, SwapchainImage};
+
+/// Removes the pipeline stages that are not supported by a queue with `queue_flags`
+pub fn supported_stages(queue_flags: vk::QueueFlags, stages: vk::PipelineStageFlags) -> vk::PipelineStageFlags {
+    let mut stages = stages;
+    if !queue_flags.contains(vk::QueueFlags::GRAPHICS) {
+        stages &= !(vk::PipelineStageFlags::VERTEX_INPUT
+            | vk::PipelineStageFlags::VERTEX_SHADER
+            | vk::PipelineStageFlags::TESSELLATION_CONTROL_SHADER
+            | vk::PipelineStageFlags::TESSELLATION_EVALUATION_SHADER
+            | vk::PipelineStageFlags::GEOMETRY_SHADER
+            | vk::PipelineStageFlags::FRAGMENT_SHADER
+            | vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS
+            | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS
+            | vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+            | vk::PipelineStageFlags::ALL_GRAPHICS);
+    }
+    if !queue_flags.intersects(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE) {
+        stages &= !(vk::PipelineStageFlags::DRAW_INDIRECT | vk::PipelineStageFlags::COMPUTE_SHADER);
+    }
+    if stages.is_empty() {
+        vk::PipelineStageFlags::ALL_COMMANDS
+    } else {
+        stages
+    }
+}
+
+/// Removes the access types that are not supported by a queue with `queue_flags`
+pub fn supported_access(queue_flags: vk::QueueFlags, access: vk::AccessFlags) -> vk::AccessFlags {
+    let mut access = access;
+    if !queue_flags.contains(vk::QueueFlags::GRAPHICS) {
+        access &= !(vk::AccessFlags::INDEX_READ
+            | vk::AccessFlags::VERTEX_ATTRIBUTE_READ
+            | vk::AccessFlags::INPUT_ATTACHMENT_READ
+            | vk::AccessFlags::COLOR_ATTACHMENT_READ
+            | vk::AccessFlags::COLOR_ATTACHMENT_WRITE
+            | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ
+            | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE);
+    }
+    if !queue_flags.intersects(vk::QueueFlags::GRAPHICS | vk::QueueFlags::COMPUTE) {
+        access &= !(vk::AccessFlags::INDIRECT_COMMAND_READ
+            | vk::AccessFlags::UNIFORM_READ
+            | vk::AccessFlags::SHADER_READ
+            | vk::AccessFlags::SHADER_WRITE);
+    }
+    access
+}
 
 impl Queue {
+    /// The type of the queue. This is the type of the device's main queue (see
+    /// `DeviceDescriptor::queue_type`) when the requested queue type was not available as a dedicated queue.
+    pub fn queue_type(&self) -> QueueType {
+        self.inner.queue.queue_type
+    }
+
     pub fn present(&self, frame: SwapchainImage) -> Result<(), SwapchainError> {
         {
             let device = &frame.swapchain.device;
             let mut state = frame.swapchain.device.state.lock();
             let command_buffer = state.get_pending_command_buffer(&device)?;
             let texture = &frame.swapchain.textures[frame.image_index as usize];
+            let resources = [QueueResource::Texture(texture.clone())];
+            state.transfer_ownership(device, &resources, &device.queue, command_buffer)?;
             texture.transition_usage_now(command_buffer, texture.descriptor.usage, None)?;
-            state.submit_pending_commands(&frame.swapchain.device, &self.inner.queue)?;
+            state.submit_pending_commands(&frame.swapchain.device)?;
 
             // these should always be empty after pending commands were submitted
             debug_assert_eq!(0, state.get_wait_semaphores().len());
@@ -46,19 +101,37 @@ impl Queue {
         device.tick()?;
 
         if !command_buffers.is_empty() {
-            let mut state = self.inner.device.state.lock();
-            let vk_command_buffer = state.get_pending_command_buffer(&device)?;
-
+            let queue = &self.inner.queue;
             for command_buffer in command_buffers.iter() {
-                command_buffer.inner.record_commands(vk_command_buffer, &mut state)?;
+                command_buffer.inner.validate_queue(queue)?;
             }
 
-            state.submit_pending_commands(&device, &self.inner.queue)
+            let mut state = self.inner.device.state.lock();
+            state.submit_to_queue(&device, queue, |vk_command_buffer, state| {
+                for command_buffer in command_buffers.iter() {
+                    let resources = command_buffer.inner.queue_resources();
+                    state.transfer_ownership(&device, &resources, queue, vk_command_buffer)?;
+                    command_buffer.inner.record_commands(vk_command_buffer, state)?;
+                }
+                Ok(())
+            })
         } else {
             Ok(())
         }
     }
 
+    /// Registers a callback that is called once the work submitted to the device so far has completed.
+    ///
+    /// Callbacks are called from `Device::poll` and the other functions that reclaim completed work,
+    /// e.g. `Queue::submit`.
+    pub fn on_submitted_work_done<F>(&self, callback: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let mut state = self.inner.device.state.lock();
+        state.add_work_done_callback(Box::new(callback));
+    }
+
     /// Creates a fence.
     ///
     /// Waiting for the fence to be signaled guarantees that all command buffers submitted
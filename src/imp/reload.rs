@@ -0,0 +1,257 @@
+use crate::imp::debug::WithoutLabel;
+use crate::imp::{
+    ComputePipelineInner, DeviceInner, ReloadableComputePipelineInner, ReloadableRenderPipelineInner,
+    RenderPipelineInner, ShaderModuleInner,
+};
+use crate::{
+    ComputePipeline, ComputePipelineDescriptor, Error, PipelineError, PipelineStageDescriptor,
+    ReloadableComputePipeline, ReloadableComputePipelineDescriptor, ReloadableRenderPipeline,
+    ReloadableRenderPipelineDescriptor, RenderPipeline, RenderPipelineDescriptor, ShaderStageFlags,
+};
+
+use parking_lot::Mutex;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Identifies a version of a file. The length is compared as well, since modification times can be
+/// too coarse to tell apart writes in quick succession.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+fn stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+#[derive(Debug)]
+struct WatchedShader {
+    path: PathBuf,
+    stage: ShaderStageFlags,
+    stamp: Option<FileStamp>,
+}
+
+/// The last good pipeline, and the descriptor it is rebuilt from when a watched shader changes
+#[derive(Debug)]
+pub struct ReloadState<D, P> {
+    descriptor: D,
+    label: Option<String>,
+    pipeline: P,
+    shaders: Vec<WatchedShader>,
+    /// The stamps of the shaders when the last rebuild failed, so that each failure is reported once
+    failed_stamps: Option<Vec<Option<FileStamp>>>,
+}
+
+impl<D: Clone, P: Clone> ReloadState<D, P> {
+    fn new(
+        descriptor: D,
+        label: Option<&str>,
+        pipeline: P,
+        shaders: &[(ShaderStageFlags, &Path)],
+    ) -> ReloadState<D, P> {
+        let shaders = shaders
+            .iter()
+            .map(|&(stage, path)| WatchedShader {
+                path: path.to_owned(),
+                stage,
+                stamp: stamp(path),
+            })
+            .collect();
+        ReloadState {
+            descriptor,
+            label: label.map(String::from),
+            pipeline,
+            shaders,
+            failed_stamps: None,
+        }
+    }
+
+    /// Compiles the changed shaders and the pipeline without holding the lock. The stamps are only
+    /// updated once the pipeline was rebuilt, so a shader that compiled is reloaded again together
+    /// with the one that failed.
+    fn reload<F>(
+        state: &Mutex<ReloadState<D, P>>,
+        device: &Arc<DeviceInner>,
+        stage_descriptor: fn(&mut D, ShaderStageFlags) -> &mut PipelineStageDescriptor,
+        create_pipeline: F,
+    ) -> Result<bool, Error>
+    where
+        F: FnOnce(D, Option<&str>) -> Result<P, PipelineError>,
+    {
+        let (stamps, changed, mut descriptor, label) = {
+            let state = state.lock();
+            let stamps: Vec<_> = state.shaders.iter().map(|shader| stamp(&shader.path)).collect();
+            if state.failed_stamps.as_ref() == Some(&stamps) {
+                return Ok(false);
+            }
+            let changed: Vec<_> = state
+                .shaders
+                .iter()
+                .zip(stamps.iter())
+                .filter(|(shader, stamp)| shader.stamp != **stamp)
+                .map(|(shader, _)| (shader.path.clone(), shader.stage))
+                .collect();
+            if changed.is_empty() {
+                return Ok(false);
+            }
+            (stamps, changed, state.descriptor.clone(), state.label.clone())
+        };
+
+        let result = changed
+            .iter()
+            .try_for_each(|(path, stage)| {
+                let module = ShaderModuleInner::from_file(device.clone(), path, *stage).map_err(|e| {
+                    log::error!("failed to reload shader {}: {}", path.display(), e);
+                    e
+                })?;
+                stage_descriptor(&mut descriptor, *stage).module = module.into();
+                Ok(())
+            })
+            .and_then(|()| {
+                create_pipeline(descriptor.clone(), label.as_deref()).map_err(|e| {
+                    log::error!("failed to rebuild pipeline, keeping the previous one: {}", e);
+                    Error::from(e)
+                })
+            });
+
+        let mut state = state.lock();
+        match result {
+            Ok(pipeline) => {
+                for (shader, stamp) in state.shaders.iter_mut().zip(stamps) {
+                    shader.stamp = stamp;
+                }
+                state.failed_stamps = None;
+                state.descriptor = descriptor;
+                state.pipeline = pipeline;
+                Ok(true)
+            }
+            Err(e) => {
+                state.failed_stamps = Some(stamps);
+                Err(e)
+            }
+        }
+    }
+}
+
+fn compute_stage_descriptor<'a>(
+    descriptor: &'a mut ComputePipelineDescriptor<'static>,
+    _stage: ShaderStageFlags,
+) -> &'a mut PipelineStageDescriptor {
+    &mut descriptor.compute_stage
+}
+
+fn render_stage_descriptor<'a>(
+    descriptor: &'a mut RenderPipelineDescriptor<'static>,
+    stage: ShaderStageFlags,
+) -> &'a mut PipelineStageDescriptor {
+    if stage == ShaderStageFlags::VERTEX {
+        &mut descriptor.vertex_stage
+    } else {
+        &mut descriptor.fragment_stage
+    }
+}
+
+impl ReloadableComputePipelineInner {
+    pub fn new(
+        device: Arc<DeviceInner>,
+        descriptor: ReloadableComputePipelineDescriptor,
+    ) -> Result<ReloadableComputePipelineInner, PipelineError> {
+        let pipeline: ComputePipeline = ComputePipelineInner::new(device.clone(), descriptor.pipeline.clone())?.into();
+        let shaders = [(ShaderStageFlags::COMPUTE, descriptor.compute_shader.as_path())];
+        let label = descriptor.pipeline.label;
+        let state = ReloadState::new(descriptor.pipeline.without_label(), label, pipeline, &shaders);
+        Ok(ReloadableComputePipelineInner {
+            device,
+            state: state.into(),
+        })
+    }
+}
+
+impl Into<ReloadableComputePipeline> for ReloadableComputePipelineInner {
+    fn into(self) -> ReloadableComputePipeline {
+        ReloadableComputePipeline { inner: Arc::new(self) }
+    }
+}
+
+impl ReloadableComputePipeline {
+    /// The most recent pipeline that was built successfully
+    pub fn pipeline(&self) -> ComputePipeline {
+        self.inner.state.lock().pipeline.clone()
+    }
+
+    /// Rebuilds the shader module and the pipeline if the shader file changed since the last call.
+    ///
+    /// Call this at a frame boundary. Returns `Ok(true)` when a new pipeline was swapped in. When
+    /// the shader or the pipeline fails to build, the error is returned and the previous pipeline is kept.
+    pub fn reload(&self) -> Result<bool, Error> {
+        let device = &self.inner.device;
+        ReloadState::reload(
+            &self.inner.state,
+            device,
+            compute_stage_descriptor,
+            |descriptor, label| {
+                Ok(
+                    ComputePipelineInner::new(device.clone(), ComputePipelineDescriptor { label, ..descriptor })?
+                        .into(),
+                )
+            },
+        )
+    }
+}
+
+impl ReloadableRenderPipelineInner {
+    pub fn new(
+        device: Arc<DeviceInner>,
+        descriptor: ReloadableRenderPipelineDescriptor,
+    ) -> Result<ReloadableRenderPipelineInner, PipelineError> {
+        let pipeline: RenderPipeline = RenderPipelineInner::new(device.clone(), descriptor.pipeline.clone())?.into();
+        let shaders = [
+            (ShaderStageFlags::VERTEX, descriptor.vertex_shader.as_path()),
+            (ShaderStageFlags::FRAGMENT, descriptor.fragment_shader.as_path()),
+        ];
+        let label = descriptor.pipeline.label;
+        let state = ReloadState::new(descriptor.pipeline.without_label(), label, pipeline, &shaders);
+        Ok(ReloadableRenderPipelineInner {
+            device,
+            state: state.into(),
+        })
+    }
+}
+
+impl Into<ReloadableRenderPipeline> for ReloadableRenderPipelineInner {
+    fn into(self) -> ReloadableRenderPipeline {
+        ReloadableRenderPipeline { inner: Arc::new(self) }
+    }
+}
+
+impl ReloadableRenderPipeline {
+    /// The most recent pipeline that was built successfully
+    pub fn pipeline(&self) -> RenderPipeline {
+        self.inner.state.lock().pipeline.clone()
+    }
+
+    /// Rebuilds the changed shader modules and the pipeline if either shader file changed since the
+    /// last call.
+    ///
+    /// Call this at a frame boundary. Returns `Ok(true)` when a new pipeline was swapped in. When
+    /// a shader or the pipeline fails to build, the error is returned and the previous pipeline is kept.
+    pub fn reload(&self) -> Result<bool, Error> {
+        let device = &self.inner.device;
+        ReloadState::reload(
+            &self.inner.state,
+            device,
+            render_stage_descriptor,
+            |descriptor, label| {
+                Ok(RenderPipelineInner::new(device.clone(), RenderPipelineDescriptor { label, ..descriptor })?.into())
+            },
+        )
+    }
+}
@@ -6,7 +6,7 @@ use smallvec::SmallVec;
 use crate::imp::texture;
 use crate::{Error, LoadOp, TextureFormat};
 
-use crate::imp::DeviceInner;
+use crate::imp::{AdapterInner, DeviceInner};
 
 use std::collections::HashMap;
 use std::ptr;
@@ -132,12 +132,13 @@ pub fn depth_stencil_attachment_reference(attachment: u32) -> vk::AttachmentRefe
 }
 
 pub fn depth_stencil_attachment_description(
+    adapter: &AdapterInner,
     depth_stencil_info: &DepthStencilInfo,
     sample_count: vk::SampleCountFlags,
 ) -> vk::AttachmentDescription {
     vk::AttachmentDescription {
         flags: vk::AttachmentDescriptionFlags::empty(),
-        format: texture::image_format(depth_stencil_info.format),
+        format: adapter.image_format(depth_stencil_info.format),
         samples: sample_count,
         load_op: attachment_load_op(depth_stencil_info.depth_load_op),
         store_op: vk::AttachmentStoreOp::STORE,
@@ -218,7 +219,11 @@ impl RenderPassCache {
         }
 
         if let Some(ref depth_stencil_info) = query.depth_stencil {
-            attachment_descriptions.push(depth_stencil_attachment_description(depth_stencil_info, sample_count));
+            attachment_descriptions.push(depth_stencil_attachment_description(
+                &device.adapter,
+                depth_stencil_info,
+                sample_count,
+            ));
         }
 
         let mut resolve_attachment_count = 0;
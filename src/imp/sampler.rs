@@ -1,8 +1,9 @@
 use ash::version::DeviceV1_0;
 use ash::vk;
 
+use crate::imp::debug::WithoutLabel;
 use crate::imp::fenced_deleter::DeleteWhenUnused;
-use crate::imp::{DeviceInner, SamplerInner};
+use crate::imp::{debug, DeviceInner, SamplerInner};
 use crate::{AddressMode, CompareFunction, Error, FilterMode, Sampler, SamplerDescriptor};
 
 use std::sync::Arc;
@@ -44,6 +45,20 @@ pub fn compare_op(func: CompareFunction) -> vk::CompareOp {
 
 impl SamplerInner {
     pub fn new(device: Arc<DeviceInner>, descriptor: SamplerDescriptor) -> Result<SamplerInner, Error> {
+        let anisotropy_enable = descriptor.max_anisotropy > 1.0;
+        if anisotropy_enable && !device.extensions.sampler_anisotropy {
+            log::error!(
+                "sampler max_anisotropy ({}) requires the sampler_anisotropy extension (label: {:?})",
+                descriptor.max_anisotropy,
+                descriptor.label
+            );
+            return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+        }
+        let max_anisotropy = descriptor
+            .max_anisotropy
+            .min(device.adapter.physical_device_properties.limits.max_sampler_anisotropy)
+            .max(1.0);
+
         let create_info = vk::SamplerCreateInfo {
             address_mode_u: address_mode(descriptor.address_mode_u),
             address_mode_v: address_mode(descriptor.address_mode_v),
@@ -52,9 +67,8 @@ impl SamplerInner {
             min_filter: filter_mode(descriptor.min_filter),
             mipmap_mode: mipmap_mode(descriptor.mipmap_filter),
             mip_lod_bias: 0.0,
-            // TODO: Inspect device features/properties to set anisotropy values
-            anisotropy_enable: vk::FALSE,
-            max_anisotropy: 1.0,
+            anisotropy_enable: if anisotropy_enable { vk::TRUE } else { vk::FALSE },
+            max_anisotropy,
             compare_op: compare_op(descriptor.compare_function),
             compare_enable: if descriptor.compare_function == CompareFunction::Never {
                 vk::FALSE
@@ -70,10 +84,13 @@ impl SamplerInner {
 
         let handle = unsafe { device.raw.create_sampler(&create_info, None)? };
 
+        let label = debug::Label::new(&device, handle, descriptor.label);
+
         Ok(SamplerInner {
             handle,
             device,
-            descriptor,
+            descriptor: descriptor.without_label(),
+            label,
         })
     }
 }
@@ -91,3 +108,13 @@ impl Into<Sampler> for SamplerInner {
         Sampler { inner: Arc::new(self) }
     }
 }
+
+impl Sampler {
+    pub fn set_label(&self, label: &str) {
+        self.inner.label.set(&self.inner.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+}
@@ -2,16 +2,17 @@ use ash::version::DeviceV1_0;
 use ash::vk;
 
 use crate::imp::fenced_deleter::DeleteWhenUnused;
-use crate::imp::{DeviceInner, ShaderModuleInner};
-use crate::{Error, ShaderModule, ShaderModuleDescriptor};
+use crate::imp::{debug, DeviceInner, ShaderModuleInner};
+use crate::{Error, ShaderModule, ShaderModuleDescriptor, ShaderReflection, ShaderStageFlags};
 
+use std::fs;
+use std::path::Path;
 use std::sync::Arc;
 use std::{mem, ptr};
 
 impl ShaderModuleInner {
     pub fn new(device: Arc<DeviceInner>, descriptor: ShaderModuleDescriptor) -> Result<ShaderModuleInner, Error> {
-        // TODO: Use spirv-cross to reflect binding and attribute info so we can determine
-        //       when a shader module is compatible with a given pipeline layout
+        let reflection = ShaderReflection::new(descriptor.code)?;
 
         // Copy the code to a temp buffer to guarantee alignment and zero padding
         let byte_count = descriptor.code.len();
@@ -31,7 +32,54 @@ impl ShaderModuleInner {
 
         let handle = unsafe { device.raw.create_shader_module(&create_info, None)? };
 
-        Ok(ShaderModuleInner { handle, device })
+        let label = debug::Label::new(&device, handle, descriptor.label);
+
+        Ok(ShaderModuleInner {
+            handle,
+            device,
+            label,
+            reflection,
+        })
+    }
+
+    /// Loads a `.spv` file, or compiles a `.wgsl` or GLSL file with the `naga` feature. `stage` is only
+    /// used by GLSL sources.
+    pub fn from_file(
+        device: Arc<DeviceInner>,
+        path: &Path,
+        stage: ShaderStageFlags,
+    ) -> Result<ShaderModuleInner, Error> {
+        let label = path.to_str();
+        let read_error = |e| Error::from(format!("failed to read shader file {}: {}", path.display(), e));
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("spv") => {
+                let code = fs::read(path).map_err(read_error)?;
+                ShaderModuleInner::new(device, ShaderModuleDescriptor { code: &code, label })
+            }
+            #[cfg(feature = "naga")]
+            extension => {
+                let source = fs::read_to_string(path).map_err(read_error)?;
+                let language = match extension {
+                    Some("wgsl") => crate::ShaderLanguage::Wgsl,
+                    _ => crate::ShaderLanguage::Glsl,
+                };
+                let code = crate::imp::shader_compiler::compile(&crate::ShaderSourceDescriptor {
+                    source: &source,
+                    language,
+                    stage,
+                    label,
+                })?;
+                ShaderModuleInner::new(device, ShaderModuleDescriptor { code: &code, label })
+            }
+            #[cfg(not(feature = "naga"))]
+            _ => {
+                let _ = stage;
+                Err(Error::from(format!(
+                    "shader file {} is not SPIR-V; compiling GLSL and WGSL requires the naga feature",
+                    path.display()
+                )))
+            }
+        }
     }
 }
 
@@ -48,3 +96,17 @@ impl Drop for ShaderModuleInner {
         state.get_fenced_deleter().delete_when_unused(self.handle, serial);
     }
 }
+
+impl ShaderModule {
+    pub fn set_label(&self, label: &str) {
+        self.inner.label.set(&self.inner.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+
+    pub fn reflection(&self) -> &ShaderReflection {
+        &self.inner.reflection
+    }
+}
@@ -0,0 +1,203 @@
+//! Compiles GLSL and WGSL sources to SPIR-V with naga
+
+use naga::back::spv;
+use naga::front::{glsl, wgsl};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use naga::SourceLocation;
+
+use crate::{Error, ShaderLanguage, ShaderSourceDescriptor, ShaderStageFlags};
+
+fn compile_error<I>(descriptor: &ShaderSourceDescriptor, diagnostics: I) -> Error
+where
+    I: IntoIterator<Item = (Option<SourceLocation>, String)>,
+{
+    let mut message = format!("failed to compile shader (label: {:?})", descriptor.label);
+    for (location, diagnostic) in diagnostics {
+        match location {
+            Some(location) => message.push_str(&format!(
+                "\n{}:{}: {}",
+                location.line_number, location.line_position, diagnostic
+            )),
+            None => message.push_str(&format!("\n{}", diagnostic)),
+        }
+    }
+    Error::from(message)
+}
+
+/// Formats an error together with its sources, since naga's validation errors are nested
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        message.push_str(&format!(": {}", error));
+        source = error.source();
+    }
+    message
+}
+
+fn shader_stage(stage: ShaderStageFlags) -> Option<naga::ShaderStage> {
+    match stage {
+        ShaderStageFlags::VERTEX => Some(naga::ShaderStage::Vertex),
+        ShaderStageFlags::FRAGMENT => Some(naga::ShaderStage::Fragment),
+        ShaderStageFlags::COMPUTE => Some(naga::ShaderStage::Compute),
+        _ => None,
+    }
+}
+
+pub fn compile(descriptor: &ShaderSourceDescriptor) -> Result<Vec<u8>, Error> {
+    let source = descriptor.source;
+    let mut options = spv::Options::default();
+
+    let module = match descriptor.language {
+        ShaderLanguage::Glsl => {
+            let stage = shader_stage(descriptor.stage).ok_or_else(|| {
+                Error::from(format!(
+                    "GLSL sources require a vertex, fragment or compute stage; found {:?} (label: {:?})",
+                    descriptor.stage, descriptor.label
+                ))
+            })?;
+            // GLSL sources are written for Vulkan, like the precompiled shaders
+            options.flags.remove(spv::WriterFlags::ADJUST_COORDINATE_SPACE);
+            glsl::Frontend::default()
+                .parse(&glsl::Options::from(stage), source)
+                .map_err(|errors| {
+                    let diagnostics = errors
+                        .iter()
+                        .map(|error| (Some(error.meta.location(source)), error.kind.to_string()));
+                    compile_error(descriptor, diagnostics)
+                })?
+        }
+        ShaderLanguage::Wgsl => wgsl::parse_str(source).map_err(|error| {
+            let diagnostic = (error.location(source), error.message().to_owned());
+            compile_error(descriptor, Some(diagnostic))
+        })?,
+    };
+
+    let info = Validator::new(ValidationFlags::all(), Capabilities::PUSH_CONSTANT)
+        .validate(&module)
+        .map_err(|error| {
+            let diagnostic = (error.location(source), error_chain(error.as_inner()));
+            compile_error(descriptor, Some(diagnostic))
+        })?;
+
+    let words = spv::write_vec(&module, &info, &options, None).map_err(|error| {
+        Error::from(format!(
+            "failed to write SPIR-V: {} (label: {:?})",
+            error, descriptor.label
+        ))
+    })?;
+
+    Ok(words.iter().flat_map(|word| word.to_le_bytes().to_vec()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compile;
+    use crate::{ErrorKind, ScalarType, ShaderLanguage, ShaderReflection, ShaderSourceDescriptor, ShaderStageFlags};
+
+    fn error_message(descriptor: &ShaderSourceDescriptor) -> String {
+        match compile(descriptor).unwrap_err().kind() {
+            ErrorKind::Message(message) => message.clone(),
+            kind => panic!("unexpected error: {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn compile_glsl() {
+        let code = compile(&ShaderSourceDescriptor {
+            source: include_str!("../../tests/shaders/pipeline.vert.glsl"),
+            language: ShaderLanguage::Glsl,
+            stage: ShaderStageFlags::VERTEX,
+            label: None,
+        })
+        .unwrap();
+
+        let reflection = ShaderReflection::new(&code).unwrap();
+        let main = reflection.entry_point("main").unwrap();
+        assert_eq!(ShaderStageFlags::VERTEX, main.stage);
+        assert_eq!(2, main.inputs.len());
+        assert_eq!(1, main.bindings.len());
+    }
+
+    #[test]
+    fn compile_wgsl() {
+        let source = "
+            @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+
+            @compute @workgroup_size(4)
+            fn double(@builtin(global_invocation_id) id: vec3<u32>) {
+                data[id.x] = data[id.x] * 2u;
+            }
+        ";
+        let code = compile(&ShaderSourceDescriptor {
+            source,
+            language: ShaderLanguage::Wgsl,
+            stage: ShaderStageFlags::NONE,
+            label: None,
+        })
+        .unwrap();
+
+        let reflection = ShaderReflection::new(&code).unwrap();
+        let double = reflection.entry_point("double").unwrap();
+        assert_eq!(ShaderStageFlags::COMPUTE, double.stage);
+        assert_eq!(Some([4, 1, 1]), double.workgroup_size);
+        assert_eq!(1, double.bindings.len());
+    }
+
+    #[test]
+    fn compile_wgsl_fragment_outputs() {
+        let source = "
+            @fragment
+            fn main() -> @location(0) vec4<f32> {
+                return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+            }
+        ";
+        let code = compile(&ShaderSourceDescriptor {
+            source,
+            language: ShaderLanguage::Wgsl,
+            stage: ShaderStageFlags::NONE,
+            label: None,
+        })
+        .unwrap();
+
+        let reflection = ShaderReflection::new(&code).unwrap();
+        let main = reflection.entry_point("main").unwrap();
+        assert_eq!(1, main.outputs.len());
+        assert_eq!(ScalarType::Float, main.outputs[0].scalar_type);
+        assert_eq!(4, main.outputs[0].components);
+    }
+
+    #[test]
+    fn compile_glsl_error_location() {
+        let message = error_message(&ShaderSourceDescriptor {
+            source: "#version 450\nvoid main() {\n    gl_Position = vec4(0.0)\n}\n",
+            language: ShaderLanguage::Glsl,
+            stage: ShaderStageFlags::VERTEX,
+            label: Some("broken"),
+        });
+        assert!(message.contains("\"broken\""), "{}", message);
+        assert!(message.contains("\n4:1: "), "{}", message);
+    }
+
+    #[test]
+    fn compile_wgsl_error_location() {
+        let message = error_message(&ShaderSourceDescriptor {
+            source: "@compute @workgroup_size(1)\nfn main() {\n    let x: u32 = 1.0;\n}\n",
+            language: ShaderLanguage::Wgsl,
+            stage: ShaderStageFlags::NONE,
+            label: None,
+        });
+        assert!(message.contains("\n3:"), "{}", message);
+    }
+
+    #[test]
+    fn compile_glsl_requires_stage() {
+        assert!(compile(&ShaderSourceDescriptor {
+            source: include_str!("../../tests/shaders/shader.vert.glsl"),
+            language: ShaderLanguage::Glsl,
+            stage: ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT,
+            label: None,
+        })
+        .is_err());
+    }
+}
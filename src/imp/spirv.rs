@@ -0,0 +1,934 @@
+//! A minimal SPIR-V parser that collects the interface of a shader module
+
+use crate::{
+    BindingReflection, EntryPointReflection, Error, InterfaceVariableReflection, PushConstantReflection, ScalarType,
+    ShaderBindingType, ShaderReflection, ShaderStageFlags,
+};
+
+use std::collections::{HashMap, HashSet};
+
+const MAGIC_NUMBER: u32 = 0x0723_0203;
+const HEADER_WORD_COUNT: usize = 5;
+
+mod op {
+    pub const NAME: u32 = 5;
+    pub const ENTRY_POINT: u32 = 15;
+    pub const EXECUTION_MODE: u32 = 16;
+    pub const TYPE_BOOL: u32 = 20;
+    pub const TYPE_INT: u32 = 21;
+    pub const TYPE_FLOAT: u32 = 22;
+    pub const TYPE_VECTOR: u32 = 23;
+    pub const TYPE_MATRIX: u32 = 24;
+    pub const TYPE_IMAGE: u32 = 25;
+    pub const TYPE_SAMPLER: u32 = 26;
+    pub const TYPE_SAMPLED_IMAGE: u32 = 27;
+    pub const TYPE_ARRAY: u32 = 28;
+    pub const TYPE_RUNTIME_ARRAY: u32 = 29;
+    pub const TYPE_STRUCT: u32 = 30;
+    pub const TYPE_POINTER: u32 = 32;
+    pub const CONSTANT: u32 = 43;
+    pub const CONSTANT_COMPOSITE: u32 = 44;
+    pub const SPEC_CONSTANT: u32 = 50;
+    pub const SPEC_CONSTANT_COMPOSITE: u32 = 51;
+    pub const FUNCTION: u32 = 54;
+    pub const FUNCTION_END: u32 = 56;
+    pub const FUNCTION_CALL: u32 = 57;
+    pub const VARIABLE: u32 = 59;
+    pub const IMAGE_TEXEL_POINTER: u32 = 60;
+    pub const LOAD: u32 = 61;
+    pub const STORE: u32 = 62;
+    pub const COPY_MEMORY: u32 = 63;
+    pub const ACCESS_CHAIN: u32 = 65;
+    pub const IN_BOUNDS_ACCESS_CHAIN: u32 = 66;
+    pub const PTR_ACCESS_CHAIN: u32 = 67;
+    pub const ARRAY_LENGTH: u32 = 68;
+    pub const DECORATE: u32 = 71;
+    pub const MEMBER_DECORATE: u32 = 72;
+    pub const ATOMIC_LOAD: u32 = 227;
+    pub const ATOMIC_STORE: u32 = 228;
+    pub const ATOMIC_XOR: u32 = 242;
+    pub const ATOMIC_FLAG_TEST_AND_SET: u32 = 318;
+    pub const ATOMIC_FLAG_CLEAR: u32 = 319;
+    pub const EXECUTION_MODE_ID: u32 = 331;
+}
+
+mod decoration {
+    pub const BLOCK: u32 = 2;
+    pub const BUFFER_BLOCK: u32 = 3;
+    pub const ARRAY_STRIDE: u32 = 6;
+    pub const MATRIX_STRIDE: u32 = 7;
+    pub const BUILT_IN: u32 = 11;
+    pub const LOCATION: u32 = 30;
+    pub const BINDING: u32 = 33;
+    pub const DESCRIPTOR_SET: u32 = 34;
+    pub const OFFSET: u32 = 35;
+}
+
+mod storage_class {
+    pub const UNIFORM_CONSTANT: u32 = 0;
+    pub const INPUT: u32 = 1;
+    pub const UNIFORM: u32 = 2;
+    pub const OUTPUT: u32 = 3;
+    pub const PUSH_CONSTANT: u32 = 9;
+    pub const STORAGE_BUFFER: u32 = 12;
+}
+
+mod execution_model {
+    pub const VERTEX: u32 = 0;
+    pub const FRAGMENT: u32 = 4;
+    pub const GL_COMPUTE: u32 = 5;
+}
+
+mod execution_mode {
+    pub const LOCAL_SIZE: u32 = 17;
+    pub const LOCAL_SIZE_ID: u32 = 38;
+}
+
+const BUILT_IN_WORKGROUP_SIZE: u32 = 25;
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+#[derive(Clone, Debug)]
+enum Type {
+    Bool,
+    Int { width: u32, signed: bool },
+    Float { width: u32 },
+    Vector { component: u32, count: u32 },
+    Matrix { column: u32, count: u32 },
+    Image { dim: u32, sampled: u32 },
+    Sampler,
+    SampledImage,
+    Array { element: u32, length: u32 },
+    RuntimeArray { element: u32 },
+    Struct { members: Vec<u32> },
+    Pointer { storage_class: u32, pointee: u32 },
+}
+
+#[derive(Clone, Debug, Default)]
+struct Decorations {
+    block: bool,
+    buffer_block: bool,
+    array_stride: Option<u32>,
+    matrix_stride: Option<u32>,
+    built_in: Option<u32>,
+    location: Option<u32>,
+    binding: Option<u32>,
+    descriptor_set: Option<u32>,
+    offset: Option<u32>,
+}
+
+impl Decorations {
+    fn set(&mut self, decoration: u32, literals: &[u32]) {
+        let literal = literals.first().cloned();
+        match decoration {
+            decoration::BLOCK => self.block = true,
+            decoration::BUFFER_BLOCK => self.buffer_block = true,
+            decoration::ARRAY_STRIDE => self.array_stride = literal,
+            decoration::MATRIX_STRIDE => self.matrix_stride = literal,
+            decoration::BUILT_IN => self.built_in = literal,
+            decoration::LOCATION => self.location = literal,
+            decoration::BINDING => self.binding = literal,
+            decoration::DESCRIPTOR_SET => self.descriptor_set = literal,
+            decoration::OFFSET => self.offset = literal,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EntryPoint {
+    execution_model: u32,
+    function: u32,
+    name: String,
+    interface: Vec<u32>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum LocalSize {
+    Literals([u32; 3]),
+    Ids([u32; 3]),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Variable {
+    pointer_type: u32,
+    storage_class: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Function {
+    calls: Vec<u32>,
+    references: HashSet<u32>,
+}
+
+#[derive(Debug, Default)]
+struct Module {
+    names: HashMap<u32, String>,
+    entry_points: Vec<EntryPoint>,
+    local_sizes: HashMap<u32, LocalSize>,
+    types: HashMap<u32, Type>,
+    constants: HashMap<u32, u32>,
+    constant_composites: HashMap<u32, Vec<u32>>,
+    variables: HashMap<u32, Variable>,
+    decorations: HashMap<u32, Decorations>,
+    member_decorations: HashMap<(u32, u32), Decorations>,
+    functions: HashMap<u32, Function>,
+}
+
+fn invalid(message: &str) -> Error {
+    Error::from(format!("invalid SPIR-V: {}", message))
+}
+
+/// Reinterprets the code as SPIR-V words, swapping the byte order if the module was written with
+/// a different endianness
+fn words(code: &[u8]) -> Result<Vec<u32>, Error> {
+    if code.len() % 4 != 0 {
+        return Err(invalid("code size is not a multiple of 4"));
+    }
+    let mut words: Vec<u32> = code
+        .chunks(4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect();
+    if words.len() < HEADER_WORD_COUNT {
+        return Err(invalid("missing header"));
+    }
+    if words[0] == MAGIC_NUMBER.swap_bytes() {
+        words.iter_mut().for_each(|word| *word = word.swap_bytes());
+    }
+    if words[0] != MAGIC_NUMBER {
+        return Err(invalid("magic number mismatch"));
+    }
+    Ok(words)
+}
+
+/// Decodes a nul terminated literal string, returning the string and the number of words it occupies
+fn literal_string(words: &[u32]) -> (String, usize) {
+    let mut bytes = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        for byte in word.to_le_bytes().iter().cloned() {
+            if byte == 0 {
+                return (String::from_utf8_lossy(&bytes).into_owned(), index + 1);
+            }
+            bytes.push(byte);
+        }
+    }
+    (String::from_utf8_lossy(&bytes).into_owned(), words.len())
+}
+
+impl Module {
+    fn parse(words: &[u32]) -> Result<Module, Error> {
+        let mut module = Module::default();
+        let mut function: Option<u32> = None;
+        let mut offset = HEADER_WORD_COUNT;
+
+        while offset < words.len() {
+            let word_count = (words[offset] >> 16) as usize;
+            let opcode = words[offset] & 0xffff;
+            if word_count == 0 || offset + word_count > words.len() {
+                return Err(invalid(&format!("truncated instruction at word {}", offset)));
+            }
+            let operands = &words[offset + 1..offset + word_count];
+            offset += word_count;
+
+            let rest = |index: usize| operands.get(index..).unwrap_or(&[]);
+            let operand = |index: usize| {
+                operands
+                    .get(index)
+                    .cloned()
+                    .ok_or_else(|| invalid(&format!("missing operand {} of opcode {}", index, opcode)))
+            };
+
+            if let Some(function_id) = function {
+                let current = module.functions.entry(function_id).or_default();
+                match opcode {
+                    op::FUNCTION_END => function = None,
+                    op::FUNCTION_CALL => {
+                        current.calls.push(operand(2)?);
+                        // pointers to global variables can be passed as arguments
+                        current.references.extend(operands.iter().skip(3));
+                    }
+                    op::LOAD
+                    | op::ACCESS_CHAIN
+                    | op::IN_BOUNDS_ACCESS_CHAIN
+                    | op::PTR_ACCESS_CHAIN
+                    | op::ARRAY_LENGTH
+                    | op::IMAGE_TEXEL_POINTER
+                    | op::ATOMIC_LOAD
+                    | op::ATOMIC_FLAG_TEST_AND_SET => {
+                        current.references.insert(operand(2)?);
+                    }
+                    op::STORE | op::ATOMIC_STORE | op::ATOMIC_FLAG_CLEAR => {
+                        current.references.insert(operand(0)?);
+                    }
+                    op::COPY_MEMORY => {
+                        current.references.insert(operand(0)?);
+                        current.references.insert(operand(1)?);
+                    }
+                    opcode if opcode > op::ATOMIC_STORE && opcode <= op::ATOMIC_XOR => {
+                        current.references.insert(operand(2)?);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            match opcode {
+                op::NAME => {
+                    let (name, _) = literal_string(rest(1));
+                    module.names.insert(operand(0)?, name);
+                }
+                op::ENTRY_POINT => {
+                    let (name, name_word_count) = literal_string(rest(2));
+                    module.entry_points.push(EntryPoint {
+                        execution_model: operand(0)?,
+                        function: operand(1)?,
+                        name,
+                        interface: rest(2 + name_word_count).to_vec(),
+                    });
+                }
+                op::EXECUTION_MODE | op::EXECUTION_MODE_ID => {
+                    let size = [
+                        operand(2).unwrap_or(1),
+                        operand(3).unwrap_or(1),
+                        operand(4).unwrap_or(1),
+                    ];
+                    match operand(1)? {
+                        execution_mode::LOCAL_SIZE => {
+                            module.local_sizes.insert(operand(0)?, LocalSize::Literals(size));
+                        }
+                        execution_mode::LOCAL_SIZE_ID => {
+                            module.local_sizes.insert(operand(0)?, LocalSize::Ids(size));
+                        }
+                        _ => {}
+                    }
+                }
+                op::TYPE_BOOL => {
+                    module.types.insert(operand(0)?, Type::Bool);
+                }
+                op::TYPE_INT => {
+                    let ty = Type::Int {
+                        width: operand(1)?,
+                        signed: operand(2)? != 0,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                op::TYPE_FLOAT => {
+                    module.types.insert(operand(0)?, Type::Float { width: operand(1)? });
+                }
+                op::TYPE_VECTOR => {
+                    let ty = Type::Vector {
+                        component: operand(1)?,
+                        count: operand(2)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                op::TYPE_MATRIX => {
+                    let ty = Type::Matrix {
+                        column: operand(1)?,
+                        count: operand(2)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                op::TYPE_IMAGE => {
+                    let ty = Type::Image {
+                        dim: operand(2)?,
+                        sampled: operand(6)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                op::TYPE_SAMPLER => {
+                    module.types.insert(operand(0)?, Type::Sampler);
+                }
+                op::TYPE_SAMPLED_IMAGE => {
+                    module.types.insert(operand(0)?, Type::SampledImage);
+                }
+                op::TYPE_ARRAY => {
+                    let ty = Type::Array {
+                        element: operand(1)?,
+                        length: operand(2)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                op::TYPE_RUNTIME_ARRAY => {
+                    module
+                        .types
+                        .insert(operand(0)?, Type::RuntimeArray { element: operand(1)? });
+                }
+                op::TYPE_STRUCT => {
+                    let ty = Type::Struct {
+                        members: rest(1).to_vec(),
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                op::TYPE_POINTER => {
+                    let ty = Type::Pointer {
+                        storage_class: operand(1)?,
+                        pointee: operand(2)?,
+                    };
+                    module.types.insert(operand(0)?, ty);
+                }
+                op::CONSTANT | op::SPEC_CONSTANT => {
+                    // only the low word matters for sizes and lengths
+                    module.constants.insert(operand(1)?, operand(2)?);
+                }
+                op::CONSTANT_COMPOSITE | op::SPEC_CONSTANT_COMPOSITE => {
+                    module.constant_composites.insert(operand(1)?, rest(2).to_vec());
+                }
+                op::VARIABLE => {
+                    let variable = Variable {
+                        pointer_type: operand(0)?,
+                        storage_class: operand(2)?,
+                    };
+                    module.variables.insert(operand(1)?, variable);
+                }
+                op::DECORATE => {
+                    let decorations = module.decorations.entry(operand(0)?).or_default();
+                    decorations.set(operand(1)?, rest(2));
+                }
+                op::MEMBER_DECORATE => {
+                    let member = (operand(0)?, operand(1)?);
+                    let decorations = module.member_decorations.entry(member).or_default();
+                    decorations.set(operand(2)?, rest(3));
+                }
+                op::FUNCTION => {
+                    let function_id = operand(1)?;
+                    module.functions.entry(function_id).or_default();
+                    function = Some(function_id);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(module)
+    }
+
+    fn name(&self, id: u32) -> Option<String> {
+        self.names.get(&id).filter(|name| !name.is_empty()).cloned()
+    }
+
+    fn decorations(&self, id: u32) -> Decorations {
+        self.decorations.get(&id).cloned().unwrap_or_default()
+    }
+
+    fn pointee(&self, variable: &Variable) -> Option<u32> {
+        match self.types.get(&variable.pointer_type) {
+            Some(Type::Pointer { pointee, .. }) => Some(*pointee),
+            _ => None,
+        }
+    }
+
+    /// The global variables that are statically used by the function or any function it calls
+    fn used_variables(&self, function: u32) -> HashSet<u32> {
+        let mut visited = HashSet::new();
+        let mut pending = vec![function];
+        let mut used = HashSet::new();
+        while let Some(function) = pending.pop() {
+            if !visited.insert(function) {
+                continue;
+            }
+            if let Some(function) = self.functions.get(&function) {
+                used.extend(
+                    function
+                        .references
+                        .iter()
+                        .filter(|id| self.variables.contains_key(id))
+                        .cloned(),
+                );
+                pending.extend(function.calls.iter().cloned());
+            }
+        }
+        used
+    }
+
+    fn workgroup_size(&self, function: u32) -> Option<[u32; 3]> {
+        // a constant decorated with the WorkgroupSize built-in overrides the execution mode
+        let built_in = self
+            .constant_composites
+            .iter()
+            .find(|(id, _)| self.decorations.get(id).and_then(|d| d.built_in) == Some(BUILT_IN_WORKGROUP_SIZE));
+        if let Some((_, components)) = built_in {
+            let component = |index: usize| {
+                components
+                    .get(index)
+                    .and_then(|id| self.constants.get(id))
+                    .cloned()
+                    .unwrap_or(1)
+            };
+            return Some([component(0), component(1), component(2)]);
+        }
+
+        match self.local_sizes.get(&function)? {
+            LocalSize::Literals(size) => Some(*size),
+            LocalSize::Ids(ids) => {
+                let component = |index: usize| self.constants.get(&ids[index]).cloned().unwrap_or(1);
+                Some([component(0), component(1), component(2)])
+            }
+        }
+    }
+
+    fn binding(&self, id: u32, variable: &Variable) -> Option<BindingReflection> {
+        let decorations = self.decorations(id);
+        let binding = decorations.binding?;
+
+        let mut ty = self.pointee(variable)?;
+        let mut count = 1;
+        match self.types.get(&ty)? {
+            Type::Array { element, length } => {
+                count = self.constants.get(length).cloned().unwrap_or(1);
+                ty = *element;
+            }
+            Type::RuntimeArray { element } => {
+                count = 0;
+                ty = *element;
+            }
+            _ => {}
+        }
+
+        let binding_type = match (variable.storage_class, self.types.get(&ty)?) {
+            (storage_class::UNIFORM, Type::Struct { .. }) => {
+                if self.decorations(ty).buffer_block {
+                    ShaderBindingType::StorageBuffer
+                } else {
+                    ShaderBindingType::UniformBuffer
+                }
+            }
+            (storage_class::STORAGE_BUFFER, Type::Struct { .. }) => ShaderBindingType::StorageBuffer,
+            (storage_class::UNIFORM_CONSTANT, Type::Sampler) => ShaderBindingType::Sampler,
+            (storage_class::UNIFORM_CONSTANT, Type::SampledImage) => ShaderBindingType::CombinedTextureSampler,
+            (storage_class::UNIFORM_CONSTANT, Type::Image { dim, sampled }) => match (*dim, *sampled) {
+                (DIM_BUFFER, 2) => ShaderBindingType::StorageTexelBuffer,
+                (DIM_BUFFER, _) => ShaderBindingType::UniformTexelBuffer,
+                (DIM_SUBPASS_DATA, _) => ShaderBindingType::InputAttachment,
+                (_, 2) => ShaderBindingType::StorageTexture,
+                (_, _) => ShaderBindingType::SampledTexture,
+            },
+            _ => return None,
+        };
+
+        Some(BindingReflection {
+            set: decorations.descriptor_set.unwrap_or(0),
+            binding,
+            binding_type,
+            count,
+            name: self.name(id).or_else(|| self.name(ty)),
+        })
+    }
+
+    /// Size of a type with the explicit layout of a push constant block
+    fn size(&self, ty: u32, matrix_stride: Option<u32>) -> u32 {
+        match self.types.get(&ty) {
+            Some(Type::Bool) => 4,
+            Some(Type::Int { width, .. }) | Some(Type::Float { width }) => width / 8,
+            Some(Type::Vector { component, count }) => count * self.size(*component, None),
+            Some(Type::Matrix { column, count }) => count * matrix_stride.unwrap_or_else(|| self.size(*column, None)),
+            Some(Type::Array { element, length }) => {
+                let length = self.constants.get(length).cloned().unwrap_or(0);
+                let stride = self
+                    .decorations(ty)
+                    .array_stride
+                    .unwrap_or_else(|| self.size(*element, matrix_stride));
+                length * stride
+            }
+            Some(Type::Struct { members }) => {
+                let mut size = 0;
+                for (index, member) in members.iter().enumerate() {
+                    let decorations = self
+                        .member_decorations
+                        .get(&(ty, index as u32))
+                        .cloned()
+                        .unwrap_or_default();
+                    let offset = decorations.offset.unwrap_or(size);
+                    size = size.max(offset + self.size(*member, decorations.matrix_stride));
+                }
+                size
+            }
+            _ => 0,
+        }
+    }
+
+    /// Offset of the first member of a push constant block
+    fn offset(&self, ty: u32) -> u32 {
+        match self.types.get(&ty) {
+            Some(Type::Struct { members }) => (0..members.len() as u32)
+                .map(|index| {
+                    self.member_decorations
+                        .get(&(ty, index))
+                        .and_then(|decorations| decorations.offset)
+                        .unwrap_or(0)
+                })
+                .min()
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn push_constants(&self, id: u32, variable: &Variable) -> Option<PushConstantReflection> {
+        let ty = self.pointee(variable)?;
+        let offset = self.offset(ty);
+        Some(PushConstantReflection {
+            offset,
+            size: self.size(ty, None) - offset,
+            name: self.name(id).or_else(|| self.name(ty)),
+        })
+    }
+
+    fn scalar(&self, ty: u32) -> Option<(ScalarType, u32)> {
+        match self.types.get(&ty)? {
+            Type::Bool => Some((ScalarType::Bool, 1)),
+            Type::Int { signed: true, .. } => Some((ScalarType::Sint, 1)),
+            Type::Int { signed: false, .. } => Some((ScalarType::Uint, 1)),
+            Type::Float { .. } => Some((ScalarType::Float, 1)),
+            Type::Vector { component, count } => self.scalar(*component).map(|(scalar_type, _)| (scalar_type, *count)),
+            _ => None,
+        }
+    }
+
+    /// Appends the locations occupied by an interface variable of type `ty`
+    fn interface_variables(
+        &self,
+        ty: u32,
+        location: u32,
+        name: &Option<String>,
+        variables: &mut Vec<InterfaceVariableReflection>,
+    ) {
+        let (element, count) = match self.types.get(&ty) {
+            Some(Type::Matrix { column, count }) => (*column, *count),
+            Some(Type::Array { element, length }) => (*element, self.constants.get(length).cloned().unwrap_or(0)),
+            _ => {
+                if let Some((scalar_type, components)) = self.scalar(ty) {
+                    variables.push(InterfaceVariableReflection {
+                        location,
+                        scalar_type,
+                        components,
+                        name: name.clone(),
+                    });
+                }
+                return;
+            }
+        };
+        let stride = self.location_count(element);
+        for index in 0..count {
+            self.interface_variables(element, location + index * stride, name, variables);
+        }
+    }
+
+    /// The number of consecutive locations taken by an interface variable of type `ty`
+    fn location_count(&self, ty: u32) -> u32 {
+        match self.types.get(&ty) {
+            // 64-bit three and four component vectors take two locations
+            Some(Type::Vector { component, count }) => match self.types.get(component) {
+                Some(Type::Int { width: 64, .. }) | Some(Type::Float { width: 64 }) if *count > 2 => 2,
+                _ => 1,
+            },
+            Some(Type::Matrix { column, count }) => count * self.location_count(*column),
+            Some(Type::Array { element, length }) => {
+                self.constants.get(length).cloned().unwrap_or(0) * self.location_count(*element)
+            }
+            Some(Type::Struct { members }) => members.iter().map(|member| self.location_count(*member)).sum(),
+            _ => 1,
+        }
+    }
+
+    fn entry_point(&self, entry_point: &EntryPoint) -> EntryPointReflection {
+        let stage = match entry_point.execution_model {
+            execution_model::VERTEX => ShaderStageFlags::VERTEX,
+            execution_model::FRAGMENT => ShaderStageFlags::FRAGMENT,
+            execution_model::GL_COMPUTE => ShaderStageFlags::COMPUTE,
+            _ => ShaderStageFlags::NONE,
+        };
+
+        let workgroup_size = if stage == ShaderStageFlags::COMPUTE {
+            self.workgroup_size(entry_point.function)
+        } else {
+            None
+        };
+
+        let mut bindings = Vec::new();
+        let mut push_constants = None;
+        for id in self.used_variables(entry_point.function) {
+            let variable = &self.variables[&id];
+            match variable.storage_class {
+                storage_class::PUSH_CONSTANT => push_constants = self.push_constants(id, variable),
+                storage_class::UNIFORM | storage_class::UNIFORM_CONSTANT | storage_class::STORAGE_BUFFER => {
+                    bindings.extend(self.binding(id, variable))
+                }
+                _ => {}
+            }
+        }
+        bindings.sort_by_key(|binding| (binding.set, binding.binding));
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        for id in entry_point.interface.iter().cloned() {
+            let variable = match self.variables.get(&id) {
+                Some(variable) => variable,
+                None => continue,
+            };
+            let interface = match variable.storage_class {
+                storage_class::INPUT => &mut inputs,
+                storage_class::OUTPUT => &mut outputs,
+                _ => continue,
+            };
+            let decorations = self.decorations(id);
+            if decorations.built_in.is_some() {
+                continue;
+            }
+            // blocks of built-ins such as gl_PerVertex don't have a location
+            if let (Some(location), Some(ty)) = (decorations.location, self.pointee(variable)) {
+                self.interface_variables(ty, location, &self.name(id), interface);
+            }
+        }
+        inputs.sort_by_key(|variable| variable.location);
+        outputs.sort_by_key(|variable| variable.location);
+
+        EntryPointReflection {
+            name: entry_point.name.clone(),
+            stage,
+            workgroup_size,
+            bindings,
+            push_constants,
+            inputs,
+            outputs,
+        }
+    }
+}
+
+impl ShaderReflection {
+    /// Parses SPIR-V code, e.g. for tooling that has no device
+    pub fn new(code: &[u8]) -> Result<ShaderReflection, Error> {
+        let words = words(code)?;
+        let module = Module::parse(&words)?;
+        let entry_points = module
+            .entry_points
+            .iter()
+            .map(|entry_point| module.entry_point(entry_point))
+            .collect();
+        Ok(ShaderReflection { entry_points })
+    }
+
+    pub fn entry_point(&self, name: &str) -> Option<&EntryPointReflection> {
+        self.entry_points.iter().find(|entry_point| entry_point.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ScalarType, ShaderBindingType, ShaderReflection, ShaderStageFlags};
+
+    #[test]
+    fn reflect_compute_storage_buffers() {
+        let code = include_bytes!("../../tests/shaders/command_buffer.copy_buffer_with_compute_shader.comp.spv");
+        let reflection = ShaderReflection::new(code).unwrap();
+        assert_eq!(1, reflection.entry_points.len());
+
+        let main = reflection.entry_point("main").unwrap();
+        assert_eq!(ShaderStageFlags::COMPUTE, main.stage);
+        assert_eq!(Some([4, 1, 1]), main.workgroup_size);
+        assert_eq!(None, main.push_constants);
+        assert!(main.inputs.is_empty());
+        assert!(main.outputs.is_empty());
+
+        assert_eq!(2, main.bindings.len());
+        for (index, binding) in main.bindings.iter().enumerate() {
+            assert_eq!(0, binding.set);
+            assert_eq!(index as u32, binding.binding);
+            assert_eq!(ShaderBindingType::StorageBuffer, binding.binding_type);
+            assert_eq!(1, binding.count);
+        }
+        assert_eq!(Some("inBuffer"), main.bindings[0].name.as_deref());
+        assert_eq!(Some("outBuffer"), main.bindings[1].name.as_deref());
+    }
+
+    #[test]
+    fn reflect_compute_push_constants() {
+        let code = include_bytes!("../../tests/shaders/command_buffer.push_constants.comp.spv");
+        let reflection = ShaderReflection::new(code).unwrap();
+
+        let main = reflection.entry_point("main").unwrap();
+        assert_eq!(Some([1, 1, 1]), main.workgroup_size);
+
+        let push_constants = main.push_constants.as_ref().unwrap();
+        assert_eq!(0, push_constants.offset);
+        assert_eq!(8, push_constants.size);
+        assert_eq!(Some("PushConstants"), push_constants.name.as_deref());
+
+        assert_eq!(1, main.bindings.len());
+    }
+
+    #[test]
+    fn reflect_compute_binding_types() {
+        let code = include_bytes!("../../tests/shaders/pipeline.set_bind_group.comp.spv");
+        let reflection = ShaderReflection::new(code).unwrap();
+
+        let main = reflection.entry_point("main").unwrap();
+        let binding_types: Vec<_> = main
+            .bindings
+            .iter()
+            .map(|binding| (binding.binding, binding.binding_type))
+            .collect();
+        assert_eq!(
+            vec![
+                (0, ShaderBindingType::UniformBuffer),
+                (1, ShaderBindingType::StorageBuffer),
+                (2, ShaderBindingType::StorageTexelBuffer),
+                (3, ShaderBindingType::Sampler),
+                (4, ShaderBindingType::SampledTexture),
+            ],
+            binding_types
+        );
+    }
+
+    #[test]
+    fn reflect_vertex_inputs() {
+        let code = include_bytes!("../../tests/shaders/pipeline.vert.spv");
+        let reflection = ShaderReflection::new(code).unwrap();
+
+        let main = reflection.entry_point("main").unwrap();
+        assert_eq!(ShaderStageFlags::VERTEX, main.stage);
+        assert_eq!(None, main.workgroup_size);
+
+        let inputs: Vec<_> = main
+            .inputs
+            .iter()
+            .map(|input| (input.location, input.scalar_type, input.components))
+            .collect();
+        assert_eq!(vec![(0, ScalarType::Float, 3), (1, ScalarType::Float, 3)], inputs);
+        assert_eq!(Some("a_Position"), main.inputs[0].name.as_deref());
+
+        // gl_Position is a built-in and not reported
+        assert_eq!(1, main.outputs.len());
+        assert_eq!(0, main.outputs[0].location);
+
+        assert_eq!(1, main.bindings.len());
+        assert_eq!(ShaderBindingType::UniformBuffer, main.bindings[0].binding_type);
+        assert_eq!(Some("MVP"), main.bindings[0].name.as_deref());
+    }
+
+    #[test]
+    fn reflect_fragment_outputs() {
+        let code = include_bytes!("../../tests/shaders/pipeline.frag.spv");
+        let reflection = ShaderReflection::new(code).unwrap();
+
+        let main = reflection.entry_point("main").unwrap();
+        assert_eq!(ShaderStageFlags::FRAGMENT, main.stage);
+        assert_eq!(1, main.inputs.len());
+        assert_eq!(1, main.outputs.len());
+        assert_eq!(0, main.outputs[0].location);
+        assert_eq!(ScalarType::Float, main.outputs[0].scalar_type);
+        assert_eq!(4, main.outputs[0].components);
+        assert!(main.bindings.is_empty());
+    }
+
+    /// Assembles a module from `(opcode, operands)` pairs
+    fn assemble(instructions: &[(u32, &[u32])]) -> Vec<u8> {
+        let mut words = vec![super::MAGIC_NUMBER, 0x0001_0000, 0, 32, 0];
+        for (opcode, operands) in instructions {
+            words.push((operands.len() as u32 + 1) << 16 | opcode);
+            words.extend_from_slice(operands);
+        }
+        words.iter().flat_map(|word| word.to_le_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn reflect_matrix_array_inputs() {
+        // layout(location = 0) in mat4 a_Transforms[2];
+        // layout(location = 8) in dvec4 a_Weights[2];
+        // layout(location = 12) in vec3 a_Position;
+        #[rustfmt::skip]
+        let code = assemble(&[
+            (17, &[1]),                                  // OpCapability Shader
+            (17, &[10]),                                 // OpCapability Float64
+            (14, &[0, 1]),                               // OpMemoryModel Logical GLSL450
+            (15, &[0, 1, 0x6e69_616d, 0, 10, 11, 12]),   // OpEntryPoint Vertex %1 "main" %10 %11 %12
+            (71, &[10, 30, 0]),                          // OpDecorate %10 Location 0
+            (71, &[11, 30, 8]),                          // OpDecorate %11 Location 8
+            (71, &[12, 30, 12]),                         // OpDecorate %12 Location 12
+            (19, &[2]),                                  // %2 = OpTypeVoid
+            (33, &[3, 2]),                               // %3 = OpTypeFunction %2
+            (22, &[4, 32]),                              // %4 = OpTypeFloat 32
+            (23, &[5, 4, 4]),                            // %5 = OpTypeVector %4 4
+            (24, &[6, 5, 4]),                            // %6 = OpTypeMatrix %5 4
+            (21, &[7, 32, 0]),                           // %7 = OpTypeInt 32 0
+            (43, &[7, 8, 2]),                            // %8 = OpConstant %7 2
+            (28, &[9, 6, 8]),                            // %9 = OpTypeArray %6 %8
+            (32, &[13, 1, 9]),                           // %13 = OpTypePointer Input %9
+            (22, &[14, 64]),                             // %14 = OpTypeFloat 64
+            (23, &[15, 14, 4]),                          // %15 = OpTypeVector %14 4
+            (28, &[16, 15, 8]),                          // %16 = OpTypeArray %15 %8
+            (32, &[17, 1, 16]),                          // %17 = OpTypePointer Input %16
+            (23, &[18, 4, 3]),                           // %18 = OpTypeVector %4 3
+            (32, &[19, 1, 18]),                          // %19 = OpTypePointer Input %18
+            (59, &[13, 10, 1]),                          // %10 = OpVariable %13 Input
+            (59, &[17, 11, 1]),                          // %11 = OpVariable %17 Input
+            (59, &[19, 12, 1]),                          // %12 = OpVariable %19 Input
+            (54, &[2, 1, 0, 3]),                         // %1 = OpFunction %2 None %3
+            (248, &[20]),                                // %20 = OpLabel
+            (253, &[]),                                  // OpReturn
+            (56, &[]),                                   // OpFunctionEnd
+        ]);
+        let reflection = ShaderReflection::new(&code).unwrap();
+
+        let main = reflection.entry_point("main").unwrap();
+        let inputs: Vec<_> = main
+            .inputs
+            .iter()
+            .map(|input| (input.location, input.scalar_type, input.components))
+            .collect();
+        let mut expected: Vec<_> = (0..8).map(|location| (location, ScalarType::Float, 4)).collect();
+        expected.push((8, ScalarType::Float, 4));
+        expected.push((10, ScalarType::Float, 4));
+        expected.push((12, ScalarType::Float, 3));
+        assert_eq!(expected, inputs);
+    }
+
+    #[test]
+    fn reflect_push_constant_offset() {
+        // layout(push_constant) uniform PushConstants {
+        //     layout(offset = 16) uint one;
+        //     uint two;
+        // };
+        #[rustfmt::skip]
+        let code = assemble(&[
+            (17, &[1]),                                  // OpCapability Shader
+            (14, &[0, 1]),                               // OpMemoryModel Logical GLSL450
+            (15, &[5, 1, 0x6e69_616d, 0]),               // OpEntryPoint GLCompute %1 "main"
+            (16, &[1, 17, 1, 1, 1]),                     // OpExecutionMode %1 LocalSize 1 1 1
+            (71, &[5, 2]),                               // OpDecorate %5 Block
+            (72, &[5, 0, 35, 16]),                       // OpMemberDecorate %5 0 Offset 16
+            (72, &[5, 1, 35, 20]),                       // OpMemberDecorate %5 1 Offset 20
+            (19, &[2]),                                  // %2 = OpTypeVoid
+            (33, &[3, 2]),                               // %3 = OpTypeFunction %2
+            (21, &[4, 32, 0]),                           // %4 = OpTypeInt 32 0
+            (30, &[5, 4, 4]),                            // %5 = OpTypeStruct %4 %4
+            (32, &[6, 9, 5]),                            // %6 = OpTypePointer PushConstant %5
+            (59, &[6, 7, 9]),                            // %7 = OpVariable %6 PushConstant
+            (54, &[2, 1, 0, 3]),                         // %1 = OpFunction %2 None %3
+            (248, &[8]),                                 // %8 = OpLabel
+            (61, &[5, 9, 7]),                            // %9 = OpLoad %5 %7
+            (253, &[]),                                  // OpReturn
+            (56, &[]),                                   // OpFunctionEnd
+        ]);
+        let reflection = ShaderReflection::new(&code).unwrap();
+
+        let main = reflection.entry_point("main").unwrap();
+        let push_constants = main.push_constants.as_ref().unwrap();
+        assert_eq!(16, push_constants.offset);
+        assert_eq!(8, push_constants.size);
+    }
+
+    #[test]
+    fn reflect_invalid_code() {
+        assert!(ShaderReflection::new(&[]).is_err());
+        assert!(ShaderReflection::new(&[0; 7]).is_err());
+        assert!(ShaderReflection::new(&[0; 20]).is_err());
+
+        let code = include_bytes!("../../tests/shaders/shader.vert.spv");
+        assert!(ShaderReflection::new(code).is_ok());
+
+        // word count of the first instruction points past the end of the code
+        let mut truncated = code.to_vec();
+        truncated[22] = 0xff;
+        truncated[23] = 0xff;
+        assert!(ShaderReflection::new(&truncated).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::imp::fenced_deleter::DeleteWhenUnused;
 use crate::imp::texture::SubresourceUsageTracker;
-use crate::imp::{texture, AdapterInner, SurfaceInner, TextureViewInner};
+use crate::imp::{debug, texture, AdapterInner, SurfaceInner, TextureViewInner};
 use crate::imp::{DeviceInner, InstanceInner, SwapchainInner, TextureInner};
 use crate::{
     Error, Extent3D, PowerPreference, Swapchain, SwapchainDescriptor, SwapchainError, SwapchainImage, Texture,
@@ -121,6 +121,7 @@ impl SwapchainInner {
                 dimension: TextureDimension::D2,
                 format: descriptor.format,
                 usage: TextureUsageFlags::PRESENT,
+                label: None,
             };
 
             let textures = images.iter().cloned().map(|handle| {
@@ -131,7 +132,9 @@ impl SwapchainInner {
                     allocation: None,
                     allocation_info: None,
                     subresource_usage: Mutex::new(subresource_usage),
+                    queue_family_index: Mutex::new(device.queue.queue_family_index),
                     descriptor: texture_descriptor,
+                    label: debug::Label::new(&device, handle, Some("swapchain_image")),
                 })
             });
             let textures: Vec<_> = textures.collect();
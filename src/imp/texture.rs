@@ -1,12 +1,14 @@
 use ash::version::DeviceV1_0;
 use ash::vk;
 
+use crate::imp::debug::WithoutLabel;
 use crate::imp::fenced_deleter::DeleteWhenUnused;
-use crate::imp::{render_pass, util};
-use crate::imp::{DeviceInner, TextureInner, TextureViewInner};
+use crate::imp::{debug, queue, render_pass, util};
+use crate::imp::{AdapterInner, DeviceInner, QueueInfo, TextureInner, TextureViewInner};
 use crate::{
-    Error, Extent3D, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsageFlags, TextureView,
-    TextureViewDescriptor, TextureViewDimension,
+    BufferCopyView, Error, Extensions, Extent3D, Limits, ScalarType, Texture, TextureCopyView, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureSubresourceRange, TextureUsageFlags, TextureView, TextureViewDescriptor,
+    TextureViewDimension,
 };
 
 use ash::vk::MemoryPropertyFlags;
@@ -30,6 +32,9 @@ pub fn memory_usage(_usage: TextureUsageFlags) -> MemoryUsage {
 
 pub fn is_depth(format: TextureFormat) -> bool {
     match format {
+        TextureFormat::D16Unorm => true,
+        TextureFormat::D24UnormS8Uint => true,
+        TextureFormat::D24PlusS8 => true,
         TextureFormat::D32Float => true,
         TextureFormat::D32FloatS8Uint => true,
         _ => false,
@@ -38,7 +43,10 @@ pub fn is_depth(format: TextureFormat) -> bool {
 
 pub fn is_stencil(format: TextureFormat) -> bool {
     match format {
+        TextureFormat::D24UnormS8Uint => true,
+        TextureFormat::D24PlusS8 => true,
         TextureFormat::D32FloatS8Uint => true,
+        TextureFormat::S8Uint => true,
         _ => false,
     }
 }
@@ -47,8 +55,66 @@ pub fn is_depth_or_stencil(format: TextureFormat) -> bool {
     is_depth(format) || is_stencil(format)
 }
 
+pub fn is_integer(format: TextureFormat) -> bool {
+    scalar_type(format) != ScalarType::Float
+}
+
+/// The shader scalar type that reads or writes the format
+#[rustfmt::skip]
+pub fn scalar_type(format: TextureFormat) -> ScalarType {
+    match format {
+        TextureFormat::R8Uint |
+        TextureFormat::R16Uint |
+        TextureFormat::R8G8Uint |
+        TextureFormat::R32Uint |
+        TextureFormat::RG16Uint |
+        TextureFormat::R8G8B8A8Uint |
+        TextureFormat::RG32Uint |
+        TextureFormat::RGBA16Uint |
+        TextureFormat::RGBA32Uint
+        => ScalarType::Uint,
+        TextureFormat::R8Sint |
+        TextureFormat::R16Sint |
+        TextureFormat::R8G8Sint |
+        TextureFormat::R32Sint |
+        TextureFormat::RG16Sint |
+        TextureFormat::R8G8B8A8Sint |
+        TextureFormat::RG32Sint |
+        TextureFormat::RGBA16Sint |
+        TextureFormat::RGBA32Sint
+        => ScalarType::Sint,
+        _ => ScalarType::Float,
+    }
+}
+
+/// The block compression family of a format, each is enabled by a device extension
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    Bc,
+    Etc2,
+    Astc,
+}
+
+pub fn compression(format: TextureFormat) -> Option<Compression> {
+    let format = image_format(format).as_raw();
+    let range = |first: vk::Format, last: vk::Format| first.as_raw() <= format && format <= last.as_raw();
+    if range(vk::Format::BC1_RGB_UNORM_BLOCK, vk::Format::BC7_SRGB_BLOCK) {
+        Some(Compression::Bc)
+    } else if range(vk::Format::ETC2_R8G8B8_UNORM_BLOCK, vk::Format::EAC_R11G11_SNORM_BLOCK) {
+        Some(Compression::Etc2)
+    } else if range(vk::Format::ASTC_4X4_UNORM_BLOCK, vk::Format::ASTC_12X12_SRGB_BLOCK) {
+        Some(Compression::Astc)
+    } else {
+        None
+    }
+}
+
+pub fn is_compressed(format: TextureFormat) -> bool {
+    compression(format).is_some()
+}
+
 pub fn image_type(dimension: TextureDimension) -> vk::ImageType {
-    // TODO: arrays?
+    // array textures are images with multiple layers
     match dimension {
         TextureDimension::D1 => vk::ImageType::TYPE_1D,
         TextureDimension::D2 => vk::ImageType::TYPE_2D,
@@ -57,15 +123,25 @@ pub fn image_type(dimension: TextureDimension) -> vk::ImageType {
 }
 
 pub fn image_view_type(descriptor: &TextureViewDescriptor) -> vk::ImageViewType {
-    // TODO: arrays?
     match descriptor.dimension {
         TextureViewDimension::D1 => vk::ImageViewType::TYPE_1D,
+        TextureViewDimension::D1Array => vk::ImageViewType::TYPE_1D_ARRAY,
         TextureViewDimension::D2 => vk::ImageViewType::TYPE_2D,
+        TextureViewDimension::D2Array => vk::ImageViewType::TYPE_2D_ARRAY,
         TextureViewDimension::D3 => vk::ImageViewType::TYPE_3D,
         TextureViewDimension::Cube => vk::ImageViewType::CUBE,
+        TextureViewDimension::CubeArray => vk::ImageViewType::CUBE_ARRAY,
     }
 }
 
+/// Whether cube views of the texture can be created
+pub fn is_cube_compatible(descriptor: &TextureDescriptor) -> bool {
+    descriptor.dimension == TextureDimension::D2
+        && descriptor.array_layer_count >= 6
+        && descriptor.size.width == descriptor.size.height
+        && descriptor.sample_count == 1
+}
+
 pub fn image_usage(usage: TextureUsageFlags, format: TextureFormat) -> vk::ImageUsageFlags {
     let mut flags = vk::ImageUsageFlags::empty();
 
@@ -104,32 +180,212 @@ pub fn image_format(format: TextureFormat) -> vk::Format {
         TextureFormat::R8Uint => vk::Format::R8_UINT,
         TextureFormat::R8Sint => vk::Format::R8_SINT,
 
+        TextureFormat::R16Uint => vk::Format::R16_UINT,
+        TextureFormat::R16Sint => vk::Format::R16_SINT,
+        TextureFormat::R16Float => vk::Format::R16_SFLOAT,
         TextureFormat::R8G8Unorm => vk::Format::R8G8_UNORM,
+        TextureFormat::R8G8Snorm => vk::Format::R8G8_SNORM,
         TextureFormat::R8G8Uint => vk::Format::R8G8_UINT,
-
+        TextureFormat::R8G8Sint => vk::Format::R8G8_SINT,
+
+        TextureFormat::R32Uint => vk::Format::R32_UINT,
+        TextureFormat::R32Sint => vk::Format::R32_SINT,
+        TextureFormat::R32Float => vk::Format::R32_SFLOAT,
+        TextureFormat::RG16Uint => vk::Format::R16G16_UINT,
+        TextureFormat::RG16Sint => vk::Format::R16G16_SINT,
+        TextureFormat::RG16Float => vk::Format::R16G16_SFLOAT,
         TextureFormat::R8G8B8A8Unorm => vk::Format::R8G8B8A8_UNORM,
         TextureFormat::R8G8B8A8UnormSRGB => vk::Format::R8G8B8A8_SRGB,
+        TextureFormat::R8G8B8A8Snorm => vk::Format::R8G8B8A8_SNORM,
         TextureFormat::R8G8B8A8Uint => vk::Format::R8G8B8A8_UINT,
+        TextureFormat::R8G8B8A8Sint => vk::Format::R8G8B8A8_SINT,
         TextureFormat::B8G8R8A8Unorm => vk::Format::B8G8R8A8_UNORM,
         TextureFormat::B8G8R8A8UnormSRGB => vk::Format::B8G8R8A8_SRGB,
-
+        TextureFormat::RGB10A2Unorm => vk::Format::A2B10G10R10_UNORM_PACK32,
+        TextureFormat::RG11B10Float => vk::Format::B10G11R11_UFLOAT_PACK32,
+
+        TextureFormat::RG32Uint => vk::Format::R32G32_UINT,
+        TextureFormat::RG32Sint => vk::Format::R32G32_SINT,
+        TextureFormat::RG32Float => vk::Format::R32G32_SFLOAT,
+        TextureFormat::RGBA16Uint => vk::Format::R16G16B16A16_UINT,
+        TextureFormat::RGBA16Sint => vk::Format::R16G16B16A16_SINT,
+        TextureFormat::RGBA16Float => vk::Format::R16G16B16A16_SFLOAT,
+
+        TextureFormat::RGBA32Uint => vk::Format::R32G32B32A32_UINT,
+        TextureFormat::RGBA32Sint => vk::Format::R32G32B32A32_SINT,
         TextureFormat::RGBA32Float => vk::Format::R32G32B32A32_SFLOAT,
 
+        TextureFormat::D16Unorm => vk::Format::D16_UNORM,
+        TextureFormat::D24UnormS8Uint => vk::Format::D24_UNORM_S8_UINT,
+        // the preferred format, `AdapterInner::image_format` falls back to D32_SFLOAT_S8_UINT
+        TextureFormat::D24PlusS8 => vk::Format::D24_UNORM_S8_UINT,
         TextureFormat::D32Float => vk::Format::D32_SFLOAT,
         TextureFormat::D32FloatS8Uint => vk::Format::D32_SFLOAT_S8_UINT,
+        TextureFormat::S8Uint => vk::Format::S8_UINT,
+
+        TextureFormat::BC1RGBAUnorm => vk::Format::BC1_RGBA_UNORM_BLOCK,
+        TextureFormat::BC1RGBAUnormSRGB => vk::Format::BC1_RGBA_SRGB_BLOCK,
+        TextureFormat::BC2RGBAUnorm => vk::Format::BC2_UNORM_BLOCK,
+        TextureFormat::BC2RGBAUnormSRGB => vk::Format::BC2_SRGB_BLOCK,
+        TextureFormat::BC3RGBAUnorm => vk::Format::BC3_UNORM_BLOCK,
+        TextureFormat::BC3RGBAUnormSRGB => vk::Format::BC3_SRGB_BLOCK,
+        TextureFormat::BC4RUnorm => vk::Format::BC4_UNORM_BLOCK,
+        TextureFormat::BC4RSnorm => vk::Format::BC4_SNORM_BLOCK,
+        TextureFormat::BC5RGUnorm => vk::Format::BC5_UNORM_BLOCK,
+        TextureFormat::BC5RGSnorm => vk::Format::BC5_SNORM_BLOCK,
+        TextureFormat::BC6HRGBUfloat => vk::Format::BC6H_UFLOAT_BLOCK,
+        TextureFormat::BC6HRGBSfloat => vk::Format::BC6H_SFLOAT_BLOCK,
+        TextureFormat::BC7RGBAUnorm => vk::Format::BC7_UNORM_BLOCK,
+        TextureFormat::BC7RGBAUnormSRGB => vk::Format::BC7_SRGB_BLOCK,
+
+        TextureFormat::ETC2RGB8Unorm => vk::Format::ETC2_R8G8B8_UNORM_BLOCK,
+        TextureFormat::ETC2RGB8UnormSRGB => vk::Format::ETC2_R8G8B8_SRGB_BLOCK,
+        TextureFormat::ETC2RGB8A1Unorm => vk::Format::ETC2_R8G8B8A1_UNORM_BLOCK,
+        TextureFormat::ETC2RGB8A1UnormSRGB => vk::Format::ETC2_R8G8B8A1_SRGB_BLOCK,
+        TextureFormat::ETC2RGBA8Unorm => vk::Format::ETC2_R8G8B8A8_UNORM_BLOCK,
+        TextureFormat::ETC2RGBA8UnormSRGB => vk::Format::ETC2_R8G8B8A8_SRGB_BLOCK,
+        TextureFormat::EACR11Unorm => vk::Format::EAC_R11_UNORM_BLOCK,
+        TextureFormat::EACR11Snorm => vk::Format::EAC_R11_SNORM_BLOCK,
+        TextureFormat::EACRG11Unorm => vk::Format::EAC_R11G11_UNORM_BLOCK,
+        TextureFormat::EACRG11Snorm => vk::Format::EAC_R11G11_SNORM_BLOCK,
+
+        TextureFormat::ASTC4x4Unorm => vk::Format::ASTC_4X4_UNORM_BLOCK,
+        TextureFormat::ASTC4x4UnormSRGB => vk::Format::ASTC_4X4_SRGB_BLOCK,
+        TextureFormat::ASTC5x4Unorm => vk::Format::ASTC_5X4_UNORM_BLOCK,
+        TextureFormat::ASTC5x4UnormSRGB => vk::Format::ASTC_5X4_SRGB_BLOCK,
+        TextureFormat::ASTC5x5Unorm => vk::Format::ASTC_5X5_UNORM_BLOCK,
+        TextureFormat::ASTC5x5UnormSRGB => vk::Format::ASTC_5X5_SRGB_BLOCK,
+        TextureFormat::ASTC6x5Unorm => vk::Format::ASTC_6X5_UNORM_BLOCK,
+        TextureFormat::ASTC6x5UnormSRGB => vk::Format::ASTC_6X5_SRGB_BLOCK,
+        TextureFormat::ASTC6x6Unorm => vk::Format::ASTC_6X6_UNORM_BLOCK,
+        TextureFormat::ASTC6x6UnormSRGB => vk::Format::ASTC_6X6_SRGB_BLOCK,
+        TextureFormat::ASTC8x5Unorm => vk::Format::ASTC_8X5_UNORM_BLOCK,
+        TextureFormat::ASTC8x5UnormSRGB => vk::Format::ASTC_8X5_SRGB_BLOCK,
+        TextureFormat::ASTC8x6Unorm => vk::Format::ASTC_8X6_UNORM_BLOCK,
+        TextureFormat::ASTC8x6UnormSRGB => vk::Format::ASTC_8X6_SRGB_BLOCK,
+        TextureFormat::ASTC8x8Unorm => vk::Format::ASTC_8X8_UNORM_BLOCK,
+        TextureFormat::ASTC8x8UnormSRGB => vk::Format::ASTC_8X8_SRGB_BLOCK,
+        TextureFormat::ASTC10x5Unorm => vk::Format::ASTC_10X5_UNORM_BLOCK,
+        TextureFormat::ASTC10x5UnormSRGB => vk::Format::ASTC_10X5_SRGB_BLOCK,
+        TextureFormat::ASTC10x6Unorm => vk::Format::ASTC_10X6_UNORM_BLOCK,
+        TextureFormat::ASTC10x6UnormSRGB => vk::Format::ASTC_10X6_SRGB_BLOCK,
+        TextureFormat::ASTC10x8Unorm => vk::Format::ASTC_10X8_UNORM_BLOCK,
+        TextureFormat::ASTC10x8UnormSRGB => vk::Format::ASTC_10X8_SRGB_BLOCK,
+        TextureFormat::ASTC10x10Unorm => vk::Format::ASTC_10X10_UNORM_BLOCK,
+        TextureFormat::ASTC10x10UnormSRGB => vk::Format::ASTC_10X10_SRGB_BLOCK,
+        TextureFormat::ASTC12x10Unorm => vk::Format::ASTC_12X10_UNORM_BLOCK,
+        TextureFormat::ASTC12x10UnormSRGB => vk::Format::ASTC_12X10_SRGB_BLOCK,
+        TextureFormat::ASTC12x12Unorm => vk::Format::ASTC_12X12_UNORM_BLOCK,
+        TextureFormat::ASTC12x12UnormSRGB => vk::Format::ASTC_12X12_SRGB_BLOCK,
     }
 }
 
-pub fn texture_format(format: vk::Format) -> TextureFormat {
-    match format {
-        vk::Format::B8G8R8A8_SRGB => TextureFormat::B8G8R8A8UnormSRGB,
-        vk::Format::B8G8R8A8_UNORM => TextureFormat::B8G8R8A8Unorm,
-        vk::Format::R8G8B8A8_SRGB => TextureFormat::R8G8B8A8UnormSRGB,
-        vk::Format::R8G8B8A8_UNORM => TextureFormat::R8G8B8A8Unorm,
-        _ => unimplemented!("todo: missing format conversion: {:?}", format), // TODO
-    }
+/// Every texture format with a fixed Vulkan format, in declaration order
+pub const TEXTURE_FORMATS: &[TextureFormat] = &[
+    TextureFormat::R8Unorm,
+    TextureFormat::R8UnormSRGB,
+    TextureFormat::R8Snorm,
+    TextureFormat::R8Uint,
+    TextureFormat::R8Sint,
+    TextureFormat::R16Uint,
+    TextureFormat::R16Sint,
+    TextureFormat::R16Float,
+    TextureFormat::R8G8Unorm,
+    TextureFormat::R8G8Snorm,
+    TextureFormat::R8G8Uint,
+    TextureFormat::R8G8Sint,
+    TextureFormat::R32Uint,
+    TextureFormat::R32Sint,
+    TextureFormat::R32Float,
+    TextureFormat::RG16Uint,
+    TextureFormat::RG16Sint,
+    TextureFormat::RG16Float,
+    TextureFormat::R8G8B8A8Unorm,
+    TextureFormat::R8G8B8A8UnormSRGB,
+    TextureFormat::R8G8B8A8Snorm,
+    TextureFormat::R8G8B8A8Uint,
+    TextureFormat::R8G8B8A8Sint,
+    TextureFormat::B8G8R8A8Unorm,
+    TextureFormat::B8G8R8A8UnormSRGB,
+    TextureFormat::RGB10A2Unorm,
+    TextureFormat::RG11B10Float,
+    TextureFormat::RG32Uint,
+    TextureFormat::RG32Sint,
+    TextureFormat::RG32Float,
+    TextureFormat::RGBA16Uint,
+    TextureFormat::RGBA16Sint,
+    TextureFormat::RGBA16Float,
+    TextureFormat::RGBA32Uint,
+    TextureFormat::RGBA32Sint,
+    TextureFormat::RGBA32Float,
+    TextureFormat::D16Unorm,
+    TextureFormat::D24UnormS8Uint,
+    TextureFormat::D32Float,
+    TextureFormat::D32FloatS8Uint,
+    TextureFormat::S8Uint,
+    TextureFormat::BC1RGBAUnorm,
+    TextureFormat::BC1RGBAUnormSRGB,
+    TextureFormat::BC2RGBAUnorm,
+    TextureFormat::BC2RGBAUnormSRGB,
+    TextureFormat::BC3RGBAUnorm,
+    TextureFormat::BC3RGBAUnormSRGB,
+    TextureFormat::BC4RUnorm,
+    TextureFormat::BC4RSnorm,
+    TextureFormat::BC5RGUnorm,
+    TextureFormat::BC5RGSnorm,
+    TextureFormat::BC6HRGBUfloat,
+    TextureFormat::BC6HRGBSfloat,
+    TextureFormat::BC7RGBAUnorm,
+    TextureFormat::BC7RGBAUnormSRGB,
+    TextureFormat::ETC2RGB8Unorm,
+    TextureFormat::ETC2RGB8UnormSRGB,
+    TextureFormat::ETC2RGB8A1Unorm,
+    TextureFormat::ETC2RGB8A1UnormSRGB,
+    TextureFormat::ETC2RGBA8Unorm,
+    TextureFormat::ETC2RGBA8UnormSRGB,
+    TextureFormat::EACR11Unorm,
+    TextureFormat::EACR11Snorm,
+    TextureFormat::EACRG11Unorm,
+    TextureFormat::EACRG11Snorm,
+    TextureFormat::ASTC4x4Unorm,
+    TextureFormat::ASTC4x4UnormSRGB,
+    TextureFormat::ASTC5x4Unorm,
+    TextureFormat::ASTC5x4UnormSRGB,
+    TextureFormat::ASTC5x5Unorm,
+    TextureFormat::ASTC5x5UnormSRGB,
+    TextureFormat::ASTC6x5Unorm,
+    TextureFormat::ASTC6x5UnormSRGB,
+    TextureFormat::ASTC6x6Unorm,
+    TextureFormat::ASTC6x6UnormSRGB,
+    TextureFormat::ASTC8x5Unorm,
+    TextureFormat::ASTC8x5UnormSRGB,
+    TextureFormat::ASTC8x6Unorm,
+    TextureFormat::ASTC8x6UnormSRGB,
+    TextureFormat::ASTC8x8Unorm,
+    TextureFormat::ASTC8x8UnormSRGB,
+    TextureFormat::ASTC10x5Unorm,
+    TextureFormat::ASTC10x5UnormSRGB,
+    TextureFormat::ASTC10x6Unorm,
+    TextureFormat::ASTC10x6UnormSRGB,
+    TextureFormat::ASTC10x8Unorm,
+    TextureFormat::ASTC10x8UnormSRGB,
+    TextureFormat::ASTC10x10Unorm,
+    TextureFormat::ASTC10x10UnormSRGB,
+    TextureFormat::ASTC12x10Unorm,
+    TextureFormat::ASTC12x10UnormSRGB,
+    TextureFormat::ASTC12x12Unorm,
+    TextureFormat::ASTC12x12UnormSRGB,
+];
+
+/// The texture format of a Vulkan format, e.g. of a surface
+pub fn texture_format(format: vk::Format) -> Option<TextureFormat> {
+    TEXTURE_FORMATS
+        .iter()
+        .cloned()
+        .find(|texture_format| image_format(*texture_format) == format)
 }
 
+/// The size in bytes of a texel block, which is a single texel for uncompressed formats
 #[rustfmt::skip]
 pub fn pixel_size(format: TextureFormat) -> u32 {
     match format {
@@ -137,19 +393,100 @@ pub fn pixel_size(format: TextureFormat) -> u32 {
         TextureFormat::R8UnormSRGB |
         TextureFormat::R8Snorm |
         TextureFormat::R8Uint |
-        TextureFormat::R8Sint
+        TextureFormat::R8Sint |
+        TextureFormat::S8Uint
         => 1,
+        TextureFormat::R16Uint |
+        TextureFormat::R16Sint |
+        TextureFormat::R16Float |
         TextureFormat::R8G8Unorm |
-        TextureFormat::R8G8Uint
+        TextureFormat::R8G8Snorm |
+        TextureFormat::R8G8Uint |
+        TextureFormat::R8G8Sint |
+        TextureFormat::D16Unorm
         => 2,
+        TextureFormat::R32Uint |
+        TextureFormat::R32Sint |
+        TextureFormat::R32Float |
+        TextureFormat::RG16Uint |
+        TextureFormat::RG16Sint |
+        TextureFormat::RG16Float |
         TextureFormat::R8G8B8A8Unorm |
         TextureFormat::R8G8B8A8UnormSRGB |
+        TextureFormat::R8G8B8A8Snorm |
         TextureFormat::R8G8B8A8Uint |
+        TextureFormat::R8G8B8A8Sint |
         TextureFormat::B8G8R8A8Unorm |
-        TextureFormat::B8G8R8A8UnormSRGB
-        => 4,
+        TextureFormat::B8G8R8A8UnormSRGB |
+        TextureFormat::RGB10A2Unorm |
+        TextureFormat::RG11B10Float |
+        TextureFormat::D24UnormS8Uint |
+        TextureFormat::D24PlusS8 |
         TextureFormat::D32Float
+        => 4,
+        TextureFormat::RG32Uint |
+        TextureFormat::RG32Sint |
+        TextureFormat::RG32Float |
+        TextureFormat::RGBA16Uint |
+        TextureFormat::RGBA16Sint |
+        TextureFormat::RGBA16Float |
+        TextureFormat::BC1RGBAUnorm |
+        TextureFormat::BC1RGBAUnormSRGB |
+        TextureFormat::BC4RUnorm |
+        TextureFormat::BC4RSnorm |
+        TextureFormat::ETC2RGB8Unorm |
+        TextureFormat::ETC2RGB8UnormSRGB |
+        TextureFormat::ETC2RGB8A1Unorm |
+        TextureFormat::ETC2RGB8A1UnormSRGB |
+        TextureFormat::EACR11Unorm |
+        TextureFormat::EACR11Snorm
         => 8,
+        TextureFormat::RGBA32Uint |
+        TextureFormat::RGBA32Sint |
+        TextureFormat::RGBA32Float |
+        TextureFormat::BC2RGBAUnorm |
+        TextureFormat::BC2RGBAUnormSRGB |
+        TextureFormat::BC3RGBAUnorm |
+        TextureFormat::BC3RGBAUnormSRGB |
+        TextureFormat::BC5RGUnorm |
+        TextureFormat::BC5RGSnorm |
+        TextureFormat::BC6HRGBUfloat |
+        TextureFormat::BC6HRGBSfloat |
+        TextureFormat::BC7RGBAUnorm |
+        TextureFormat::BC7RGBAUnormSRGB |
+        TextureFormat::ETC2RGBA8Unorm |
+        TextureFormat::ETC2RGBA8UnormSRGB |
+        TextureFormat::EACRG11Unorm |
+        TextureFormat::EACRG11Snorm |
+        TextureFormat::ASTC4x4Unorm |
+        TextureFormat::ASTC4x4UnormSRGB |
+        TextureFormat::ASTC5x4Unorm |
+        TextureFormat::ASTC5x4UnormSRGB |
+        TextureFormat::ASTC5x5Unorm |
+        TextureFormat::ASTC5x5UnormSRGB |
+        TextureFormat::ASTC6x5Unorm |
+        TextureFormat::ASTC6x5UnormSRGB |
+        TextureFormat::ASTC6x6Unorm |
+        TextureFormat::ASTC6x6UnormSRGB |
+        TextureFormat::ASTC8x5Unorm |
+        TextureFormat::ASTC8x5UnormSRGB |
+        TextureFormat::ASTC8x6Unorm |
+        TextureFormat::ASTC8x6UnormSRGB |
+        TextureFormat::ASTC8x8Unorm |
+        TextureFormat::ASTC8x8UnormSRGB |
+        TextureFormat::ASTC10x5Unorm |
+        TextureFormat::ASTC10x5UnormSRGB |
+        TextureFormat::ASTC10x6Unorm |
+        TextureFormat::ASTC10x6UnormSRGB |
+        TextureFormat::ASTC10x8Unorm |
+        TextureFormat::ASTC10x8UnormSRGB |
+        TextureFormat::ASTC10x10Unorm |
+        TextureFormat::ASTC10x10UnormSRGB |
+        TextureFormat::ASTC12x10Unorm |
+        TextureFormat::ASTC12x10UnormSRGB |
+        TextureFormat::ASTC12x12Unorm |
+        TextureFormat::ASTC12x12UnormSRGB
+        => 16,
         // TODO: D32FloatS8Uint
         // Dawn has this as "8", but the Vulkan spec states:
         //
@@ -160,8 +497,28 @@ pub fn pixel_size(format: TextureFormat) -> u32 {
         // This sounds like 64 bits total?
         TextureFormat::D32FloatS8Uint
         => 16,
-        TextureFormat::RGBA32Float
-        => 32,
+    }
+}
+
+/// The width and height in texels of a texel block. Copies of compressed formats operate on whole blocks.
+pub fn block_dimensions(format: TextureFormat) -> (u32, u32) {
+    match format {
+        TextureFormat::ASTC4x4Unorm | TextureFormat::ASTC4x4UnormSRGB => (4, 4),
+        TextureFormat::ASTC5x4Unorm | TextureFormat::ASTC5x4UnormSRGB => (5, 4),
+        TextureFormat::ASTC5x5Unorm | TextureFormat::ASTC5x5UnormSRGB => (5, 5),
+        TextureFormat::ASTC6x5Unorm | TextureFormat::ASTC6x5UnormSRGB => (6, 5),
+        TextureFormat::ASTC6x6Unorm | TextureFormat::ASTC6x6UnormSRGB => (6, 6),
+        TextureFormat::ASTC8x5Unorm | TextureFormat::ASTC8x5UnormSRGB => (8, 5),
+        TextureFormat::ASTC8x6Unorm | TextureFormat::ASTC8x6UnormSRGB => (8, 6),
+        TextureFormat::ASTC8x8Unorm | TextureFormat::ASTC8x8UnormSRGB => (8, 8),
+        TextureFormat::ASTC10x5Unorm | TextureFormat::ASTC10x5UnormSRGB => (10, 5),
+        TextureFormat::ASTC10x6Unorm | TextureFormat::ASTC10x6UnormSRGB => (10, 6),
+        TextureFormat::ASTC10x8Unorm | TextureFormat::ASTC10x8UnormSRGB => (10, 8),
+        TextureFormat::ASTC10x10Unorm | TextureFormat::ASTC10x10UnormSRGB => (10, 10),
+        TextureFormat::ASTC12x10Unorm | TextureFormat::ASTC12x10UnormSRGB => (12, 10),
+        TextureFormat::ASTC12x12Unorm | TextureFormat::ASTC12x12UnormSRGB => (12, 12),
+        _ if is_compressed(format) => (4, 4),
+        _ => (1, 1),
     }
 }
 
@@ -286,24 +643,16 @@ pub fn image_layout(usage: TextureUsageFlags, format: TextureFormat) -> vk::Imag
     }
 }
 
-pub fn default_texture_view_descriptor(texture: &TextureInner) -> TextureViewDescriptor {
+pub fn default_texture_view_descriptor(texture: &TextureInner) -> TextureViewDescriptor<'static> {
     let aspect_flags = aspect_mask(texture.descriptor.format);
     let aspect = unsafe { std::mem::transmute(aspect_flags) };
 
-    //    let dimension = if texture.descriptor.array_layer_count >= 6
-    //        && texture.descriptor.size.width == texture.descriptor.size.height
-    //    {
-    //        TextureViewDimension::Cube
-    //    } else {
-    //        match texture.descriptor.dimension {
-    //            TextureDimension::D1 => TextureViewDimension::D1,
-    //            TextureDimension::D2 => TextureViewDimension::D2,
-    //            TextureDimension::D3 => TextureViewDimension::D3,
-    //        }
-    //    };
-
+    // cube views have to be requested explicitly, textures with several layers default to arrays
+    let array = texture.descriptor.array_layer_count > 1;
     let dimension = match texture.descriptor.dimension {
+        TextureDimension::D1 if array => TextureViewDimension::D1Array,
         TextureDimension::D1 => TextureViewDimension::D1,
+        TextureDimension::D2 if array => TextureViewDimension::D2Array,
         TextureDimension::D2 => TextureViewDimension::D2,
         TextureDimension::D3 => TextureViewDimension::D3,
     };
@@ -316,6 +665,7 @@ pub fn default_texture_view_descriptor(texture: &TextureInner) -> TextureViewDes
         base_array_layer: 0,
         base_mip_level: 0,
         aspect,
+        label: None,
     }
 }
 
@@ -328,6 +678,14 @@ fn allocation_flags(format: TextureFormat) -> AllocationCreateFlags {
 }
 
 impl Texture {
+    pub fn set_label(&self, label: &str) {
+        self.inner.label.set(&self.inner.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+
     pub fn create_view(&self, descriptor: TextureViewDescriptor) -> Result<TextureView, Error> {
         let texture_view = TextureViewInner::new(self.inner.clone(), descriptor)?;
         Ok(texture_view.into())
@@ -346,11 +704,308 @@ impl Texture {
     pub fn mip_level_count(&self) -> u32 {
         self.inner.descriptor.mip_level_count
     }
+
+    pub fn array_layer_count(&self) -> u32 {
+        self.inner.descriptor.array_layer_count
+    }
+
+    /// Every mip level and array layer of the texture
+    pub fn subresource_range(&self) -> TextureSubresourceRange {
+        TextureSubresourceRange {
+            base_mip_level: 0,
+            mip_level_count: self.inner.descriptor.mip_level_count,
+            base_array_layer: 0,
+            array_layer_count: self.inner.descriptor.array_layer_count,
+        }
+    }
+}
+
+fn validate_texture_limits(limits: &Limits, descriptor: &TextureDescriptor) -> Result<(), Error> {
+    let size = descriptor.size;
+    let (max_dimension, exceeded) = match descriptor.dimension {
+        TextureDimension::D1 => (
+            limits.max_texture_dimension_1d,
+            size.width > limits.max_texture_dimension_1d,
+        ),
+        TextureDimension::D2 => (
+            limits.max_texture_dimension_2d,
+            size.width > limits.max_texture_dimension_2d || size.height > limits.max_texture_dimension_2d,
+        ),
+        TextureDimension::D3 => (
+            limits.max_texture_dimension_3d,
+            size.width > limits.max_texture_dimension_3d
+                || size.height > limits.max_texture_dimension_3d
+                || size.depth > limits.max_texture_dimension_3d,
+        ),
+    };
+    if exceeded {
+        log::error!(
+            "texture size {:?} exceeds max_texture_dimension ({}) for {:?} (label: {:?})",
+            size,
+            max_dimension,
+            descriptor.dimension,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    if descriptor.array_layer_count > limits.max_texture_array_layers {
+        log::error!(
+            "texture array_layer_count ({}) exceeds max_texture_array_layers ({}) (label: {:?})",
+            descriptor.array_layer_count,
+            limits.max_texture_array_layers,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    Ok(())
+}
+
+fn validate_texture_format(adapter: &AdapterInner, descriptor: &TextureDescriptor) -> Result<(), Error> {
+    let capabilities = adapter.texture_format_capabilities(descriptor.format);
+    let required = [
+        (TextureUsageFlags::SAMPLED, capabilities.sampled),
+        (TextureUsageFlags::STORAGE, capabilities.storage),
+        (TextureUsageFlags::OUTPUT_ATTACHMENT, capabilities.render_attachment),
+    ];
+    for (usage, supported) in required.iter() {
+        if descriptor.usage.intersects(*usage) && !supported {
+            let msg = format!(
+                "Texture format {:?} does not support usage {:?} (label: {:?})",
+                descriptor.format, usage, descriptor.label
+            );
+            log::error!("{}", msg);
+            return Err(Error::from(msg));
+        }
+    }
+    if !capabilities.supports_sample_count(descriptor.sample_count) {
+        let msg = format!(
+            "Texture format {:?} does not support sample count {} (supported: {:#b}, label: {:?})",
+            descriptor.format, descriptor.sample_count, capabilities.sample_counts, descriptor.label
+        );
+        log::error!("{}", msg);
+        return Err(Error::from(msg));
+    }
+    Ok(())
+}
+
+/// Checks that a copied region of a texture consists of whole texel blocks. Regions of compressed
+/// formats may end at the edge of the mip level, which does not need to be a multiple of the block size.
+pub fn validate_texture_copy(view: &TextureCopyView, size: Extent3D) -> Result<(), Error> {
+    let texture = &view.texture.inner;
+    let descriptor = &texture.descriptor;
+    let (mip_level, origin) = (view.mip_level, view.origin);
+    if mip_level >= descriptor.mip_level_count {
+        log::error!(
+            "texture copy mip level {} exceeds the {} levels of the texture (label: {:?})",
+            mip_level,
+            descriptor.mip_level_count,
+            texture.label.get()
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    let layer_end = view.array_layer + view.array_layer_count;
+    if view.array_layer_count == 0 || layer_end > descriptor.array_layer_count {
+        log::error!(
+            "texture copy layers {}..{} are outside the {} layers of the texture (label: {:?})",
+            view.array_layer,
+            layer_end,
+            descriptor.array_layer_count,
+            texture.label.get()
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    let format = descriptor.format;
+    let (block_width, block_height) = block_dimensions(format);
+    let mip_width = (descriptor.size.width >> mip_level).max(1);
+    let mip_height = (descriptor.size.height >> mip_level).max(1);
+    // only 3D textures have depth, the layers of array textures are selected separately
+    let mip_depth = match descriptor.dimension {
+        TextureDimension::D3 => (descriptor.size.depth >> mip_level).max(1),
+        _ => 1,
+    };
+    let within = |origin: i32, size: u32, mip_size: u32| origin >= 0 && origin as u32 + size <= mip_size;
+    if !within(origin.x, size.width, mip_width)
+        || !within(origin.y, size.height, mip_height)
+        || !within(origin.z, size.depth, mip_depth)
+    {
+        log::error!(
+            "texture copy at {:?} of size {:?} exceeds mip level {} of size {}x{}x{} (label: {:?})",
+            origin,
+            size,
+            mip_level,
+            mip_width,
+            mip_height,
+            mip_depth,
+            texture.label.get()
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    let aligned = |origin: i32, size: u32, block: u32, mip_size: u32| {
+        origin as u32 % block == 0 && (size % block == 0 || origin as u32 + size == mip_size)
+    };
+    if !aligned(origin.x, size.width, block_width, mip_width)
+        || !aligned(origin.y, size.height, block_height, mip_height)
+    {
+        log::error!(
+            "texture copy at {:?} of size {:?} is not aligned to the {}x{} blocks of {:?} (label: {:?})",
+            origin,
+            size,
+            block_width,
+            block_height,
+            format,
+            texture.label.get()
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    Ok(())
+}
+
+/// Converts a row pitch in bytes to Vulkan's row length in texels
+pub fn buffer_row_length(format: TextureFormat, bytes_per_row: u32) -> u32 {
+    let (block_width, _) = block_dimensions(format);
+    bytes_per_row / pixel_size(format) * block_width
+}
+
+/// Checks the layout of texture data in a buffer. `bytes_per_row` must cover whole texel blocks,
+/// `rows_per_image` whole rows of blocks and the copy must fit in the buffer.
+pub fn validate_buffer_copy(buffer: &BufferCopyView, texture: &TextureCopyView, size: Extent3D) -> Result<(), Error> {
+    let format = texture.texture.inner.descriptor.format;
+    if format == TextureFormat::D24PlusS8 {
+        log::error!(
+            "{:?} textures can not be copied to or from buffers (label: {:?})",
+            format,
+            texture.texture.inner.label.get()
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    let (block_width, block_height) = block_dimensions(format);
+    let block_size = pixel_size(format);
+    if buffer.bytes_per_row % block_size != 0 || buffer.rows_per_image % block_height != 0 {
+        log::error!(
+            "buffer bytes per row {} and rows per image {} do not cover whole {}x{} blocks of {} bytes for {:?}",
+            buffer.bytes_per_row,
+            buffer.rows_per_image,
+            block_width,
+            block_height,
+            block_size,
+            format
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    // depth and stencil aspects are copied separately, and only need 4 byte alignment
+    let offset_alignment = if is_depth_or_stencil(format) {
+        4
+    } else {
+        block_size as usize
+    };
+    if buffer.offset % offset_alignment != 0 {
+        log::error!(
+            "buffer offset {} is not a multiple of {} bytes for {:?}",
+            buffer.offset,
+            offset_alignment,
+            format
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    let width_blocks = size.width.div_ceil(block_width);
+    let height_blocks = size.height.div_ceil(block_height);
+    let row_bytes = width_blocks * block_size;
+    // zero means tightly packed, like in Vulkan
+    let bytes_per_row = match buffer.bytes_per_row {
+        0 => row_bytes,
+        bytes_per_row => bytes_per_row,
+    };
+    let rows_per_image = match buffer.rows_per_image {
+        0 => height_blocks * block_height,
+        rows_per_image => rows_per_image,
+    };
+    if bytes_per_row < row_bytes || rows_per_image < size.height {
+        log::error!(
+            "buffer bytes per row {} and rows per image {} are smaller than the copy size {:?} of {:?}",
+            bytes_per_row,
+            rows_per_image,
+            size,
+            format
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+
+    // the layers of array textures are laid out like the slices of 3D textures
+    let images = u64::from(size.depth) * u64::from(texture.array_layer_count);
+    let required_size = if width_blocks == 0 || height_blocks == 0 || images == 0 {
+        0
+    } else {
+        let image_bytes = u64::from(bytes_per_row) * u64::from(rows_per_image / block_height);
+        image_bytes * (images - 1) + u64::from(bytes_per_row) * u64::from(height_blocks - 1) + u64::from(row_bytes)
+    };
+    let buffer_size = buffer.buffer.inner.descriptor.size as u64;
+    if buffer.offset as u64 + required_size > buffer_size {
+        log::error!(
+            "buffer copy of {} bytes at offset {} overruns the buffer of {} bytes (label: {:?})",
+            required_size,
+            buffer.offset,
+            buffer_size,
+            buffer.buffer.inner.label.get()
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    Ok(())
+}
+
+fn validate_texture_compression(extensions: &Extensions, descriptor: &TextureDescriptor) -> Result<(), Error> {
+    let compression = match compression(descriptor.format) {
+        Some(compression) => compression,
+        None => return Ok(()),
+    };
+    let (extension, enabled) = match compression {
+        Compression::Bc => ("texture_compression_bc", extensions.texture_compression_bc),
+        Compression::Etc2 => ("texture_compression_etc2", extensions.texture_compression_etc2),
+        Compression::Astc => ("texture_compression_astc", extensions.texture_compression_astc),
+    };
+    if !enabled {
+        log::error!(
+            "texture format {:?} requires the {} extension (label: {:?})",
+            descriptor.format,
+            extension,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    if descriptor.dimension != TextureDimension::D2 {
+        log::error!(
+            "compressed texture format {:?} requires a 2D texture (label: {:?})",
+            descriptor.format,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    let (block_width, block_height) = block_dimensions(descriptor.format);
+    if descriptor.size.width % block_width != 0 || descriptor.size.height % block_height != 0 {
+        log::error!(
+            "texture size {}x{} is not a multiple of the {}x{} blocks of {:?} (label: {:?})",
+            descriptor.size.width,
+            descriptor.size.height,
+            block_width,
+            block_height,
+            descriptor.format,
+            descriptor.label
+        );
+        return Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT));
+    }
+    Ok(())
 }
 
 impl TextureInner {
     pub fn new(device: Arc<DeviceInner>, descriptor: TextureDescriptor) -> Result<TextureInner, Error> {
-        let flags = if descriptor.array_layer_count >= 6 && descriptor.size.width == descriptor.size.height {
+        validate_texture_limits(&device.limits, &descriptor)?;
+        validate_texture_format(&device.adapter, &descriptor)?;
+        validate_texture_compression(&device.extensions, &descriptor)?;
+
+        let flags = if is_cube_compatible(&descriptor) {
             vk::ImageCreateFlags::CUBE_COMPATIBLE
         } else {
             vk::ImageCreateFlags::empty()
@@ -359,7 +1014,7 @@ impl TextureInner {
         let create_info = vk::ImageCreateInfo {
             flags,
             image_type: image_type(descriptor.dimension),
-            format: image_format(descriptor.format),
+            format: device.adapter.image_format(descriptor.format),
             extent: util::extent_3d(descriptor.size),
             mip_levels: descriptor.mip_level_count,
             array_layers: descriptor.array_layer_count,
@@ -407,23 +1062,32 @@ impl TextureInner {
             }
         }
 
-        let (image, allocation, allocation_info) = result.expect("failed to create image"); // TODO
+        let (image, allocation, allocation_info) = result?;
 
         log::trace!("created image: {:?}, allocation_info: {:?}", image, allocation_info);
 
+        state
+            .memory_usage_mut()
+            .add_texture(descriptor.usage, allocation_info.get_size());
+        drop(state);
+
         let subresource_usage = SubresourceUsageTracker::new(
             descriptor.mip_level_count,
             descriptor.array_layer_count,
             descriptor.format,
         );
 
+        let label = debug::Label::new(&device, image, descriptor.label);
+
         Ok(TextureInner {
             handle: image,
             device: device.clone(),
             allocation: Some(allocation),
             allocation_info: Some(allocation_info),
-            descriptor,
+            descriptor: descriptor.without_label(),
             subresource_usage: Mutex::new(subresource_usage),
+            queue_family_index: Mutex::new(device.queue.queue_family_index),
+            label,
         })
     }
 
@@ -432,15 +1096,18 @@ impl TextureInner {
         &self,
         command_buffer: vk::CommandBuffer,
         usage: TextureUsageFlags,
-        subresource: Option<Subresource>,
+        subresource_range: Option<SubresourceRange>,
     ) -> Result<(), Error> {
         let format = self.descriptor.format;
 
+        // the texture is owned by the queue family that the command buffer was allocated for
+        let queue_flags = self.device.queue_flags(*self.queue_family_index.lock());
+
         // log2(32768) + 1 = 16; enough barriers on the stack for a non-array image with mipmaps, up to 32768 x 32768
         let mut image_memory_barriers = SmallVec::<[vk::ImageMemoryBarrier; 16]>::new();
 
         let mut src_stage_mask = vk::PipelineStageFlags::empty();
-        let dst_stage_mask = pipeline_stage(usage, format);
+        let dst_stage_mask = queue::supported_stages(queue_flags, pipeline_stage(usage, format));
 
         let mut add_image_memory_barrier =
             |range: vk::ImageSubresourceRange, range_last_usage: &mut TextureUsageFlags| {
@@ -455,8 +1122,8 @@ impl TextureInner {
 
                 src_stage_mask |= pipeline_stage(*range_last_usage, format);
 
-                let src_access_mask = access_flags(*range_last_usage, format);
-                let dst_access_mask = access_flags(usage, format);
+                let src_access_mask = queue::supported_access(queue_flags, access_flags(*range_last_usage, format));
+                let dst_access_mask = queue::supported_access(queue_flags, access_flags(usage, format));
 
                 let old_layout = image_layout(*range_last_usage, format);
                 let new_layout = image_layout(usage, format);
@@ -499,11 +1166,13 @@ impl TextureInner {
                 image_memory_barriers.push(image_memory_barrier);
             };
 
-        match subresource {
-            Some(subresource) => {
+        match subresource_range {
+            Some(subresource_range) => {
                 let mut subresource_usage = self.subresource_usage.lock();
-                let (range, range_last_usage) = subresource_usage.usage_mut(subresource);
-                add_image_memory_barrier(range, range_last_usage);
+                for subresource in subresource_range.iter() {
+                    let (range, range_last_usage) = subresource_usage.usage_mut(subresource);
+                    add_image_memory_barrier(range, range_last_usage);
+                }
             }
             None => {
                 for (range, range_last_usage) in self.subresource_usage.lock().iter_mut() {
@@ -520,7 +1189,7 @@ impl TextureInner {
             unsafe {
                 self.device.raw.cmd_pipeline_barrier(
                     command_buffer,
-                    src_stage_mask,
+                    queue::supported_stages(queue_flags, src_stage_mask),
                     dst_stage_mask,
                     dependency_flags,
                     memory_barriers,
@@ -532,6 +1201,98 @@ impl TextureInner {
 
         Ok(())
     }
+
+    /// Returns true if none of the subresources has been used yet
+    pub fn is_unused(&self) -> bool {
+        self.subresource_usage
+            .lock()
+            .iter()
+            .all(|(_, usage)| usage == TextureUsageFlags::NONE)
+    }
+
+    /// Records the release (on the source queue) or acquire (on the destination queue) half of a
+    /// queue family ownership transfer. The layout of each subresource is left unchanged.
+    pub fn transfer_queue_family(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        queue: &QueueInfo,
+        src_queue_family_index: u32,
+        dst_queue_family_index: u32,
+    ) {
+        let format = self.descriptor.format;
+        let release = queue.queue_family_index == src_queue_family_index;
+
+        let mut image_memory_barriers = SmallVec::<[vk::ImageMemoryBarrier; 16]>::new();
+        let mut src_stage_mask = vk::PipelineStageFlags::empty();
+
+        for (range, usage) in self.subresource_usage.lock().iter() {
+            if usage == TextureUsageFlags::NONE {
+                continue;
+            }
+
+            let layout = image_layout(usage, format);
+            let (src_access_mask, dst_access_mask) = if release {
+                src_stage_mask |= pipeline_stage(usage, format);
+                (
+                    queue::supported_access(queue.queue_flags, access_flags(usage, format)),
+                    vk::AccessFlags::empty(),
+                )
+            } else {
+                (
+                    vk::AccessFlags::empty(),
+                    vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE,
+                )
+            };
+
+            image_memory_barriers.push(vk::ImageMemoryBarrier {
+                src_access_mask,
+                dst_access_mask,
+                old_layout: layout,
+                new_layout: layout,
+                image: self.handle,
+                subresource_range: range,
+                src_queue_family_index,
+                dst_queue_family_index,
+                ..Default::default()
+            });
+        }
+
+        log::trace!(
+            "transfer_queue_family texture: {:?}, release: {}, src_queue_family_index: {}, dst_queue_family_index: {}",
+            self.handle,
+            release,
+            src_queue_family_index,
+            dst_queue_family_index
+        );
+
+        if image_memory_barriers.is_empty() {
+            return;
+        }
+
+        let (src_stage_mask, dst_stage_mask) = if release {
+            (
+                queue::supported_stages(queue.queue_flags, src_stage_mask),
+                vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+            )
+        } else {
+            (
+                vk::PipelineStageFlags::TOP_OF_PIPE,
+                vk::PipelineStageFlags::ALL_COMMANDS,
+            )
+        };
+
+        unsafe {
+            self.device.raw.cmd_pipeline_barrier(
+                command_buffer,
+                src_stage_mask,
+                dst_stage_mask,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &image_memory_barriers,
+            );
+        }
+    }
 }
 
 impl Into<Texture> for TextureInner {
@@ -544,6 +1305,11 @@ impl Drop for TextureInner {
     fn drop(&mut self) {
         if let Some(allocation) = self.allocation.as_ref() {
             let mut state = self.device.state.lock();
+            if let Some(allocation_info) = self.allocation_info.as_ref() {
+                state
+                    .memory_usage_mut()
+                    .remove_texture(self.descriptor.usage, allocation_info.get_size());
+            }
             let serial = state.get_next_pending_serial();
             state
                 .get_fenced_deleter()
@@ -552,8 +1318,77 @@ impl Drop for TextureInner {
     }
 }
 
+fn validate_texture_view(texture: &TextureInner, descriptor: &TextureViewDescriptor) -> Result<(), Error> {
+    let texture_descriptor = &texture.descriptor;
+    let fail = |msg: String| {
+        log::error!("{} (label: {:?})", msg, descriptor.label);
+        Err(Error::from(vk::Result::ERROR_VALIDATION_FAILED_EXT))
+    };
+
+    let layer_count = descriptor.array_layer_count;
+    if layer_count == 0 || descriptor.base_array_layer + layer_count > texture_descriptor.array_layer_count {
+        return fail(format!(
+            "texture view layers {}..{} are outside the {} layers of the texture",
+            descriptor.base_array_layer,
+            descriptor.base_array_layer + layer_count,
+            texture_descriptor.array_layer_count
+        ));
+    }
+    if descriptor.mip_level_count == 0
+        || descriptor.base_mip_level + descriptor.mip_level_count > texture_descriptor.mip_level_count
+    {
+        return fail(format!(
+            "texture view mip levels {}..{} are outside the {} levels of the texture",
+            descriptor.base_mip_level,
+            descriptor.base_mip_level + descriptor.mip_level_count,
+            texture_descriptor.mip_level_count
+        ));
+    }
+
+    let texture_dimension = match descriptor.dimension {
+        TextureViewDimension::D1 | TextureViewDimension::D1Array => TextureDimension::D1,
+        TextureViewDimension::D3 => TextureDimension::D3,
+        _ => TextureDimension::D2,
+    };
+    if texture_dimension != texture_descriptor.dimension {
+        return fail(format!(
+            "a {:?} view can not be created for a {:?} texture",
+            descriptor.dimension, texture_descriptor.dimension
+        ));
+    }
+
+    let valid_layer_count = match descriptor.dimension {
+        TextureViewDimension::D1 | TextureViewDimension::D2 | TextureViewDimension::D3 => layer_count == 1,
+        TextureViewDimension::D1Array | TextureViewDimension::D2Array => true,
+        TextureViewDimension::Cube => layer_count == 6,
+        TextureViewDimension::CubeArray => layer_count % 6 == 0,
+    };
+    if !valid_layer_count {
+        return fail(format!(
+            "a {:?} view can not have {} array layers",
+            descriptor.dimension, layer_count
+        ));
+    }
+
+    if descriptor.dimension == TextureViewDimension::Cube || descriptor.dimension == TextureViewDimension::CubeArray {
+        if !is_cube_compatible(texture_descriptor) {
+            return fail(format!(
+                "cube views require a square, single sampled 2D texture with at least 6 layers, found {:?}",
+                texture_descriptor
+            ));
+        }
+        if descriptor.dimension == TextureViewDimension::CubeArray && !texture.device.extensions.texture_cube_array {
+            return fail("cube array views require the texture_cube_array extension".to_owned());
+        }
+    }
+
+    Ok(())
+}
+
 impl TextureViewInner {
     pub fn new(texture: Arc<TextureInner>, descriptor: TextureViewDescriptor) -> Result<TextureViewInner, Error> {
+        validate_texture_view(&texture, &descriptor)?;
+
         let aspect_mask = unsafe { std::mem::transmute(descriptor.aspect) };
         let base_mip_level = descriptor.base_mip_level;
         let level_count = descriptor.mip_level_count;
@@ -562,7 +1397,7 @@ impl TextureViewInner {
         let view_type = image_view_type(&descriptor);
 
         let create_info = vk::ImageViewCreateInfo {
-            format: image_format(descriptor.format),
+            format: texture.device.adapter.image_format(descriptor.format),
             flags: vk::ImageViewCreateFlags::empty(),
             image: texture.handle,
             subresource_range: vk::ImageSubresourceRange {
@@ -588,10 +1423,13 @@ impl TextureViewInner {
 
         log::trace!("created image_view: {:?}", image_view);
 
+        let label = debug::Label::new(&texture.device, image_view, descriptor.label);
+
         Ok(TextureViewInner {
-            descriptor,
+            descriptor: descriptor.without_label(),
             handle: image_view,
             texture,
+            label,
         })
     }
 
@@ -623,6 +1461,16 @@ impl Drop for TextureViewInner {
 }
 
 impl TextureView {
+    pub fn set_label(&self, label: &str) {
+        self.inner
+            .label
+            .set(&self.inner.texture.device, self.inner.handle, label);
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.inner.label.get()
+    }
+
     /// Returns a handle to the associated `Texture`.
     pub fn texture(&self) -> Texture {
         Texture {
@@ -645,6 +1493,24 @@ pub struct Subresource {
     pub array_layer: u32,
 }
 
+/// A range of array layers in a single mip level
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SubresourceRange {
+    pub mip_level: u32,
+    pub base_array_layer: u32,
+    pub array_layer_count: u32,
+}
+
+impl SubresourceRange {
+    fn iter(self) -> impl Iterator<Item = Subresource> {
+        let layers = self.base_array_layer..self.base_array_layer + self.array_layer_count;
+        layers.map(move |array_layer| Subresource {
+            mip_level: self.mip_level,
+            array_layer,
+        })
+    }
+}
+
 impl SubresourceUsageTracker {
     pub fn new(mip_levels: u32, array_layers: u32, format: TextureFormat) -> SubresourceUsageTracker {
         let none = TextureUsageFlags::NONE;
@@ -679,6 +1545,20 @@ impl SubresourceUsageTracker {
         (range, usage)
     }
 
+    fn iter(&self) -> impl Iterator<Item = (vk::ImageSubresourceRange, TextureUsageFlags)> + '_ {
+        let aspect_mask = self.aspect_mask;
+        self.ranges.iter().map(move |(k, usage)| {
+            let range = vk::ImageSubresourceRange {
+                aspect_mask,
+                base_mip_level: k.mip_level,
+                level_count: 1,
+                base_array_layer: k.array_layer,
+                layer_count: 1,
+            };
+            (range, *usage)
+        })
+    }
+
     fn iter_mut(&mut self) -> impl Iterator<Item = (vk::ImageSubresourceRange, &mut TextureUsageFlags)> {
         let aspect_mask = self.aspect_mask;
         self.ranges.iter_mut().map(move |(k, usage)| {
@@ -693,3 +1573,46 @@ impl SubresourceUsageTracker {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texture_format_round_trip() {
+        for format in TEXTURE_FORMATS.iter().cloned() {
+            assert_eq!(Some(format), texture_format(image_format(format)));
+        }
+        assert_eq!(None, texture_format(vk::Format::R4G4_UNORM_PACK8));
+    }
+
+    #[test]
+    fn texture_format_blocks() {
+        assert_eq!((1, 1), block_dimensions(TextureFormat::RGBA16Float));
+        assert_eq!(8, pixel_size(TextureFormat::RGBA16Float));
+        assert_eq!((4, 4), block_dimensions(TextureFormat::BC1RGBAUnorm));
+        assert_eq!(8, pixel_size(TextureFormat::BC1RGBAUnorm));
+        assert_eq!((4, 4), block_dimensions(TextureFormat::EACRG11Snorm));
+        assert_eq!(16, pixel_size(TextureFormat::EACRG11Snorm));
+        assert_eq!((10, 8), block_dimensions(TextureFormat::ASTC10x8UnormSRGB));
+        assert_eq!(16, pixel_size(TextureFormat::ASTC10x8UnormSRGB));
+
+        assert_eq!(None, compression(TextureFormat::RG11B10Float));
+        assert_eq!(Some(Compression::Bc), compression(TextureFormat::BC7RGBAUnormSRGB));
+        assert_eq!(Some(Compression::Etc2), compression(TextureFormat::ETC2RGB8Unorm));
+        assert_eq!(Some(Compression::Astc), compression(TextureFormat::ASTC12x12UnormSRGB));
+    }
+
+    #[test]
+    fn texture_format_types() {
+        assert!(is_depth(TextureFormat::D16Unorm) && !is_stencil(TextureFormat::D16Unorm));
+        assert!(is_depth(TextureFormat::D24UnormS8Uint) && is_stencil(TextureFormat::D24UnormS8Uint));
+        assert!(is_depth(TextureFormat::D24PlusS8) && is_stencil(TextureFormat::D24PlusS8));
+        assert!(!is_depth(TextureFormat::S8Uint) && is_stencil(TextureFormat::S8Uint));
+        assert_eq!(ScalarType::Uint, scalar_type(TextureFormat::RG32Uint));
+        assert_eq!(ScalarType::Sint, scalar_type(TextureFormat::RGBA16Sint));
+        assert_eq!(ScalarType::Float, scalar_type(TextureFormat::R16Float));
+        assert!(is_integer(TextureFormat::R16Sint));
+        assert!(!is_integer(TextureFormat::RGB10A2Unorm));
+    }
+}
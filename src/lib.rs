@@ -13,19 +13,111 @@ mod macros;
 mod error;
 mod imp;
 
-pub use crate::error::{Error, ErrorKind, FenceError, SwapchainError, VkResult};
+pub use crate::error::{Error, ErrorKind, FenceError, InstanceError, PipelineError, SwapchainError, VkResult};
 pub use crate::imp::validate;
+pub use crate::imp::AdapterProperties;
+pub use ash::vk::{ObjectType as VkObjectType, PhysicalDeviceType as VkPhysicalDeviceType};
 
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::ops::Range;
+use std::path::PathBuf;
 
 #[derive(Clone, Debug)]
 pub struct Instance {
     inner: Arc<imp::InstanceInner>,
 }
 
+#[derive(Clone, Debug)]
+pub struct InstanceDescriptor {
+    /// Enable the validation layers. `Some(true)` fails instance creation when they are not
+    /// installed. `None` enables them in debug builds if they are present.
+    pub validation: Option<bool>,
+    /// Additional layers that must be present for instance creation to succeed
+    pub extra_layers: Vec<Cow<'static, str>>,
+    /// Additional extensions that must be present for instance creation to succeed
+    pub extra_extensions: Vec<Cow<'static, str>>,
+    /// The requested Vulkan API version as `(major, minor, patch)`
+    pub api_version: (u32, u32, u32),
+    pub app_name: Cow<'static, str>,
+    pub engine_name: Cow<'static, str>,
+}
+
+impl Default for InstanceDescriptor {
+    fn default() -> InstanceDescriptor {
+        InstanceDescriptor {
+            validation: None,
+            extra_layers: Vec::new(),
+            extra_extensions: Vec::new(),
+            api_version: (1, 0, 0),
+            app_name: Cow::Borrowed(""),
+            engine_name: Cow::Borrowed("vki"),
+        }
+    }
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DebugMessageSeverity {
+    Verbose,
+    Info,
+    Warning,
+    Error,
+}
+
+bitflags! {
+    #[repr(transparent)]
+    pub struct DebugMessageTypeFlags: u32 {
+        const GENERAL = 0b1;        // vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
+        const VALIDATION = 0b10;    // vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
+        const PERFORMANCE = 0b100;  // vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
+        const ALL = 0b111;
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DebugMessengerDescriptor {
+    /// Messages less severe than this are not delivered
+    pub min_severity: DebugMessageSeverity,
+    pub message_type: DebugMessageTypeFlags,
+}
+
+impl Default for DebugMessengerDescriptor {
+    fn default() -> DebugMessengerDescriptor {
+        DebugMessengerDescriptor {
+            min_severity: DebugMessageSeverity::Warning,
+            message_type: DebugMessageTypeFlags::ALL,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugMessageObject {
+    pub object_type: VkObjectType,
+    pub handle: u64,
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugMessage {
+    pub severity: DebugMessageSeverity,
+    pub message_type: DebugMessageTypeFlags,
+    pub message_id_name: Option<String>,
+    pub message_id_number: i32,
+    pub message: String,
+    pub queue_labels: Vec<String>,
+    pub command_buffer_labels: Vec<String>,
+    pub objects: Vec<DebugMessageObject>,
+}
+
+/// Receives messages until dropped
+#[derive(Clone, Debug)]
+pub struct DebugMessenger {
+    inner: Arc<imp::DebugMessengerInner>,
+}
+
 #[repr(u32)]
 #[derive(Copy, Clone, Debug)]
 pub enum PowerPreference {
@@ -39,14 +131,37 @@ impl Default for PowerPreference {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct AdapterOptions {
     pub power_preference: PowerPreference,
+    /// Only select adapters of this type, e.g. `VkPhysicalDeviceType::CPU` for a software rasterizer
+    pub device_type: Option<VkPhysicalDeviceType>,
+    /// Only select adapters whose name contains this string (case insensitive)
+    pub name: Option<Cow<'static, str>>,
+    /// Only select adapters with this PCI vendor id
+    pub vendor_id: Option<u32>,
 }
 
-#[derive(Clone, Debug, Default)]
+/// Optional device features. `Adapter::extensions` reports the features supported by an adapter and
+/// `DeviceDescriptor::extensions` selects the features that are enabled on a device.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Extensions {
-    pub anisotropic_filtering: bool,
+    pub depth_clamp: bool,
+    pub fill_mode_non_solid: bool,
+    pub wide_lines: bool,
+    pub multi_draw_indirect: bool,
+    pub sampler_anisotropy: bool,
+    pub texture_compression_bc: bool,
+    pub texture_compression_etc2: bool,
+    pub texture_compression_astc: bool,
+    pub independent_blend: bool,
+    pub shader_float64: bool,
+    pub robust_buffer_access: bool,
+    pub depth_bounds: bool,
+    pub sample_rate_shading: bool,
+    pub texture_cube_array: bool,
+    /// Backed by the `VK_EXT_conservative_rasterization` device extension
+    pub conservative_rasterization: bool,
 }
 
 #[derive(Clone)]
@@ -54,17 +169,61 @@ pub struct Adapter {
     inner: Arc<imp::AdapterInner>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Limits {
     pub max_bind_groups: u32,
+    pub max_bindings_per_bind_group: u32,
+    pub max_dynamic_uniform_buffers_per_pipeline_layout: u32,
+    pub max_dynamic_storage_buffers_per_pipeline_layout: u32,
+    pub max_uniform_buffer_binding_size: u32,
+    pub max_storage_buffer_binding_size: u32,
+    pub max_texture_dimension_1d: u32,
+    pub max_texture_dimension_2d: u32,
+    pub max_texture_dimension_3d: u32,
+    pub max_texture_array_layers: u32,
+    pub max_push_constant_size: u32,
+    pub min_uniform_buffer_offset_alignment: u32,
+    pub min_storage_buffer_offset_alignment: u32,
+}
+
+/// The defaults are the minimum values guaranteed by every Vulkan implementation.
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_bind_groups: 4,
+            max_bindings_per_bind_group: 16,
+            max_dynamic_uniform_buffers_per_pipeline_layout: 8,
+            max_dynamic_storage_buffers_per_pipeline_layout: 4,
+            max_uniform_buffer_binding_size: 16384,
+            max_storage_buffer_binding_size: 134_217_728,
+            max_texture_dimension_1d: 4096,
+            max_texture_dimension_2d: 4096,
+            max_texture_dimension_3d: 256,
+            max_texture_array_layers: 256,
+            max_push_constant_size: 128,
+            min_uniform_buffer_offset_alignment: 256,
+            min_storage_buffer_offset_alignment: 256,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct DeviceDescriptor<'a> {
     pub extensions: Extensions,
+    /// The limits that the device must support. When `None`, the device is created with the
+    /// adapter's limits.
+    pub required_limits: Option<Limits>,
     /// The queue created for the device will have support for the provided surface
     pub surface_support: Option<&'a Surface>,
-    // pub queue_descriptors: &'a [QueueDescriptor<'a>],
+    /// Additional queues to create alongside the graphics queue. Each is created from a dedicated
+    /// queue family when the adapter has one, otherwise `Device::get_queue` returns the graphics queue.
+    pub extra_queues: &'a [QueueType],
+    /// The capabilities of the device's main queue. `QueueType::Compute` and `QueueType::Transfer`
+    /// allow adapters without graphics support; render passes and render pipelines are then rejected.
+    pub queue_type: QueueType,
+    /// Data previously returned by `Device::pipeline_cache_data`. Data that was created by a
+    /// different driver or device is discarded.
+    pub pipeline_cache_data: Option<&'a [u8]>,
 }
 
 impl<'a> DeviceDescriptor<'a> {
@@ -72,6 +231,31 @@ impl<'a> DeviceDescriptor<'a> {
         self.surface_support = Some(surface);
         self
     }
+
+    pub fn with_extensions(mut self, extensions: Extensions) -> DeviceDescriptor<'a> {
+        self.extensions = extensions;
+        self
+    }
+
+    pub fn with_queue_type(mut self, queue_type: QueueType) -> DeviceDescriptor<'a> {
+        self.queue_type = queue_type;
+        self
+    }
+
+    pub fn with_extra_queues(mut self, queue_types: &'a [QueueType]) -> DeviceDescriptor<'a> {
+        self.extra_queues = queue_types;
+        self
+    }
+
+    pub fn with_required_limits(mut self, limits: Limits) -> DeviceDescriptor<'a> {
+        self.required_limits = Some(limits);
+        self
+    }
+
+    pub fn with_pipeline_cache_data(mut self, data: &'a [u8]) -> DeviceDescriptor<'a> {
+        self.pipeline_cache_data = Some(data);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -79,6 +263,51 @@ pub struct Device {
     inner: Arc<imp::DeviceInner>,
 }
 
+/// GPU memory statistics of a device, see `Device::memory_report`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    pub heaps: Vec<MemoryHeapReport>,
+    /// Bytes occupied by allocations, over all heaps
+    pub used_bytes: u64,
+    /// Bytes allocated from the driver but not occupied by allocations, over all heaps
+    pub unused_bytes: u64,
+    /// Number of `VkDeviceMemory` blocks
+    pub block_count: u32,
+    pub allocation_count: u32,
+    /// Live buffers grouped by their usage flags
+    pub buffers: Vec<BufferMemoryUsage>,
+    /// Live textures grouped by their usage flags. Swapchain images are not included.
+    pub textures: Vec<TextureMemoryUsage>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoryHeapReport {
+    pub size: u64,
+    pub device_local: bool,
+    /// Amount of memory the process can allocate from the heap, as reported by VK_EXT_memory_budget.
+    ///
+    /// `None` when the adapter does not support the extension.
+    pub budget: Option<u64>,
+    pub used_bytes: u64,
+    pub unused_bytes: u64,
+    pub block_count: u32,
+    pub allocation_count: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BufferMemoryUsage {
+    pub usage: BufferUsageFlags,
+    pub count: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextureMemoryUsage {
+    pub usage: TextureUsageFlags,
+    pub count: usize,
+    pub size_bytes: u64,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct SwapchainDescriptor<'a> {
     pub surface: &'a Surface,
@@ -140,6 +369,29 @@ pub struct Surface {
     inner: Arc<imp::SurfaceInner>,
 }
 
+/// How `Device::poll` treats work that is still executing
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Maintain {
+    /// Block until all submitted work has completed
+    Wait,
+    /// Only reclaim work that has already completed
+    Poll,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum QueueType {
+    Graphics,
+    Compute,
+    Transfer,
+}
+
+impl Default for QueueType {
+    fn default() -> QueueType {
+        QueueType::Graphics
+    }
+}
+
 pub struct Queue {
     inner: imp::QueueInner,
 }
@@ -184,24 +436,114 @@ pub enum TextureFormat {
     R8Uint,
     R8Sint,
 
-    // TODO: Update 16-bit formats
+    // 16-bit formats
+    R16Uint,
+    R16Sint,
+    R16Float,
     R8G8Unorm,
+    R8G8Snorm,
     R8G8Uint,
-    //
-    //    Rgb8Unorm,
-
-    // TODO: Update 32-bit formats
+    R8G8Sint,
+
+    // 32-bit formats
+    R32Uint,
+    R32Sint,
+    R32Float,
+    RG16Uint,
+    RG16Sint,
+    RG16Float,
     R8G8B8A8Unorm,
     R8G8B8A8UnormSRGB,
+    R8G8B8A8Snorm,
     R8G8B8A8Uint,
+    R8G8B8A8Sint,
     B8G8R8A8Unorm,
     B8G8R8A8UnormSRGB,
-
-    // TODO: Update 128-bit formats
+    RGB10A2Unorm,
+    RG11B10Float,
+
+    // 64-bit formats
+    RG32Uint,
+    RG32Sint,
+    RG32Float,
+    RGBA16Uint,
+    RGBA16Sint,
+    RGBA16Float,
+
+    // 128-bit formats
+    RGBA32Uint,
+    RGBA32Sint,
     RGBA32Float,
 
+    // Depth and stencil formats
+    D16Unorm,
+    /// Not supported by every adapter, see `D24PlusS8`
+    D24UnormS8Uint,
+    /// `D24UnormS8Uint` where the adapter supports it as a depth stencil attachment and
+    /// `D32FloatS8Uint` otherwise. Since the layout of the texels depends on the adapter, it can not
+    /// be copied to or from buffers.
+    D24PlusS8,
     D32Float,
     D32FloatS8Uint,
+    S8Uint,
+
+    // BC compressed formats, 4x4 blocks
+    BC1RGBAUnorm,
+    BC1RGBAUnormSRGB,
+    BC2RGBAUnorm,
+    BC2RGBAUnormSRGB,
+    BC3RGBAUnorm,
+    BC3RGBAUnormSRGB,
+    BC4RUnorm,
+    BC4RSnorm,
+    BC5RGUnorm,
+    BC5RGSnorm,
+    BC6HRGBUfloat,
+    BC6HRGBSfloat,
+    BC7RGBAUnorm,
+    BC7RGBAUnormSRGB,
+
+    // ETC2 and EAC compressed formats, 4x4 blocks
+    ETC2RGB8Unorm,
+    ETC2RGB8UnormSRGB,
+    ETC2RGB8A1Unorm,
+    ETC2RGB8A1UnormSRGB,
+    ETC2RGBA8Unorm,
+    ETC2RGBA8UnormSRGB,
+    EACR11Unorm,
+    EACR11Snorm,
+    EACRG11Unorm,
+    EACRG11Snorm,
+
+    // ASTC compressed formats, the block dimensions are part of the name
+    ASTC4x4Unorm,
+    ASTC4x4UnormSRGB,
+    ASTC5x4Unorm,
+    ASTC5x4UnormSRGB,
+    ASTC5x5Unorm,
+    ASTC5x5UnormSRGB,
+    ASTC6x5Unorm,
+    ASTC6x5UnormSRGB,
+    ASTC6x6Unorm,
+    ASTC6x6UnormSRGB,
+    ASTC8x5Unorm,
+    ASTC8x5UnormSRGB,
+    ASTC8x6Unorm,
+    ASTC8x6UnormSRGB,
+    ASTC8x8Unorm,
+    ASTC8x8UnormSRGB,
+    ASTC10x5Unorm,
+    ASTC10x5UnormSRGB,
+    ASTC10x6Unorm,
+    ASTC10x6UnormSRGB,
+    ASTC10x8Unorm,
+    ASTC10x8UnormSRGB,
+    ASTC10x10Unorm,
+    ASTC10x10UnormSRGB,
+    ASTC12x10Unorm,
+    ASTC12x10UnormSRGB,
+    ASTC12x12Unorm,
+    ASTC12x12UnormSRGB,
 }
 
 bitflags! {
@@ -230,13 +572,18 @@ pub enum TextureDimension {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum TextureViewDimension {
     D1,
+    D1Array,
     D2,
+    D2Array,
     D3,
+    /// Six layers of a square 2D texture
     Cube,
+    /// A multiple of six layers of a square 2D texture. Requires the `texture_cube_array` extension.
+    CubeArray,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct TextureDescriptor {
+pub struct TextureDescriptor<'a> {
     pub size: Extent3D,
     pub array_layer_count: u32,
     pub mip_level_count: u32,
@@ -244,6 +591,28 @@ pub struct TextureDescriptor {
     pub dimension: TextureDimension,
     pub format: TextureFormat,
     pub usage: TextureUsageFlags,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
+}
+
+/// The ways a `TextureFormat` can be used on an adapter with optimal tiling
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextureFormatCapabilities {
+    pub sampled: bool,
+    pub storage: bool,
+    pub render_attachment: bool,
+    pub blendable: bool,
+    pub linear_filterable: bool,
+    pub blit_src: bool,
+    pub blit_dst: bool,
+    /// Bitmask of the supported sample counts, e.g. `1 | 4` when 1 and 4 samples are supported
+    pub sample_counts: u32,
+}
+
+impl TextureFormatCapabilities {
+    pub fn supports_sample_count(&self, sample_count: u32) -> bool {
+        sample_count.is_power_of_two() && (self.sample_counts & sample_count) != 0
+    }
 }
 
 bitflags! {
@@ -256,7 +625,7 @@ bitflags! {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct TextureViewDescriptor {
+pub struct TextureViewDescriptor<'a> {
     pub format: TextureFormat,
     pub dimension: TextureViewDimension,
     pub aspect: TextureAspectFlags,
@@ -264,6 +633,8 @@ pub struct TextureViewDescriptor {
     pub mip_level_count: u32,
     pub base_array_layer: u32,
     pub array_layer_count: u32,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -288,9 +659,11 @@ bitflags! {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct BufferDescriptor {
+pub struct BufferDescriptor<'a> {
     pub size: usize,
     pub usage: BufferUsageFlags,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -375,7 +748,7 @@ pub enum CompareFunction {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub struct SamplerDescriptor {
+pub struct SamplerDescriptor<'a> {
     pub address_mode_u: AddressMode,
     pub address_mode_v: AddressMode,
     pub address_mode_w: AddressMode,
@@ -385,22 +758,30 @@ pub struct SamplerDescriptor {
     pub lod_min_clamp: f32,
     pub lod_max_clamp: f32,
     pub compare_function: CompareFunction,
+    /// Values greater than `1.0` enable anisotropic filtering and require `Extensions::sampler_anisotropy`.
+    /// The value is clamped to the maximum supported by the adapter.
+    pub max_anisotropy: f32,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
 }
 
-impl Eq for SamplerDescriptor {}
+impl<'a> Eq for SamplerDescriptor<'a> {}
 
 #[allow(clippy::derive_hash_xor_eq)]
-impl Hash for SamplerDescriptor {
+impl<'a> Hash for SamplerDescriptor<'a> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         use std::{mem, slice};
+        // the label is compared by value and must not contribute its address to the hash
+        let key = SamplerDescriptor { label: None, ..*self };
         let size = mem::size_of::<SamplerDescriptor>();
-        let bytes = unsafe { slice::from_raw_parts(self as *const _ as *const u8, size) };
+        let bytes = unsafe { slice::from_raw_parts(&key as *const _ as *const u8, size) };
         state.write(bytes);
+        self.label.hash(state);
     }
 }
 
-impl Default for SamplerDescriptor {
-    fn default() -> SamplerDescriptor {
+impl<'a> Default for SamplerDescriptor<'a> {
+    fn default() -> SamplerDescriptor<'a> {
         SamplerDescriptor {
             address_mode_u: AddressMode::ClampToEdge,
             address_mode_v: AddressMode::ClampToEdge,
@@ -411,6 +792,8 @@ impl Default for SamplerDescriptor {
             lod_min_clamp: 0.0,
             lod_max_clamp: std::f32::MAX,
             compare_function: CompareFunction::Never,
+            max_anisotropy: 1.0,
+            label: None,
         }
     }
 }
@@ -515,9 +898,11 @@ pub struct BindGroupBinding {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct BindGroupDescriptor {
+pub struct BindGroupDescriptor<'a> {
     pub layout: BindGroupLayout,
     pub bindings: Vec<BindGroupBinding>,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -526,9 +911,11 @@ pub struct BindGroup {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct PipelineLayoutDescriptor {
+pub struct PipelineLayoutDescriptor<'a> {
     pub bind_group_layouts: Vec<BindGroupLayout>,
     pub push_constant_ranges: Vec<PushConstantRange>,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -663,7 +1050,7 @@ impl Default for StencilStateFaceDescriptor {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug)]
 pub struct DepthStencilStateDescriptor {
     pub format: TextureFormat,
     pub depth_write_enabled: bool,
@@ -672,11 +1059,68 @@ pub struct DepthStencilStateDescriptor {
     pub stencil_back: StencilStateFaceDescriptor,
     pub stencil_read_mask: u32,
     pub stencil_write_mask: u32,
+    /// Discards fragments whose stored depth is outside `(min, max)`. Requires the `depth_bounds`
+    /// extension.
+    pub depth_bounds: Option<(f32, f32)>,
 }
 
+impl PartialEq for DepthStencilStateDescriptor {
+    #[rustfmt::skip]
+    fn eq(&self, other: &DepthStencilStateDescriptor) -> bool {
+        self.format.eq(&other.format) &&
+        self.depth_write_enabled.eq(&other.depth_write_enabled) &&
+        self.depth_compare.eq(&other.depth_compare) &&
+        self.stencil_front.eq(&other.stencil_front) &&
+        self.stencil_back.eq(&other.stencil_back) &&
+        self.stencil_read_mask.eq(&other.stencil_read_mask) &&
+        self.stencil_write_mask.eq(&other.stencil_write_mask) &&
+        self.depth_bounds.eq(&other.depth_bounds)
+    }
+}
+
+impl Hash for DepthStencilStateDescriptor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.format.hash(state);
+        self.depth_write_enabled.hash(state);
+        self.depth_compare.hash(state);
+        self.stencil_front.hash(state);
+        self.stencil_back.hash(state);
+        self.stencil_read_mask.hash(state);
+        self.stencil_write_mask.hash(state);
+        self.depth_bounds
+            .map(|(min, max)| (min.to_bits(), max.to_bits()))
+            .hash(state);
+    }
+}
+
+impl Eq for DepthStencilStateDescriptor {}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ShaderModuleDescriptor<'a> {
     pub code: &'a [u8],
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
+}
+
+#[cfg(feature = "naga")]
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ShaderLanguage {
+    /// Vulkan flavored GLSL with a single `main` entry point
+    Glsl,
+    Wgsl,
+}
+
+/// Shader source that is compiled to SPIR-V with naga. Requires the `naga` feature.
+#[cfg(feature = "naga")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderSourceDescriptor<'a> {
+    pub source: &'a str,
+    pub language: ShaderLanguage,
+    /// The stage of GLSL sources. WGSL entry points declare their own stage and ignore this.
+    pub stage: ShaderStageFlags,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -684,16 +1128,152 @@ pub struct ShaderModule {
     inner: Arc<imp::ShaderModuleInner>,
 }
 
+/// The interface of a shader module, collected from its SPIR-V code
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShaderReflection {
+    pub entry_points: Vec<EntryPointReflection>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryPointReflection {
+    pub name: String,
+    /// `ShaderStageFlags::NONE` for execution models other than vertex, fragment and compute
+    pub stage: ShaderStageFlags,
+    /// The `local_size` of compute shaders
+    pub workgroup_size: Option<[u32; 3]>,
+    /// The descriptor bindings that are used by the entry point
+    pub bindings: Vec<BindingReflection>,
+    /// The push constant block that is used by the entry point
+    pub push_constants: Option<PushConstantReflection>,
+    /// Stage inputs, i.e. the vertex attributes of vertex shaders. Built-ins are not included.
+    pub inputs: Vec<InterfaceVariableReflection>,
+    /// Stage outputs, i.e. the color attachments of fragment shaders. Built-ins are not included.
+    pub outputs: Vec<InterfaceVariableReflection>,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ShaderBindingType {
+    UniformBuffer,
+    StorageBuffer,
+    Sampler,
+    SampledTexture,
+    StorageTexture,
+    CombinedTextureSampler,
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    InputAttachment,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BindingReflection {
+    pub set: u32,
+    pub binding: u32,
+    pub binding_type: ShaderBindingType,
+    /// Number of array elements; 1 for non-arrays and 0 for runtime sized arrays
+    pub count: u32,
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PushConstantReflection {
+    /// Offset of the first member of the push constant block in bytes
+    pub offset: u32,
+    /// Size of the push constant block in bytes, from `offset` to the end of its last member
+    pub size: u32,
+    pub name: Option<String>,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScalarType {
+    Float,
+    Sint,
+    Uint,
+    Bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct InterfaceVariableReflection {
+    pub location: u32,
+    pub scalar_type: ScalarType,
+    /// Number of vector components; 1 for scalars
+    pub components: u32,
+    pub name: Option<String>,
+}
+
+/// The value of a SPIR-V specialization constant, i.e. `layout(constant_id = N) const`
+#[derive(Clone, Copy, Debug)]
+pub enum SpecializationConstant {
+    Bool(bool),
+    Int(i32),
+    Uint(u32),
+    Float(f32),
+}
+
+impl SpecializationConstant {
+    /// The 32-bit value passed to `VkSpecializationInfo`
+    pub fn bits(&self) -> u32 {
+        match *self {
+            SpecializationConstant::Bool(value) => value as u32,
+            SpecializationConstant::Int(value) => value as u32,
+            SpecializationConstant::Uint(value) => value,
+            SpecializationConstant::Float(value) => value.to_bits(),
+        }
+    }
+
+    fn discriminant(&self) -> u32 {
+        match *self {
+            SpecializationConstant::Bool(_) => 0,
+            SpecializationConstant::Int(_) => 1,
+            SpecializationConstant::Uint(_) => 2,
+            SpecializationConstant::Float(_) => 3,
+        }
+    }
+}
+
+// floats are compared by their bits so that the descriptor can be used as a cache key
+impl PartialEq for SpecializationConstant {
+    fn eq(&self, other: &SpecializationConstant) -> bool {
+        self.discriminant() == other.discriminant() && self.bits() == other.bits()
+    }
+}
+
+impl Eq for SpecializationConstant {}
+
+impl Hash for SpecializationConstant {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.discriminant().hash(state);
+        self.bits().hash(state);
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PipelineStageDescriptor {
     pub module: ShaderModule,
     pub entry_point: Cow<'static, str>,
+    /// Values of the specialization constants, keyed by constant id
+    pub specialization_constants: BTreeMap<u32, SpecializationConstant>,
+}
+
+impl PipelineStageDescriptor {
+    pub fn with_specialization_constant(
+        mut self,
+        constant_id: u32,
+        value: SpecializationConstant,
+    ) -> PipelineStageDescriptor {
+        self.specialization_constants.insert(constant_id, value);
+        self
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct ComputePipelineDescriptor {
-    pub layout: PipelineLayout,
+pub struct ComputePipelineDescriptor<'a> {
+    /// Derived from the reflected shader interface when `None`
+    pub layout: Option<PipelineLayout>,
     pub compute_stage: PipelineStageDescriptor,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -711,13 +1291,40 @@ pub enum PrimitiveTopology {
     TriangleStrip,
 }
 
+/// `Line` and `Point` require the `fill_mode_non_solid` extension
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PolygonMode {
+    Fill,
+    Line,
+    Point,
+}
+
+/// `Overestimate` and `Underestimate` require the `conservative_rasterization` extension
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ConservativeRasterizationMode {
+    Disabled,
+    /// Generates fragments for every pixel that the primitive touches
+    Overestimate,
+    /// Generates fragments only for pixels that the primitive fully covers
+    Underestimate,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct RasterizationStateDescriptor {
     pub front_face: FrontFace,
     pub cull_mode: CullMode,
+    pub polygon_mode: PolygonMode,
+    /// Clamps fragment depth instead of clipping primitives against the near and far planes.
+    /// Requires the `depth_clamp` extension.
+    pub depth_clamp: bool,
     pub depth_bias: i32,
     pub depth_bias_slope_scale: f32,
     pub depth_bias_clamp: f32,
+    /// Widths other than 1.0 require the `wide_lines` extension
+    pub line_width: f32,
+    pub conservative_rasterization: ConservativeRasterizationMode,
 }
 
 impl PartialEq for RasterizationStateDescriptor {
@@ -725,9 +1332,13 @@ impl PartialEq for RasterizationStateDescriptor {
     fn eq(&self, other: &RasterizationStateDescriptor) -> bool {
         self.front_face.eq(&other.front_face) &&
         self.cull_mode.eq(&other.cull_mode) &&
+        self.polygon_mode.eq(&other.polygon_mode) &&
+        self.depth_clamp.eq(&other.depth_clamp) &&
         self.depth_bias.eq(&other.depth_bias) &&
         self.depth_bias_slope_scale.eq(&other.depth_bias_slope_scale) &&
-        self.depth_bias_clamp.eq(&other.depth_bias_clamp)
+        self.depth_bias_clamp.eq(&other.depth_bias_clamp) &&
+        self.line_width.eq(&other.line_width) &&
+        self.conservative_rasterization.eq(&other.conservative_rasterization)
     }
 }
 
@@ -735,14 +1346,59 @@ impl Hash for RasterizationStateDescriptor {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.front_face.hash(state);
         self.cull_mode.hash(state);
+        self.polygon_mode.hash(state);
+        self.depth_clamp.hash(state);
         self.depth_bias.hash(state);
         self.depth_bias_slope_scale.to_bits().hash(state);
         self.depth_bias_clamp.to_bits().hash(state);
+        self.line_width.to_bits().hash(state);
+        self.conservative_rasterization.hash(state);
     }
 }
 
 impl Eq for RasterizationStateDescriptor {}
 
+#[derive(Clone, Copy, Debug)]
+pub struct MultisampleStateDescriptor {
+    /// Derives the coverage of each fragment from the alpha of the color output at location 0.
+    /// Requires a `sample_count` greater than 1.
+    pub alpha_to_coverage_enabled: bool,
+    /// Samples whose bit is zero are not written. Samples 32 through 63 are always enabled.
+    pub sample_mask: u32,
+    /// Shades at least this fraction of the samples of each fragment separately, from 0.0 to 1.0.
+    /// Requires the `sample_rate_shading` extension.
+    pub min_sample_shading: Option<f32>,
+}
+
+impl Default for MultisampleStateDescriptor {
+    fn default() -> MultisampleStateDescriptor {
+        MultisampleStateDescriptor {
+            alpha_to_coverage_enabled: false,
+            sample_mask: !0,
+            min_sample_shading: None,
+        }
+    }
+}
+
+impl PartialEq for MultisampleStateDescriptor {
+    #[rustfmt::skip]
+    fn eq(&self, other: &MultisampleStateDescriptor) -> bool {
+        self.alpha_to_coverage_enabled.eq(&other.alpha_to_coverage_enabled) &&
+        self.sample_mask.eq(&other.sample_mask) &&
+        self.min_sample_shading.eq(&other.min_sample_shading)
+    }
+}
+
+impl Hash for MultisampleStateDescriptor {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.alpha_to_coverage_enabled.hash(state);
+        self.sample_mask.hash(state);
+        self.min_sample_shading.map(f32::to_bits).hash(state);
+    }
+}
+
+impl Eq for MultisampleStateDescriptor {}
+
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum IndexFormat {
@@ -818,8 +1474,9 @@ pub struct InputStateDescriptor {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct RenderPipelineDescriptor {
-    pub layout: PipelineLayout,
+pub struct RenderPipelineDescriptor<'a> {
+    /// Derived from the reflected shader interface when `None`
+    pub layout: Option<PipelineLayout>,
     pub vertex_stage: PipelineStageDescriptor,
     pub fragment_stage: PipelineStageDescriptor,
     pub primitive_topology: PrimitiveTopology,
@@ -828,6 +1485,9 @@ pub struct RenderPipelineDescriptor {
     pub depth_stencil_state: Option<DepthStencilStateDescriptor>,
     pub input_state: InputStateDescriptor,
     pub sample_count: u32,
+    pub multisample_state: MultisampleStateDescriptor,
+    /// Debug name forwarded to `VK_EXT_debug_utils`
+    pub label: Option<&'a str>,
 }
 
 #[derive(Clone, Debug)]
@@ -835,6 +1495,35 @@ pub struct RenderPipeline {
     inner: Arc<imp::RenderPipelineInner>,
 }
 
+/// A compute pipeline that is rebuilt when its shader file changes
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReloadableComputePipelineDescriptor<'a> {
+    /// The initial pipeline. Its stage module is replaced when the shader file is reloaded.
+    pub pipeline: ComputePipelineDescriptor<'a>,
+    /// A `.spv` file, or GLSL and `.wgsl` sources with the `naga` feature
+    pub compute_shader: PathBuf,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReloadableComputePipeline {
+    inner: Arc<imp::ReloadableComputePipelineInner>,
+}
+
+/// A render pipeline that is rebuilt when one of its shader files changes
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReloadableRenderPipelineDescriptor<'a> {
+    /// The initial pipeline. Its stage modules are replaced when the shader files are reloaded.
+    pub pipeline: RenderPipelineDescriptor<'a>,
+    /// A `.spv` file, or GLSL and `.wgsl` sources with the `naga` feature
+    pub vertex_shader: PathBuf,
+    pub fragment_shader: PathBuf,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReloadableRenderPipeline {
+    inner: Arc<imp::ReloadableRenderPipelineInner>,
+}
+
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum LoadOp {
@@ -894,7 +1583,11 @@ pub struct CommandBuffer {
     inner: imp::CommandBufferInner,
 }
 
-pub struct CommandEncoderDescriptor {}
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CommandEncoderDescriptor<'a> {
+    /// Debug label that encloses the recorded commands when submitted
+    pub label: Option<&'a str>,
+}
 
 /// Specifies buffer to texture copy operation
 ///
@@ -903,19 +1596,37 @@ pub struct CommandEncoderDescriptor {}
 pub struct BufferCopyView<'a> {
     pub buffer: &'a Buffer,
     pub offset: usize,
-    /// Specified in texels (Note that WebGPU currently uses rowPitch in bytes)
-    pub row_length: u32,
-    pub image_height: u32,
+    /// Stride between rows of texel blocks in bytes, as in WebGPU. Must be a multiple of the block
+    /// size of the texture format. Zero means the rows are tightly packed.
+    pub bytes_per_row: u32,
+    /// Stride between images or array layers in rows of texels. Must be a multiple of the block
+    /// height of the texture format. Zero means the images are tightly packed.
+    pub rows_per_image: u32,
 }
 
+/// Specifies the texture side of a copy operation
+///
+/// Copies of 2D array textures cover `array_layer_count` layers starting at `array_layer`, with
+/// the layers laid out one after the other in buffers. 3D textures have a single layer and copy
+/// the depth range given by `origin.z` and the depth of the copy size.
 #[derive(Clone, Debug)]
 pub struct TextureCopyView<'a> {
     pub texture: &'a Texture,
     pub mip_level: u32,
     pub array_layer: u32,
+    pub array_layer_count: u32,
     pub origin: Origin3D,
 }
 
+/// The mip levels and array layers of a texture. Cube faces are array layers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextureSubresourceRange {
+    pub base_mip_level: u32,
+    pub mip_level_count: u32,
+    pub base_array_layer: u32,
+    pub array_layer_count: u32,
+}
+
 /// Not in the GPUWeb spec as of 2019/04/13
 #[derive(Clone, Debug)]
 pub struct TextureBlitView<'a> {
@@ -0,0 +1,19 @@
+#version 450
+#extension GL_EXT_samplerless_texture_functions : require
+
+layout(set = 0, binding = 0) uniform texture2D u_Source;
+
+layout(location = 0) out vec4 o_Color;
+
+// Averages the 2x2 texels of the source level that cover the destination texel
+void main() {
+    ivec2 last = textureSize(u_Source, 0) - 1;
+    ivec2 base = ivec2(gl_FragCoord.xy) * 2;
+
+    vec4 color = texelFetch(u_Source, min(base, last), 0);
+    color += texelFetch(u_Source, min(base + ivec2(1, 0), last), 0);
+    color += texelFetch(u_Source, min(base + ivec2(0, 1), last), 0);
+    color += texelFetch(u_Source, min(base + ivec2(1, 1), last), 0);
+
+    o_Color = color * 0.25;
+}
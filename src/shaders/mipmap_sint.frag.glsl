@@ -0,0 +1,20 @@
+#version 450
+#extension GL_EXT_samplerless_texture_functions : require
+
+layout(set = 0, binding = 0) uniform itexture2D u_Source;
+
+layout(location = 0) out ivec4 o_Color;
+
+// Averages the 2x2 texels of the source level that cover the destination texel. The remainders
+// are summed separately so that 32-bit values do not overflow.
+void main() {
+    ivec2 last = textureSize(u_Source, 0) - 1;
+    ivec2 base = ivec2(gl_FragCoord.xy) * 2;
+
+    ivec4 a = texelFetch(u_Source, min(base, last), 0);
+    ivec4 b = texelFetch(u_Source, min(base + ivec2(1, 0), last), 0);
+    ivec4 c = texelFetch(u_Source, min(base + ivec2(0, 1), last), 0);
+    ivec4 d = texelFetch(u_Source, min(base + ivec2(1, 1), last), 0);
+
+    o_Color = (a >> 2) + (b >> 2) + (c >> 2) + (d >> 2) + (((a & 3) + (b & 3) + (c & 3) + (d & 3)) >> 2);
+}
@@ -33,12 +33,14 @@ fn create_bind_group() {
         let buffer_descriptor = BufferDescriptor {
             usage: BufferUsageFlags::UNIFORM | BufferUsageFlags::TRANSFER_DST,
             size: 1024,
+            label: None,
         };
         let buffer = device.create_buffer(buffer_descriptor)?;
 
         let texel_buffer_descriptor = BufferDescriptor {
             usage: BufferUsageFlags::STORAGE,
             size: 1024,
+            label: None,
         };
         let texel_buffer = device.create_buffer(texel_buffer_descriptor)?;
         let texel_buffer_view = texel_buffer.create_view(BufferViewDescriptor {
@@ -62,6 +64,7 @@ fn create_bind_group() {
             mip_level_count: 1,
             sample_count: 1,
             usage: TextureUsageFlags::SAMPLED,
+            label: None,
         };
         let texture = device.create_texture(texture_descriptor)?;
         let texture_view = texture.create_default_view()?;
@@ -112,6 +115,7 @@ fn create_bind_group() {
                     resource: BindingResource::BufferView(texel_buffer_view),
                 },
             ],
+            label: None,
         };
         let _bind_group = device.create_bind_group(bind_group_descriptor)?;
 
@@ -1,5 +1,5 @@
 use std::time::Duration;
-use vki::{BufferDescriptor, BufferUsageFlags};
+use vki::{BufferDescriptor, BufferUsageFlags, DeviceDescriptor, QueueType};
 
 pub mod support;
 
@@ -11,6 +11,7 @@ fn create_buffer_vertex_transfer_dst() {
         let descriptor = BufferDescriptor {
             usage: BufferUsageFlags::VERTEX | BufferUsageFlags::TRANSFER_DST,
             size: 1024,
+            label: None,
         };
 
         let _buffer = device.create_buffer(descriptor)?;
@@ -19,6 +20,37 @@ fn create_buffer_vertex_transfer_dst() {
     });
 }
 
+#[test]
+fn create_buffer_with_label() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let descriptor = BufferDescriptor {
+            usage: BufferUsageFlags::VERTEX | BufferUsageFlags::TRANSFER_DST,
+            size: 1024,
+            label: Some("vertex_buffer"),
+        };
+
+        let buffer = device.create_buffer(descriptor)?;
+        assert_eq!(Some("vertex_buffer".to_owned()), buffer.label());
+        assert!(format!("{:?}", buffer).contains("vertex_buffer"));
+
+        buffer.set_label("renamed_vertex_buffer");
+        assert_eq!(Some("renamed_vertex_buffer".to_owned()), buffer.label());
+
+        // labels are not required to be 'static
+        let label = format!("vertex_buffer_{}", 2);
+        let buffer = device.create_buffer(BufferDescriptor {
+            label: Some(&label),
+            ..descriptor
+        })?;
+        drop(label);
+        assert_eq!(Some("vertex_buffer_2".to_owned()), buffer.label());
+
+        Ok(instance)
+    });
+}
+
 #[test]
 fn create_buffer_uniform_mapped_write() {
     vki::validate(|| {
@@ -27,6 +59,7 @@ fn create_buffer_uniform_mapped_write() {
         let descriptor = BufferDescriptor {
             usage: BufferUsageFlags::UNIFORM | BufferUsageFlags::MAP_WRITE,
             size: 1024,
+            label: None,
         };
 
         let _buffer = device.create_buffer(descriptor)?;
@@ -43,6 +76,7 @@ fn create_buffer_write_staging() {
         let descriptor = BufferDescriptor {
             usage: BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::MAP_WRITE,
             size: 1024,
+            label: None,
         };
 
         let _buffer = device.create_buffer(descriptor)?;
@@ -59,6 +93,7 @@ fn create_buffer_read_staging() {
         let descriptor = BufferDescriptor {
             usage: BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::MAP_READ,
             size: 1024,
+            label: None,
         };
 
         let _buffer = device.create_buffer(descriptor)?;
@@ -75,6 +110,7 @@ fn create_buffer_read_storage() {
         let descriptor = BufferDescriptor {
             usage: BufferUsageFlags::STORAGE | BufferUsageFlags::MAP_READ,
             size: 1024,
+            label: None,
         };
 
         let _buffer = device.create_buffer(descriptor)?;
@@ -99,6 +135,7 @@ fn create_buffer_mapped() {
         let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
             usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
             size: data_byte_size,
+            label: None,
         })?;
 
         write_buffer_mapped.copy_from_slice(data)?;
@@ -106,11 +143,12 @@ fn create_buffer_mapped() {
         let read_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
             size: data_byte_size,
+            label: None,
         })?;
 
         encoder.copy_buffer_to_buffer(&write_buffer_mapped.unmap(), 0, &read_buffer, 0, data_byte_size);
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(&[encoder.finish()?])?;
 
@@ -141,6 +179,7 @@ fn create_buffer_mapped_write_data() {
         let mut write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
             usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
             size: data_byte_size,
+            label: None,
         })?;
 
         let mut write_data = write_buffer_mapped.write::<u32>(0, data.len())?;
@@ -153,11 +192,12 @@ fn create_buffer_mapped_write_data() {
         let read_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
             size: data_byte_size,
+            label: None,
         })?;
 
         encoder.copy_buffer_to_buffer(&write_buffer_mapped.unmap(), 0, &read_buffer, 0, data_byte_size);
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(&[encoder.finish()?])?;
 
@@ -185,6 +225,7 @@ fn set_sub_data() {
         let read_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
             size: data_byte_size,
+            label: None,
         })?;
 
         read_buffer.set_sub_data(0, data)?;
@@ -195,7 +236,7 @@ fn set_sub_data() {
         //       read and write mapping should probably handle this
         //       better.
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         let encoder = device.create_command_encoder()?;
 
@@ -226,12 +267,13 @@ fn set_sub_data_offset() {
         let read_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
             size: (2 * data_byte_size) as _,
+            label: None,
         })?;
 
         read_buffer.set_sub_data(0, data)?;
         read_buffer.set_sub_data(data.len(), data)?;
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         let encoder = device.create_command_encoder()?;
 
@@ -267,6 +309,7 @@ fn mapping_twice_should_fail() {
         let buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
             size: data_byte_size,
+            label: None,
         })?;
 
         let _mapped = buffer.map_read()?;
@@ -276,3 +319,104 @@ fn mapping_twice_should_fail() {
         Ok(instance)
     });
 }
+
+#[test]
+fn copy_buffer_across_queues() {
+    vki::validate(|| {
+        let (instance, adapter, _device) = support::init()?;
+
+        let device = adapter
+            .create_device(DeviceDescriptor::default().with_extra_queues(&[QueueType::Compute, QueueType::Transfer]))?;
+
+        let transfer_queue = device.get_queue(QueueType::Transfer);
+        let graphics_queue = device.get_queue(QueueType::Graphics);
+        assert_eq!(QueueType::Graphics, graphics_queue.queue_type());
+
+        let data: &[u32] = &[1, 2, 3, 4, 5];
+        let data_byte_size = std::mem::size_of::<u32>() * data.len();
+
+        let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
+            size: data_byte_size,
+            label: None,
+        })?;
+
+        write_buffer_mapped.copy_from_slice(data)?;
+
+        let buffer = device.create_buffer(BufferDescriptor {
+            usage: BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::TRANSFER_DST,
+            size: data_byte_size,
+            label: None,
+        })?;
+
+        let read_buffer = device.create_buffer(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
+            size: data_byte_size,
+            label: None,
+        })?;
+
+        // upload on the transfer queue
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_buffer(&write_buffer_mapped.unmap(), 0, &buffer, 0, data_byte_size);
+        transfer_queue.submit(&[encoder.finish()?])?;
+
+        // read back on the graphics queue; ownership of `buffer` has to be transferred
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_buffer(&buffer, 0, &read_buffer, 0, data_byte_size);
+        graphics_queue.submit(&[encoder.finish()?])?;
+
+        let fence = graphics_queue.create_fence()?;
+
+        fence.wait(Duration::from_millis(1_000_000_000))?;
+
+        let read_buffer_mapped = read_buffer.map_read()?;
+
+        let read: &[u32] = read_buffer_mapped.read(0, data.len())?;
+        assert_eq!(data, read);
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn memory_report() {
+    vki::validate(|| {
+        let (instance, adapter, device) = support::init()?;
+
+        let usage = BufferUsageFlags::STORAGE | BufferUsageFlags::TRANSFER_DST;
+        let report = device.memory_report()?;
+        assert!(!report.heaps.is_empty());
+        if adapter.properties().memory_budget {
+            for heap in report.heaps.iter() {
+                let budget = heap.budget.expect("heap budget missing");
+                assert!(budget > 0 && budget <= heap.size);
+            }
+        } else {
+            assert!(report.heaps.iter().all(|heap| heap.budget.is_none()));
+        }
+        assert_eq!(None, report.buffers.iter().find(|buffers| buffers.usage == usage));
+
+        let buffer = device.create_buffer(BufferDescriptor {
+            usage,
+            size: 1024,
+            label: None,
+        })?;
+
+        let report = device.memory_report()?;
+        assert!(report.allocation_count > 0);
+        let buffers = report
+            .buffers
+            .iter()
+            .find(|buffers| buffers.usage == usage)
+            .expect("buffer missing from memory report");
+        assert_eq!(1, buffers.count);
+        assert!(buffers.size_bytes >= 1024);
+
+        drop(buffer);
+
+        let report = device.memory_report()?;
+        assert_eq!(None, report.buffers.iter().find(|buffers| buffers.usage == usage));
+
+        Ok(instance)
+    });
+}
@@ -3,8 +3,8 @@ use std::time::Duration;
 use vki::{
     BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor, BindingResource,
     BindingType, BufferDescriptor, BufferUsageFlags, ComputePipelineDescriptor, DispatchIndirectCommand,
-    PipelineLayoutDescriptor, PipelineStageDescriptor, PushConstantRange, RenderPassDescriptor, ShaderModuleDescriptor,
-    ShaderStageFlags,
+    PipelineLayoutDescriptor, PipelineStageDescriptor, PushConstantRange, QueueType, RenderPassDescriptor,
+    ShaderModuleDescriptor, ShaderStageFlags,
 };
 
 pub mod support;
@@ -16,6 +16,7 @@ fn copy_buffer_with_compute_shader() {
 
         let compute_module = device.create_shader_module(ShaderModuleDescriptor {
             code: include_bytes!("shaders/command_buffer.copy_buffer_with_compute_shader.comp.spv"),
+            label: None,
         })?;
 
         let bind_group_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -36,14 +37,17 @@ fn copy_buffer_with_compute_shader() {
         let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout.clone()],
             push_constant_ranges: vec![],
+            label: None,
         })?;
 
         let pipeline = device.create_compute_pipeline(ComputePipelineDescriptor {
             compute_stage: PipelineStageDescriptor {
                 entry_point: Cow::Borrowed("main"),
                 module: compute_module,
+                specialization_constants: Default::default(),
             },
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
+            label: None,
         })?;
 
         let mut encoder = device.create_command_encoder()?;
@@ -60,6 +64,7 @@ fn copy_buffer_with_compute_shader() {
         let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
             usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::STORAGE,
             size: data_byte_size,
+            label: None,
         })?;
 
         write_buffer_mapped.copy_from_slice(data)?;
@@ -67,6 +72,7 @@ fn copy_buffer_with_compute_shader() {
         let read_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::STORAGE,
             size: data_byte_size,
+            label: None,
         })?;
 
         let bind_group = device.create_bind_group(BindGroupDescriptor {
@@ -81,6 +87,7 @@ fn copy_buffer_with_compute_shader() {
                     resource: BindingResource::Buffer(read_buffer.clone(), 0..data_byte_size),
                 },
             ],
+            label: None,
         })?;
 
         let mut compute_pass = encoder.begin_compute_pass();
@@ -89,7 +96,7 @@ fn copy_buffer_with_compute_shader() {
         compute_pass.dispatch(4, 1, 1);
         compute_pass.end_pass();
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(&[encoder.finish()?])?;
 
@@ -117,6 +124,7 @@ fn push_constants() {
 
         let compute_module = device.create_shader_module(ShaderModuleDescriptor {
             code: include_bytes!("shaders/command_buffer.push_constants.comp.spv"),
+            label: None,
         })?;
 
         let bind_group_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -134,14 +142,17 @@ fn push_constants() {
                 stages: ShaderStageFlags::COMPUTE,
                 size: data_byte_size,
             }],
+            label: None,
         })?;
 
         let pipeline = device.create_compute_pipeline(ComputePipelineDescriptor {
             compute_stage: PipelineStageDescriptor {
                 entry_point: Cow::Borrowed("main"),
                 module: compute_module,
+                specialization_constants: Default::default(),
             },
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
+            label: None,
         })?;
 
         let mut encoder = device.create_command_encoder()?;
@@ -149,6 +160,7 @@ fn push_constants() {
         let read_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::STORAGE,
             size: data_byte_size,
+            label: None,
         })?;
 
         let bind_group = device.create_bind_group(BindGroupDescriptor {
@@ -157,6 +169,7 @@ fn push_constants() {
                 binding: 0,
                 resource: BindingResource::Buffer(read_buffer.clone(), 0..data_byte_size),
             }],
+            label: None,
         })?;
 
         let mut compute_pass = encoder.begin_compute_pass();
@@ -167,7 +180,7 @@ fn push_constants() {
         compute_pass.dispatch(1, 1, 1);
         compute_pass.end_pass();
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(&[encoder.finish()?])?;
 
@@ -210,7 +223,7 @@ fn debug_markers() {
         render_pass.end_pass();
 
         let command_buffer = encoder.finish()?;
-        device.get_queue().submit(&[command_buffer])?;
+        device.get_queue(QueueType::Graphics).submit(&[command_buffer])?;
 
         Ok(instance)
     });
@@ -223,6 +236,7 @@ fn dispatch_indirect() {
 
         let compute_module = device.create_shader_module(ShaderModuleDescriptor {
             code: include_bytes!("shaders/command_buffer.copy_buffer_with_compute_shader.comp.spv"),
+            label: None,
         })?;
 
         let bind_group_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
@@ -243,14 +257,17 @@ fn dispatch_indirect() {
         let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout.clone()],
             push_constant_ranges: vec![],
+            label: None,
         })?;
 
         let pipeline = device.create_compute_pipeline(ComputePipelineDescriptor {
             compute_stage: PipelineStageDescriptor {
                 entry_point: Cow::Borrowed("main"),
                 module: compute_module,
+                specialization_constants: Default::default(),
             },
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
+            label: None,
         })?;
 
         let mut encoder = device.create_command_encoder()?;
@@ -267,6 +284,7 @@ fn dispatch_indirect() {
         let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
             usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::STORAGE,
             size: data_byte_size,
+            label: None,
         })?;
 
         write_buffer_mapped.copy_from_slice(data)?;
@@ -274,6 +292,7 @@ fn dispatch_indirect() {
         let read_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::STORAGE,
             size: data_byte_size,
+            label: None,
         })?;
 
         let bind_group = device.create_bind_group(BindGroupDescriptor {
@@ -288,11 +307,13 @@ fn dispatch_indirect() {
                     resource: BindingResource::Buffer(read_buffer.clone(), 0..data_byte_size),
                 },
             ],
+            label: None,
         })?;
 
         let indirect_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::INDIRECT | BufferUsageFlags::TRANSFER_DST,
             size: std::mem::size_of::<DispatchIndirectCommand>(),
+            label: None,
         })?;
 
         let cmd = DispatchIndirectCommand { x: 4, y: 1, z: 1 };
@@ -305,7 +326,7 @@ fn dispatch_indirect() {
         compute_pass.dispatch_indirect(&indirect_buffer, 0);
         compute_pass.end_pass();
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(&[encoder.finish()?])?;
 
@@ -1,6 +1,6 @@
 use vki::{
-    Color, Extent3D, LoadOp, RenderPassColorAttachmentDescriptor, RenderPassDescriptor, StoreOp, TextureDescriptor,
-    TextureDimension, TextureFormat, TextureUsageFlags,
+    Color, DeviceDescriptor, Extent3D, LoadOp, QueueType, RenderPassColorAttachmentDescriptor, RenderPassDescriptor,
+    StoreOp, TextureDescriptor, TextureDimension, TextureFormat, TextureUsageFlags,
 };
 
 pub mod support;
@@ -27,6 +27,7 @@ fn create_command_encoder() {
                 height: 1024,
                 depth: 1,
             },
+            label: None,
         })?;
 
         let texture_view = texture.create_default_view()?;
@@ -76,6 +77,7 @@ fn submit_command_buffer() {
                 height: 1024,
                 depth: 1,
             },
+            label: None,
         })?;
 
         let texture_view = texture.create_default_view()?;
@@ -99,10 +101,68 @@ fn submit_command_buffer() {
 
         let command_buffer = command_encoder.finish()?;
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(&[command_buffer])?;
 
         Ok(instance)
     });
 }
+
+#[test]
+fn render_pass_on_compute_device() {
+    vki::validate(|| {
+        let (instance, adapter, _device) = support::init()?;
+
+        let device = adapter.create_device(DeviceDescriptor::default().with_queue_type(QueueType::Compute))?;
+        assert_eq!(QueueType::Compute, device.get_queue(QueueType::Graphics).queue_type());
+
+        let mut command_encoder = device.create_command_encoder()?;
+        let compute_pass = command_encoder.begin_compute_pass();
+        compute_pass.end_pass();
+        let command_buffer = command_encoder.finish()?;
+        device.get_queue(QueueType::Compute).submit(&[command_buffer])?;
+
+        let texture = device.create_texture(TextureDescriptor {
+            sample_count: 1,
+            format: TextureFormat::R8G8B8A8Unorm,
+            usage: TextureUsageFlags::OUTPUT_ATTACHMENT,
+            mip_level_count: 1,
+            dimension: TextureDimension::D2,
+            array_layer_count: 1,
+            size: Extent3D {
+                width: 16,
+                height: 16,
+                depth: 1,
+            },
+            label: None,
+        })?;
+
+        let texture_view = texture.create_default_view()?;
+
+        let mut command_encoder = device.create_command_encoder()?;
+        let render_pass = command_encoder.begin_render_pass(RenderPassDescriptor {
+            color_attachments: &[RenderPassColorAttachmentDescriptor {
+                attachment: &texture_view,
+                resolve_target: None,
+                load_op: LoadOp::Clear,
+                store_op: StoreOp::Store,
+                clear_color: Color {
+                    r: 0.2,
+                    g: 0.2,
+                    b: 0.2,
+                    a: 1.0,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+        render_pass.end_pass();
+
+        assert!(
+            command_encoder.finish().is_err(),
+            "render passes are not supported on compute devices"
+        );
+
+        Ok(instance)
+    });
+}
@@ -1,6 +1,8 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
-use vki::FenceError;
+use vki::{FenceError, Maintain, QueueType};
 
 pub mod support;
 
@@ -9,7 +11,7 @@ fn is_not_signaled() {
     vki::validate(|| {
         let (instance, _adapter, device) = support::init()?;
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         let fence = queue.create_fence()?;
         assert_eq!(false, fence.is_signaled());
@@ -23,7 +25,7 @@ fn is_signaled_after_wait() {
     vki::validate(|| {
         let (instance, _adapter, device) = support::init()?;
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         let fence1 = queue.create_fence()?;
         assert_eq!(false, fence1.is_signaled());
@@ -49,7 +51,7 @@ fn timeout() {
     vki::validate(|| {
         let (instance, _adapter, device) = support::init()?;
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         let fence = queue.create_fence()?;
         assert_eq!(false, fence.is_signaled());
@@ -67,3 +69,26 @@ fn timeout() {
         Ok(instance)
     });
 }
+
+#[test]
+fn poll_calls_work_done_callbacks() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let queue = device.get_queue(QueueType::Graphics);
+
+        let encoder = device.create_command_encoder()?;
+        queue.submit(&[encoder.finish()?])?;
+
+        let done = Arc::new(AtomicBool::new(false));
+        let callback_done = done.clone();
+        queue.on_submitted_work_done(move || callback_done.store(true, Ordering::SeqCst));
+
+        let serial = device.poll(Maintain::Wait)?;
+        assert_eq!(true, done.load(Ordering::SeqCst));
+
+        assert!(device.poll(Maintain::Poll)? >= serial);
+
+        Ok(instance)
+    });
+}
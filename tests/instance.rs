@@ -1,4 +1,11 @@
-use vki::{AdapterOptions, DeviceDescriptor, Instance, PowerPreference};
+use vki::{
+    AdapterOptions, BufferDescriptor, BufferUsageFlags, BufferViewDescriptor, BufferViewFormat, DebugMessageSeverity,
+    DebugMessageTypeFlags, DebugMessengerDescriptor, DeviceDescriptor, Extensions, Instance, InstanceDescriptor,
+    InstanceError, Limits, PowerPreference, TextureFormat, VkObjectType,
+};
+
+use std::sync::mpsc;
+use std::sync::Mutex;
 
 #[test]
 fn instance_new() {
@@ -10,6 +17,46 @@ fn instance_new() {
     });
 }
 
+#[test]
+fn instance_with_descriptor() {
+    let _ = pretty_env_logger::try_init();
+    vki::validate(|| {
+        let instance = Instance::with_descriptor(InstanceDescriptor {
+            validation: Some(true),
+            app_name: "instance_with_descriptor".into(),
+            ..InstanceDescriptor::default()
+        })?;
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn instance_missing_layer() {
+    let _ = pretty_env_logger::try_init();
+    let result = Instance::with_descriptor(InstanceDescriptor {
+        extra_layers: vec!["VK_LAYER_VKI_does_not_exist".into()],
+        ..InstanceDescriptor::default()
+    });
+    match result {
+        Err(InstanceError::MissingLayer(name)) => assert_eq!(name, "VK_LAYER_VKI_does_not_exist"),
+        other => panic!("expected a missing layer error: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn instance_missing_extension() {
+    let _ = pretty_env_logger::try_init();
+    let result = Instance::with_descriptor(InstanceDescriptor {
+        extra_extensions: vec!["VK_VKI_does_not_exist".into()],
+        ..InstanceDescriptor::default()
+    });
+    match result {
+        Err(InstanceError::MissingExtension(name)) => assert_eq!(name, "VK_VKI_does_not_exist"),
+        other => panic!("expected a missing extension error: {:?}", other.map(|_| ())),
+    }
+}
+
 #[test]
 fn instance_request_adapter() {
     let _ = pretty_env_logger::try_init();
@@ -18,12 +65,14 @@ fn instance_request_adapter() {
 
         let options = AdapterOptions {
             power_preference: PowerPreference::HighPerformance,
+            ..AdapterOptions::default()
         };
         let adapter = instance.get_adapter(options)?;
         assert!(!adapter.name().is_empty());
 
         let options = AdapterOptions {
             power_preference: PowerPreference::LowPower,
+            ..AdapterOptions::default()
         };
         let adapter = instance.get_adapter(options)?;
         assert!(!adapter.name().is_empty());
@@ -32,6 +81,32 @@ fn instance_request_adapter() {
     });
 }
 
+#[test]
+fn instance_enumerate_adapters() {
+    let _ = pretty_env_logger::try_init();
+    vki::validate(|| {
+        let instance = Instance::new()?;
+
+        let adapters = instance.enumerate_adapters()?;
+        assert!(!adapters.is_empty());
+
+        for adapter in adapters.iter() {
+            let properties = adapter.properties();
+            let options = AdapterOptions::default()
+                .device_type(properties.device_type)
+                .vendor_id(properties.vender_id)
+                .name(properties.device_name.to_uppercase());
+            let selected = instance.get_adapter(options)?;
+            assert_eq!(adapter.name(), selected.name());
+        }
+
+        let options = AdapterOptions::default().name("no adapter has this name");
+        assert!(instance.get_adapter(options).is_err());
+
+        Ok(instance)
+    });
+}
+
 #[test]
 fn instance_create_device() {
     let _ = pretty_env_logger::try_init();
@@ -44,3 +119,164 @@ fn instance_create_device() {
         Ok(instance)
     });
 }
+
+#[test]
+fn instance_create_device_with_required_limits() {
+    let _ = pretty_env_logger::try_init();
+    vki::validate(|| {
+        let instance = Instance::new()?;
+        let adapter = instance.get_adapter(AdapterOptions::default())?;
+
+        let device = adapter.create_device(DeviceDescriptor::default())?;
+        assert_eq!(adapter.limits(), device.limits());
+
+        let device = adapter.create_device(DeviceDescriptor::default().with_required_limits(Limits::default()))?;
+        assert_eq!(Limits::default(), device.limits());
+
+        let unsupported = Limits {
+            max_bind_groups: adapter.limits().max_bind_groups + 1,
+            ..Limits::default()
+        };
+        assert!(adapter
+            .create_device(DeviceDescriptor::default().with_required_limits(unsupported))
+            .is_err());
+
+        let unsupported = Limits {
+            min_uniform_buffer_offset_alignment: 3,
+            ..Limits::default()
+        };
+        assert!(adapter
+            .create_device(DeviceDescriptor::default().with_required_limits(unsupported))
+            .is_err());
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn instance_create_device_with_extensions() {
+    let _ = pretty_env_logger::try_init();
+    vki::validate(|| {
+        let instance = Instance::new()?;
+        let adapter = instance.get_adapter(AdapterOptions::default())?;
+
+        let supported = *adapter.extensions();
+        let device = adapter.create_device(DeviceDescriptor::default().with_extensions(supported))?;
+        assert_eq!(&supported, device.extensions());
+
+        let device = adapter.create_device(DeviceDescriptor::default())?;
+        assert_eq!(&Extensions::default(), device.extensions());
+
+        let all = Extensions {
+            depth_clamp: true,
+            fill_mode_non_solid: true,
+            wide_lines: true,
+            multi_draw_indirect: true,
+            sampler_anisotropy: true,
+            texture_compression_bc: true,
+            texture_compression_etc2: true,
+            texture_compression_astc: true,
+            independent_blend: true,
+            shader_float64: true,
+            robust_buffer_access: true,
+            depth_bounds: true,
+            sample_rate_shading: true,
+            texture_cube_array: true,
+            conservative_rasterization: true,
+        };
+        if supported != all {
+            assert!(adapter
+                .create_device(DeviceDescriptor::default().with_extensions(all))
+                .is_err());
+        }
+
+        Ok(instance)
+    });
+}
+
+// Not wrapped in vki::validate: the validation error is triggered on purpose
+#[test]
+fn instance_debug_messenger() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = pretty_env_logger::try_init();
+    let instance = Instance::with_descriptor(InstanceDescriptor {
+        validation: Some(true),
+        ..InstanceDescriptor::default()
+    })?;
+
+    let (sender, receiver) = mpsc::channel();
+    let sender = Mutex::new(sender);
+    let descriptor = DebugMessengerDescriptor {
+        min_severity: DebugMessageSeverity::Warning,
+        message_type: DebugMessageTypeFlags::VALIDATION,
+    };
+    let messenger = instance.create_debug_messenger(descriptor, move |message| {
+        let _ = sender.lock().unwrap().send(message.clone());
+    })?;
+
+    let adapter = instance.get_adapter(AdapterOptions::default())?;
+    let device = adapter.create_device(DeviceDescriptor::default())?;
+
+    let label = "instance_debug_messenger buffer";
+    let buffer = device.create_buffer(BufferDescriptor {
+        size: 1024,
+        usage: BufferUsageFlags::TRANSFER_DST,
+        label: Some(label),
+    })?;
+
+    let messages: Vec<_> = receiver.try_iter().collect();
+    assert!(messages.is_empty(), "{:#?}", messages);
+
+    // texel buffer views require a texel buffer usage
+    let _ = buffer.create_view(BufferViewDescriptor {
+        format: BufferViewFormat::Texture(TextureFormat::RGBA32Float),
+        offset: 0,
+        size: 1024,
+    });
+
+    drop(messenger);
+
+    let messages: Vec<_> = receiver.try_iter().collect();
+    assert_eq!(1, messages.len(), "{:#?}", messages);
+
+    let message = &messages[0];
+    assert_eq!(DebugMessageSeverity::Error, message.severity);
+    assert_eq!(DebugMessageTypeFlags::VALIDATION, message.message_type);
+    assert!(!message.message_id_name.as_deref().unwrap_or_default().is_empty());
+
+    let object = message
+        .objects
+        .iter()
+        .find(|object| object.object_type == VkObjectType::BUFFER)
+        .expect("the buffer is reported");
+    assert_ne!(0, object.handle);
+    assert_eq!(Some(label), object.name.as_deref());
+
+    Ok(())
+}
+
+#[test]
+fn instance_create_device_with_pipeline_cache_data() {
+    let _ = pretty_env_logger::try_init();
+    vki::validate(|| {
+        let instance = Instance::new()?;
+        let adapter = instance.get_adapter(AdapterOptions::default())?;
+
+        let device = adapter.create_device(DeviceDescriptor::default())?;
+        let data = device.pipeline_cache_data()?;
+        assert!(data.len() >= 32, "pipeline cache data contains at least the header");
+
+        let device = adapter.create_device(DeviceDescriptor::default().with_pipeline_cache_data(&data))?;
+        let _ = device.pipeline_cache_data()?;
+
+        // data from a different device is discarded
+        let mut mismatched = data.clone();
+        mismatched[16] = !mismatched[16];
+        let device = adapter.create_device(DeviceDescriptor::default().with_pipeline_cache_data(&mismatched))?;
+        let _ = device.pipeline_cache_data()?;
+
+        let device = adapter.create_device(DeviceDescriptor::default().with_pipeline_cache_data(&[1, 2, 3]))?;
+        let _ = device.pipeline_cache_data()?;
+
+        Ok(instance)
+    });
+}
@@ -8,12 +8,14 @@ use vki::{
     AddressMode, BindGroupBinding, BindGroupDescriptor, BindGroupLayoutBinding, BindGroupLayoutDescriptor,
     BindingResource, BindingType, BlendDescriptor, BlendFactor, BlendOperation, BufferDescriptor, BufferUsageFlags,
     BufferViewDescriptor, BufferViewFormat, Color, ColorStateDescriptor, ColorWriteFlags, CompareFunction,
-    ComputePipelineDescriptor, CullMode, DepthStencilStateDescriptor, Extent3D, FilterMode, FrontFace, IndexFormat,
-    InputStateDescriptor, InputStepMode, LoadOp, PipelineLayoutDescriptor, PipelineStageDescriptor, PrimitiveTopology,
-    RasterizationStateDescriptor, RenderPassColorAttachmentDescriptor, RenderPassDescriptor, RenderPipelineDescriptor,
-    SamplerDescriptor, ShaderModuleDescriptor, ShaderStageFlags, StencilOperation, StencilStateFaceDescriptor, StoreOp,
-    Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsageFlags, TextureView,
-    VertexAttributeDescriptor, VertexBufferDescriptor, VertexFormat,
+    ComputePipelineDescriptor, ConservativeRasterizationMode, CullMode, DepthStencilStateDescriptor, DeviceDescriptor,
+    Extensions, Extent3D, FilterMode, FrontFace, IndexFormat, InputStateDescriptor, InputStepMode, LoadOp,
+    MultisampleStateDescriptor, PipelineError, PipelineLayoutDescriptor, PipelineStageDescriptor, PolygonMode,
+    PrimitiveTopology, PushConstantRange, QueueType, RasterizationStateDescriptor, ReloadableComputePipelineDescriptor,
+    RenderPassColorAttachmentDescriptor, RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor, ScalarType,
+    ShaderBindingType, ShaderModuleDescriptor, ShaderStageFlags, SpecializationConstant, StencilOperation,
+    StencilStateFaceDescriptor, StoreOp, Texture, TextureDescriptor, TextureDimension, TextureFormat,
+    TextureUsageFlags, TextureView, VertexAttributeDescriptor, VertexBufferDescriptor, VertexFormat,
 };
 
 pub mod support;
@@ -54,6 +56,7 @@ fn create_pipeline_layout() {
         let pipeline_layout_descriptor = PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout],
             push_constant_ranges: vec![],
+            label: None,
         };
 
         let _pipeline_layout = device.create_pipeline_layout(pipeline_layout_descriptor)?;
@@ -69,6 +72,7 @@ fn create_compute_pipeline() {
 
         let shader_module_descriptor = ShaderModuleDescriptor {
             code: include_bytes!("shaders/pipeline.comp.spv"),
+            label: None,
         };
         let shader_module = device.create_shader_module(shader_module_descriptor)?;
 
@@ -91,6 +95,7 @@ fn create_compute_pipeline() {
         let pipeline_layout_descriptor = PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout],
             push_constant_ranges: vec![],
+            label: None,
         };
 
         let pipeline_layout = device.create_pipeline_layout(pipeline_layout_descriptor)?;
@@ -98,11 +103,13 @@ fn create_compute_pipeline() {
         let pipeline_stage_descriptor = PipelineStageDescriptor {
             entry_point: Cow::Borrowed("main"),
             module: shader_module,
+            specialization_constants: Default::default(),
         };
 
         let compute_pipeline_descriptor = ComputePipelineDescriptor {
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
             compute_stage: pipeline_stage_descriptor,
+            label: None,
         };
 
         let _compute_pipeline = device.create_compute_pipeline(compute_pipeline_descriptor)?;
@@ -112,16 +119,517 @@ fn create_compute_pipeline() {
 }
 
 #[test]
-fn create_render_pipeline() {
+fn reload_compute_pipeline() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let path = std::env::temp_dir().join(format!("vki.reload_compute_pipeline.{}.spv", std::process::id()));
+        std::fs::write(&path, &include_bytes!("shaders/pipeline.comp.spv")[..]).unwrap();
+
+        let reloadable_pipeline = device.create_reloadable_compute_pipeline(ReloadableComputePipelineDescriptor {
+            pipeline: ComputePipelineDescriptor {
+                layout: None,
+                compute_stage: PipelineStageDescriptor {
+                    entry_point: Cow::Borrowed("main"),
+                    module: device.create_shader_module_from_file(&path, ShaderStageFlags::COMPUTE)?,
+                    specialization_constants: Default::default(),
+                },
+                label: None,
+            },
+            compute_shader: path.clone(),
+        })?;
+
+        let initial_pipeline = reloadable_pipeline.pipeline();
+        assert_eq!(Ok(false), reloadable_pipeline.reload());
+
+        // the length differs from the previous contents, so the change is detected even if the
+        // modification time stays the same on file systems with coarse timestamps
+        std::fs::write(&path, &[0u8; 20][..]).unwrap();
+        assert!(reloadable_pipeline.reload().is_err());
+        assert_eq!(initial_pipeline, reloadable_pipeline.pipeline());
+        assert_eq!(Ok(false), reloadable_pipeline.reload());
+
+        std::fs::write(
+            &path,
+            &include_bytes!("shaders/command_buffer.push_constants.comp.spv")[..],
+        )
+        .unwrap();
+        assert_eq!(Ok(true), reloadable_pipeline.reload());
+        assert_ne!(initial_pipeline, reloadable_pipeline.pipeline());
+
+        std::fs::remove_file(&path).unwrap();
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn create_compute_pipeline_with_derived_layout() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let shader_module_descriptor = ShaderModuleDescriptor {
+            code: include_bytes!("shaders/pipeline.comp.spv"),
+            label: None,
+        };
+        let shader_module = device.create_shader_module(shader_module_descriptor)?;
+
+        let compute_pipeline_descriptor = ComputePipelineDescriptor {
+            layout: None,
+            compute_stage: PipelineStageDescriptor {
+                entry_point: Cow::Borrowed("main"),
+                module: shader_module,
+                specialization_constants: Default::default(),
+            },
+            label: None,
+        };
+
+        let compute_pipeline = device.create_compute_pipeline(compute_pipeline_descriptor)?;
+
+        let bind_group_layout = compute_pipeline.get_bind_group_layout(0).unwrap();
+        assert!(compute_pipeline.get_bind_group_layout(1).is_none());
+
+        let uniform_buffer = device.create_buffer(BufferDescriptor {
+            size: 64,
+            usage: BufferUsageFlags::UNIFORM,
+            label: None,
+        })?;
+        let storage_buffer = device.create_buffer(BufferDescriptor {
+            size: 64,
+            usage: BufferUsageFlags::STORAGE,
+            label: None,
+        })?;
+
+        let _bind_group = device.create_bind_group(BindGroupDescriptor {
+            layout: bind_group_layout,
+            bindings: vec![
+                BindGroupBinding {
+                    binding: 0,
+                    resource: BindingResource::Buffer(uniform_buffer, 0..64),
+                },
+                BindGroupBinding {
+                    binding: 1,
+                    resource: BindingResource::Buffer(storage_buffer, 0..64),
+                },
+            ],
+            label: None,
+        })?;
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn create_compute_pipeline_with_specialization_constants() {
     vki::validate(|| {
         let (instance, _adapter, device) = support::init()?;
 
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            code: include_bytes!("shaders/pipeline.comp.spv"),
+            label: None,
+        })?;
+
+        let compute_stage = PipelineStageDescriptor {
+            entry_point: Cow::Borrowed("main"),
+            module: shader_module,
+            specialization_constants: Default::default(),
+        };
+
+        let specialized_stage = compute_stage
+            .clone()
+            .with_specialization_constant(0, SpecializationConstant::Bool(true))
+            .with_specialization_constant(1, SpecializationConstant::Float(0.5));
+
+        assert_ne!(compute_stage, specialized_stage);
+        assert_eq!(
+            specialized_stage,
+            specialized_stage
+                .clone()
+                .with_specialization_constant(1, SpecializationConstant::Float(0.5))
+        );
+        assert_ne!(
+            specialized_stage,
+            specialized_stage
+                .clone()
+                .with_specialization_constant(1, SpecializationConstant::Uint(0.5f32.to_bits()))
+        );
+
+        // constant ids that the shader does not declare are ignored
+        let _compute_pipeline = device.create_compute_pipeline(ComputePipelineDescriptor {
+            layout: None,
+            compute_stage: specialized_stage,
+            label: None,
+        })?;
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn create_compute_pipeline_with_mismatched_shader() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            code: include_bytes!("shaders/pipeline.comp.spv"),
+            label: None,
+        })?;
+
         let vertex_shader_module = device.create_shader_module(ShaderModuleDescriptor {
             code: include_bytes!("shaders/pipeline.vert.spv"),
+            label: None,
+        })?;
+
+        let create_compute_pipeline = |layout_binding: BindGroupLayoutBinding,
+                                       compute_stage: PipelineStageDescriptor| {
+            let bind_group_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+                bindings: vec![
+                    BindGroupLayoutBinding {
+                        binding: 0,
+                        visibility: ShaderStageFlags::COMPUTE,
+                        binding_type: BindingType::UniformBuffer,
+                    },
+                    layout_binding,
+                ],
+            })?;
+            let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
+                bind_group_layouts: vec![bind_group_layout],
+                push_constant_ranges: vec![],
+                label: None,
+            })?;
+            device.create_compute_pipeline(ComputePipelineDescriptor {
+                layout: Some(pipeline_layout),
+                compute_stage,
+                label: None,
+            })
+        };
+
+        let compute_stage = PipelineStageDescriptor {
+            entry_point: Cow::Borrowed("main"),
+            module: shader_module.clone(),
+            specialization_constants: Default::default(),
+        };
+
+        let storage_buffer = BindGroupLayoutBinding {
+            binding: 1,
+            visibility: ShaderStageFlags::COMPUTE,
+            binding_type: BindingType::StorageBuffer,
+        };
+
+        create_compute_pipeline(storage_buffer, compute_stage.clone())?;
+
+        let result = create_compute_pipeline(
+            BindGroupLayoutBinding {
+                binding_type: BindingType::UniformBuffer,
+                ..storage_buffer
+            },
+            compute_stage.clone(),
+        );
+        assert_eq!(
+            Some(PipelineError::BindingTypeMismatch {
+                stage: ShaderStageFlags::COMPUTE,
+                set: 0,
+                binding: 1,
+                layout_type: BindingType::UniformBuffer,
+                shader_type: ShaderBindingType::StorageBuffer,
+            }),
+            result.map_err(PipelineError::from).err()
+        );
+
+        let result = create_compute_pipeline(
+            BindGroupLayoutBinding {
+                visibility: ShaderStageFlags::FRAGMENT,
+                ..storage_buffer
+            },
+            compute_stage.clone(),
+        );
+        assert_eq!(
+            Some(PipelineError::BindingNotVisible {
+                stage: ShaderStageFlags::COMPUTE,
+                set: 0,
+                binding: 1,
+            }),
+            result.map_err(PipelineError::from).err()
+        );
+
+        let result = create_compute_pipeline(
+            BindGroupLayoutBinding {
+                binding: 2,
+                ..storage_buffer
+            },
+            compute_stage.clone(),
+        );
+        assert_eq!(
+            Some(PipelineError::MissingBinding {
+                stage: ShaderStageFlags::COMPUTE,
+                set: 0,
+                binding: 1,
+            }),
+            result.map_err(PipelineError::from).err()
+        );
+
+        let result = create_compute_pipeline(
+            storage_buffer,
+            PipelineStageDescriptor {
+                entry_point: Cow::Borrowed("not_main"),
+                module: shader_module,
+                specialization_constants: Default::default(),
+            },
+        );
+        assert_eq!(
+            Some(PipelineError::EntryPointNotFound {
+                stage: ShaderStageFlags::COMPUTE,
+                entry_point: "not_main".to_owned(),
+            }),
+            result.map_err(PipelineError::from).err()
+        );
+
+        let result = create_compute_pipeline(
+            storage_buffer,
+            PipelineStageDescriptor {
+                entry_point: Cow::Borrowed("main"),
+                module: vertex_shader_module,
+                specialization_constants: Default::default(),
+            },
+        );
+        assert_eq!(
+            Some(PipelineError::EntryPointStageMismatch {
+                entry_point: "main".to_owned(),
+                expected: ShaderStageFlags::COMPUTE,
+                found: ShaderStageFlags::VERTEX,
+            }),
+            result.map_err(PipelineError::from).err()
+        );
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn create_compute_pipeline_with_push_constant_ranges() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        // the push constant block covers bytes 0..8
+        let shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            code: include_bytes!("shaders/command_buffer.push_constants.comp.spv"),
+            label: None,
+        })?;
+
+        let create_compute_pipeline = |offset: usize, size: usize| {
+            let bind_group_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+                bindings: vec![BindGroupLayoutBinding {
+                    binding: 0,
+                    visibility: ShaderStageFlags::COMPUTE,
+                    binding_type: BindingType::StorageBuffer,
+                }],
+            })?;
+            let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
+                bind_group_layouts: vec![bind_group_layout],
+                push_constant_ranges: vec![PushConstantRange {
+                    stages: ShaderStageFlags::COMPUTE,
+                    offset,
+                    size,
+                }],
+                label: None,
+            })?;
+            device.create_compute_pipeline(ComputePipelineDescriptor {
+                layout: Some(pipeline_layout),
+                compute_stage: PipelineStageDescriptor {
+                    entry_point: Cow::Borrowed("main"),
+                    module: shader_module.clone(),
+                    specialization_constants: Default::default(),
+                },
+                label: None,
+            })
+        };
+
+        create_compute_pipeline(0, 8)?;
+        create_compute_pipeline(0, 16)?;
+
+        let missing_range = Some(PipelineError::MissingPushConstantRange {
+            stage: ShaderStageFlags::COMPUTE,
+            offset: 0,
+            size: 8,
+        });
+        // the range ends past the block, but does not cover its start
+        assert_eq!(
+            missing_range,
+            create_compute_pipeline(4, 8).map_err(PipelineError::from).err()
+        );
+        assert_eq!(
+            missing_range,
+            create_compute_pipeline(0, 4).map_err(PipelineError::from).err()
+        );
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn create_render_pipeline_with_mismatched_shaders() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let vertex_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            code: include_bytes!("shaders/pipeline.vert.spv"),
+            label: None,
+        })?;
+
+        let fragment_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            code: include_bytes!("shaders/pipeline.frag.spv"),
+            label: None,
+        })?;
+
+        let color_replace = BlendDescriptor {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::Zero,
+            operation: BlendOperation::Add,
+        };
+
+        #[rustfmt::skip]
+        let render_pipeline_descriptor = RenderPipelineDescriptor {
+            layout: None,
+            primitive_topology: PrimitiveTopology::TriangleList,
+            vertex_stage: PipelineStageDescriptor {
+                entry_point: Cow::Borrowed("main"),
+                module: vertex_shader_module,
+                specialization_constants: Default::default(),
+            },
+            fragment_stage: PipelineStageDescriptor {
+                entry_point: Cow::Borrowed("main"),
+                module: fragment_shader_module,
+                specialization_constants: Default::default(),
+            },
+            input_state: InputStateDescriptor {
+                index_format: IndexFormat::U16,
+                vertex_buffers: vec![
+                    VertexBufferDescriptor {
+                        input_slot: 0,
+                        step_mode: InputStepMode::Vertex,
+                        stride: 24,
+                        attributes: vec![
+                            VertexAttributeDescriptor {
+                                format: VertexFormat::Float3,
+                                offset: 0,
+                                shader_location: 0,
+                            },
+                            VertexAttributeDescriptor {
+                                format: VertexFormat::Float3,
+                                offset: 12,
+                                shader_location: 1,
+                            },
+                        ],
+                    }
+                ],
+            },
+            color_states: vec![
+                ColorStateDescriptor {
+                    format: TextureFormat::B8G8R8A8Unorm,
+                    write_mask: ColorWriteFlags::ALL,
+                    color_blend: color_replace,
+                    alpha_blend: color_replace,
+                }
+            ],
+            depth_stencil_state: None,
+            rasterization_state: RasterizationStateDescriptor {
+                front_face: FrontFace::Ccw,
+                cull_mode: CullMode::Back,
+                polygon_mode: PolygonMode::Fill,
+                depth_clamp: false,
+                depth_bias: 0,
+                depth_bias_slope_scale: 0.0,
+                depth_bias_clamp: 0.0,
+                line_width: 1.0,
+                conservative_rasterization: ConservativeRasterizationMode::Disabled,
+            },
+            multisample_state: MultisampleStateDescriptor::default(),
+            sample_count: 1,
+            label: None,
+        };
+
+        let render_pipeline = device.create_render_pipeline(render_pipeline_descriptor.clone())?;
+        assert!(render_pipeline.get_bind_group_layout(0).is_some());
+
+        let mut descriptor = render_pipeline_descriptor.clone();
+        descriptor.input_state.vertex_buffers[0].attributes.pop();
+        assert_eq!(
+            Some(PipelineError::MissingVertexAttribute { location: 1 }),
+            device
+                .create_render_pipeline(descriptor)
+                .map_err(PipelineError::from)
+                .err()
+        );
+
+        let mut descriptor = render_pipeline_descriptor.clone();
+        descriptor.input_state.vertex_buffers[0].attributes[1].format = VertexFormat::UInt3;
+        assert_eq!(
+            Some(PipelineError::VertexAttributeTypeMismatch {
+                location: 1,
+                format: VertexFormat::UInt3,
+                shader_type: ScalarType::Float,
+            }),
+            device
+                .create_render_pipeline(descriptor)
+                .map_err(PipelineError::from)
+                .err()
+        );
+
+        let mut descriptor = render_pipeline_descriptor.clone();
+        descriptor.color_states.clear();
+        assert_eq!(
+            Some(PipelineError::MissingColorState { location: 0 }),
+            device
+                .create_render_pipeline(descriptor)
+                .map_err(PipelineError::from)
+                .err()
+        );
+
+        let mut descriptor = render_pipeline_descriptor.clone();
+        descriptor.color_states[0].format = TextureFormat::R8G8B8A8Uint;
+        assert_eq!(
+            Some(PipelineError::ColorStateTypeMismatch {
+                location: 0,
+                format: TextureFormat::R8G8B8A8Uint,
+                shader_type: ScalarType::Float,
+            }),
+            device
+                .create_render_pipeline(descriptor)
+                .map_err(PipelineError::from)
+                .err()
+        );
+
+        let mut descriptor = render_pipeline_descriptor.clone();
+        descriptor.fragment_stage = render_pipeline_descriptor.vertex_stage.clone();
+        assert_eq!(
+            Some(PipelineError::EntryPointStageMismatch {
+                entry_point: "main".to_owned(),
+                expected: ShaderStageFlags::FRAGMENT,
+                found: ShaderStageFlags::VERTEX,
+            }),
+            device
+                .create_render_pipeline(descriptor)
+                .map_err(PipelineError::from)
+                .err()
+        );
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn create_render_pipeline() {
+    vki::validate(|| {
+        let (instance, adapter, device) = support::init()?;
+
+        let vertex_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            code: include_bytes!("shaders/pipeline.vert.spv"),
+            label: None,
         })?;
 
         let fragment_shader_module = device.create_shader_module(ShaderModuleDescriptor {
             code: include_bytes!("shaders/pipeline.frag.spv"),
+            label: None,
         })?;
 
         #[rustfmt::skip]
@@ -138,6 +646,7 @@ fn create_render_pipeline() {
         let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout],
             push_constant_ranges: vec![],
+            label: None,
         })?;
 
         #[repr(C)]
@@ -161,15 +670,17 @@ fn create_render_pipeline() {
 
         #[rustfmt::skip]
         let render_pipeline_descriptor = RenderPipelineDescriptor {
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
             primitive_topology: PrimitiveTopology::TriangleList,
             vertex_stage: PipelineStageDescriptor {
                 entry_point: Cow::Borrowed("main"),
                 module: vertex_shader_module,
+                specialization_constants: Default::default(),
             },
             fragment_stage: PipelineStageDescriptor {
                 entry_point: Cow::Borrowed("main"),
                 module: fragment_shader_module,
+                specialization_constants: Default::default(),
             },
             input_state: InputStateDescriptor {
                 index_format: IndexFormat::U16,
@@ -209,18 +720,100 @@ fn create_render_pipeline() {
                 stencil_front: stencil_disabled,
                 stencil_write_mask: 0,
                 stencil_read_mask: 0,
+                depth_bounds: None,
             }),
             rasterization_state: RasterizationStateDescriptor {
                 front_face: FrontFace::Ccw,
                 cull_mode: CullMode::Back,
+                polygon_mode: PolygonMode::Fill,
+                depth_clamp: false,
                 depth_bias: 0,
                 depth_bias_slope_scale: 0.0,
                 depth_bias_clamp: 0.0,
+                line_width: 1.0,
+                conservative_rasterization: ConservativeRasterizationMode::Disabled,
             },
+            multisample_state: MultisampleStateDescriptor::default(),
             sample_count: 1,
+            label: None,
         };
 
-        let _render_pipeline = device.create_render_pipeline(render_pipeline_descriptor)?;
+        let _render_pipeline = device.create_render_pipeline(render_pipeline_descriptor.clone())?;
+
+        // the extended rasterization options require device extensions, which are not enabled
+        let mut wireframe = render_pipeline_descriptor.clone();
+        wireframe.rasterization_state.polygon_mode = PolygonMode::Line;
+        assert!(device.create_render_pipeline(wireframe).is_err());
+
+        let mut depth_clamp = render_pipeline_descriptor.clone();
+        depth_clamp.rasterization_state.depth_clamp = true;
+        assert!(device.create_render_pipeline(depth_clamp).is_err());
+
+        let mut wide_lines = render_pipeline_descriptor.clone();
+        wide_lines.rasterization_state.line_width = 2.0;
+        assert!(device.create_render_pipeline(wide_lines).is_err());
+
+        let mut depth_bounds = render_pipeline_descriptor.clone();
+        depth_bounds.depth_stencil_state.as_mut().unwrap().depth_bounds = Some((0.25, 0.75));
+        assert!(device.create_render_pipeline(depth_bounds).is_err());
+
+        let mut conservative = render_pipeline_descriptor.clone();
+        conservative.rasterization_state.conservative_rasterization = ConservativeRasterizationMode::Overestimate;
+        assert!(device.create_render_pipeline(conservative).is_err());
+
+        let supported = *adapter.extensions();
+        if !supported.fill_mode_non_solid && !supported.depth_clamp && !supported.conservative_rasterization {
+            return Ok(instance);
+        }
+
+        let extensions = Extensions {
+            fill_mode_non_solid: supported.fill_mode_non_solid,
+            depth_clamp: supported.depth_clamp,
+            conservative_rasterization: supported.conservative_rasterization,
+            ..Extensions::default()
+        };
+        let device = adapter.create_device(DeviceDescriptor::default().with_extensions(extensions))?;
+
+        let vertex_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            code: include_bytes!("shaders/pipeline.vert.spv"),
+            label: None,
+        })?;
+
+        let fragment_shader_module = device.create_shader_module(ShaderModuleDescriptor {
+            code: include_bytes!("shaders/pipeline.frag.spv"),
+            label: None,
+        })?;
+
+        let render_pipeline_descriptor = RenderPipelineDescriptor {
+            layout: None,
+            vertex_stage: PipelineStageDescriptor {
+                module: vertex_shader_module,
+                ..render_pipeline_descriptor.vertex_stage
+            },
+            fragment_stage: PipelineStageDescriptor {
+                module: fragment_shader_module,
+                ..render_pipeline_descriptor.fragment_stage
+            },
+            ..render_pipeline_descriptor
+        };
+
+        if extensions.fill_mode_non_solid {
+            let mut wireframe = render_pipeline_descriptor.clone();
+            wireframe.rasterization_state.polygon_mode = PolygonMode::Line;
+            let _render_pipeline = device.create_render_pipeline(wireframe)?;
+        }
+
+        if extensions.depth_clamp {
+            let mut depth_clamp = render_pipeline_descriptor.clone();
+            depth_clamp.rasterization_state.depth_clamp = true;
+            let _render_pipeline = device.create_render_pipeline(depth_clamp)?;
+        }
+
+        if extensions.conservative_rasterization {
+            let mut conservative = render_pipeline_descriptor.clone();
+            conservative.rasterization_state.conservative_rasterization = ConservativeRasterizationMode::Overestimate;
+            let _render_pipeline = device.create_render_pipeline(conservative)?;
+        }
 
         Ok(instance)
     });
@@ -233,10 +826,12 @@ fn create_multi_sample_render_pipeline() {
 
         let vertex_shader_module = device.create_shader_module(ShaderModuleDescriptor {
             code: include_bytes!("shaders/pipeline.vert.spv"),
+            label: None,
         })?;
 
         let fragment_shader_module = device.create_shader_module(ShaderModuleDescriptor {
             code: include_bytes!("shaders/pipeline.frag.spv"),
+            label: None,
         })?;
 
         #[rustfmt::skip]
@@ -255,6 +850,7 @@ fn create_multi_sample_render_pipeline() {
         let uniform_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::UNIFORM | BufferUsageFlags::TRANSFER_DST,
             size: uniform_buffer_size,
+            label: None,
         })?;
 
         let bind_group = device.create_bind_group(BindGroupDescriptor {
@@ -263,11 +859,13 @@ fn create_multi_sample_render_pipeline() {
                 binding: 0,
                 resource: BindingResource::Buffer(uniform_buffer, 0..uniform_buffer_size),
             }],
+            label: None,
         })?;
 
         let pipeline_layout = device.create_pipeline_layout(PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout],
             push_constant_ranges: vec![],
+            label: None,
         })?;
 
         #[repr(C)]
@@ -279,6 +877,7 @@ fn create_multi_sample_render_pipeline() {
         let vertex_buffer = device.create_buffer(BufferDescriptor {
             usage: BufferUsageFlags::VERTEX,
             size: (3 * std::mem::size_of::<Vertex>()) as _,
+            label: None,
         })?;
 
         let color_replace = BlendDescriptor {
@@ -291,15 +890,17 @@ fn create_multi_sample_render_pipeline() {
 
         #[rustfmt::skip]
         let render_pipeline_descriptor = RenderPipelineDescriptor {
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
             primitive_topology: PrimitiveTopology::TriangleList,
             vertex_stage: PipelineStageDescriptor {
                 entry_point: Cow::Borrowed("main"),
                 module: vertex_shader_module,
+                specialization_constants: Default::default(),
             },
             fragment_stage: PipelineStageDescriptor {
                 entry_point: Cow::Borrowed("main"),
                 module: fragment_shader_module,
+                specialization_constants: Default::default(),
             },
             input_state: InputStateDescriptor {
                 index_format: IndexFormat::U16,
@@ -335,14 +936,38 @@ fn create_multi_sample_render_pipeline() {
             rasterization_state: RasterizationStateDescriptor {
                 front_face: FrontFace::Ccw,
                 cull_mode: CullMode::Back,
+                polygon_mode: PolygonMode::Fill,
+                depth_clamp: false,
                 depth_bias: 0,
                 depth_bias_slope_scale: 0.0,
                 depth_bias_clamp: 0.0,
+                line_width: 1.0,
+                conservative_rasterization: ConservativeRasterizationMode::Disabled,
             },
+            multisample_state: MultisampleStateDescriptor::default(),
             sample_count,
+            label: None,
         };
 
-        let pipeline = device.create_render_pipeline(render_pipeline_descriptor)?;
+        let pipeline = device.create_render_pipeline(render_pipeline_descriptor.clone())?;
+
+        let mut alpha_to_coverage = render_pipeline_descriptor.clone();
+        alpha_to_coverage.multisample_state = MultisampleStateDescriptor {
+            alpha_to_coverage_enabled: true,
+            sample_mask: 0b0101,
+            min_sample_shading: None,
+        };
+        let _alpha_to_coverage_pipeline = device.create_render_pipeline(alpha_to_coverage.clone())?;
+
+        // alpha to coverage requires multisampling
+        let mut single_sample = alpha_to_coverage.clone();
+        single_sample.sample_count = 1;
+        assert!(device.create_render_pipeline(single_sample).is_err());
+
+        // sample shading requires the sample_rate_shading extension, which is not enabled
+        let mut sample_shading = render_pipeline_descriptor.clone();
+        sample_shading.multisample_state.min_sample_shading = Some(0.5);
+        assert!(device.create_render_pipeline(sample_shading).is_err());
 
         let size = Extent3D {
             width: 800,
@@ -363,6 +988,7 @@ fn create_multi_sample_render_pipeline() {
             dimension,
             format,
             usage,
+            label: None,
         })?;
 
         let frame_view = frame_texture.create_default_view()?;
@@ -380,6 +1006,7 @@ fn create_multi_sample_render_pipeline() {
             dimension,
             format,
             usage,
+            label: None,
         })?;
 
         let output_view = output_texture.create_default_view()?;
@@ -408,7 +1035,7 @@ fn create_multi_sample_render_pipeline() {
         render_pass.draw(3, 1, 0, 0);
         render_pass.end_pass();
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
         queue.submit(&[encoder.finish()?])?;
 
         Ok(instance)
@@ -422,6 +1049,7 @@ fn set_bind_group() {
 
         let shader_module_descriptor = ShaderModuleDescriptor {
             code: include_bytes!("shaders/pipeline.set_bind_group.comp.spv"),
+            label: None,
         };
         let shader_module = device.create_shader_module(shader_module_descriptor)?;
 
@@ -459,6 +1087,7 @@ fn set_bind_group() {
         let pipeline_layout_descriptor = PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout.clone()],
             push_constant_ranges: vec![],
+            label: None,
         };
 
         let pipeline_layout = device.create_pipeline_layout(pipeline_layout_descriptor)?;
@@ -466,11 +1095,13 @@ fn set_bind_group() {
         let pipeline_stage_descriptor = PipelineStageDescriptor {
             entry_point: Cow::Borrowed("main"),
             module: shader_module,
+            specialization_constants: Default::default(),
         };
 
         let compute_pipeline_descriptor = ComputePipelineDescriptor {
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
             compute_stage: pipeline_stage_descriptor,
+            label: None,
         };
 
         let compute_pipeline = device.create_compute_pipeline(compute_pipeline_descriptor)?;
@@ -478,14 +1109,17 @@ fn set_bind_group() {
         let uniform_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
             usage: BufferUsageFlags::UNIFORM,
+            label: None,
         })?;
         let storage_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
             usage: BufferUsageFlags::STORAGE,
+            label: None,
         })?;
         let image_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
-            usage: BufferUsageFlags::STORAGE, // TODO: texel storage
+            usage: BufferUsageFlags::STORAGE, // TODO: texel storage,
+            label: None,
         })?;
         let image_buffer_view = image_buffer.create_view(BufferViewDescriptor {
             size: 1024,
@@ -502,6 +1136,8 @@ fn set_bind_group() {
             address_mode_v: AddressMode::ClampToEdge,
             address_mode_w: AddressMode::ClampToEdge,
             compare_function: CompareFunction::Never,
+            label: None,
+            max_anisotropy: 1.0,
         })?;
         let texture = device.create_texture(TextureDescriptor {
             size: Extent3D {
@@ -515,6 +1151,7 @@ fn set_bind_group() {
             sample_count: 1,
             mip_level_count: 1,
             array_layer_count: 1,
+            label: None,
         })?;
         let texture_view = texture.create_default_view()?;
 
@@ -542,6 +1179,7 @@ fn set_bind_group() {
                     resource: BindingResource::TextureView(texture_view),
                 },
             ],
+            label: None,
         })?;
 
         let mut encoder = device.create_command_encoder()?;
@@ -553,7 +1191,7 @@ fn set_bind_group() {
         compute_pass.end_pass();
 
         let command_buffer = encoder.finish()?;
-        device.get_queue().submit(&[command_buffer])?;
+        device.get_queue(QueueType::Graphics).submit(&[command_buffer])?;
 
         Ok(instance)
     });
@@ -566,6 +1204,7 @@ fn set_bind_group_out_of_order() {
 
         let shader_module_descriptor = ShaderModuleDescriptor {
             code: include_bytes!("shaders/pipeline.set_bind_group.comp.spv"),
+            label: None,
         };
         let shader_module = device.create_shader_module(shader_module_descriptor)?;
 
@@ -603,6 +1242,7 @@ fn set_bind_group_out_of_order() {
         let pipeline_layout_descriptor = PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout.clone()],
             push_constant_ranges: vec![],
+            label: None,
         };
 
         let pipeline_layout = device.create_pipeline_layout(pipeline_layout_descriptor)?;
@@ -610,11 +1250,13 @@ fn set_bind_group_out_of_order() {
         let pipeline_stage_descriptor = PipelineStageDescriptor {
             entry_point: Cow::Borrowed("main"),
             module: shader_module,
+            specialization_constants: Default::default(),
         };
 
         let compute_pipeline_descriptor = ComputePipelineDescriptor {
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
             compute_stage: pipeline_stage_descriptor,
+            label: None,
         };
 
         let compute_pipeline = device.create_compute_pipeline(compute_pipeline_descriptor)?;
@@ -622,14 +1264,17 @@ fn set_bind_group_out_of_order() {
         let uniform_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
             usage: BufferUsageFlags::UNIFORM,
+            label: None,
         })?;
         let storage_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
             usage: BufferUsageFlags::STORAGE,
+            label: None,
         })?;
         let image_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
-            usage: BufferUsageFlags::STORAGE, // TODO: texel storage
+            usage: BufferUsageFlags::STORAGE, // TODO: texel storage,
+            label: None,
         })?;
         let image_buffer_view = image_buffer.create_view(BufferViewDescriptor {
             size: 1024,
@@ -646,6 +1291,8 @@ fn set_bind_group_out_of_order() {
             address_mode_v: AddressMode::ClampToEdge,
             address_mode_w: AddressMode::ClampToEdge,
             compare_function: CompareFunction::Never,
+            label: None,
+            max_anisotropy: 1.0,
         })?;
         let texture = device.create_texture(TextureDescriptor {
             size: Extent3D {
@@ -659,6 +1306,7 @@ fn set_bind_group_out_of_order() {
             sample_count: 1,
             mip_level_count: 1,
             array_layer_count: 1,
+            label: None,
         })?;
         let texture_view = texture.create_default_view()?;
 
@@ -688,6 +1336,7 @@ fn set_bind_group_out_of_order() {
                     resource: BindingResource::BufferView(image_buffer_view),
                 },
             ],
+            label: None,
         })?;
 
         let mut encoder = device.create_command_encoder()?;
@@ -699,7 +1348,7 @@ fn set_bind_group_out_of_order() {
         compute_pass.end_pass();
 
         let command_buffer = encoder.finish()?;
-        device.get_queue().submit(&[command_buffer])?;
+        device.get_queue(QueueType::Graphics).submit(&[command_buffer])?;
 
         Ok(instance)
     });
@@ -712,6 +1361,7 @@ fn set_bind_group_dynamic_offsets() {
 
         let shader_module_descriptor = ShaderModuleDescriptor {
             code: include_bytes!("shaders/pipeline.set_bind_group.comp.spv"),
+            label: None,
         };
         let shader_module = device.create_shader_module(shader_module_descriptor)?;
 
@@ -749,6 +1399,7 @@ fn set_bind_group_dynamic_offsets() {
         let pipeline_layout_descriptor = PipelineLayoutDescriptor {
             bind_group_layouts: vec![bind_group_layout.clone()],
             push_constant_ranges: vec![],
+            label: None,
         };
 
         let pipeline_layout = device.create_pipeline_layout(pipeline_layout_descriptor)?;
@@ -756,11 +1407,13 @@ fn set_bind_group_dynamic_offsets() {
         let pipeline_stage_descriptor = PipelineStageDescriptor {
             entry_point: Cow::Borrowed("main"),
             module: shader_module,
+            specialization_constants: Default::default(),
         };
 
         let compute_pipeline_descriptor = ComputePipelineDescriptor {
-            layout: pipeline_layout,
+            layout: Some(pipeline_layout),
             compute_stage: pipeline_stage_descriptor,
+            label: None,
         };
 
         let compute_pipeline = device.create_compute_pipeline(compute_pipeline_descriptor)?;
@@ -768,14 +1421,17 @@ fn set_bind_group_dynamic_offsets() {
         let uniform_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
             usage: BufferUsageFlags::UNIFORM,
+            label: None,
         })?;
         let storage_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
             usage: BufferUsageFlags::STORAGE,
+            label: None,
         })?;
         let image_buffer = device.create_buffer(BufferDescriptor {
             size: 1024,
-            usage: BufferUsageFlags::STORAGE, // TODO: texel storage
+            usage: BufferUsageFlags::STORAGE, // TODO: texel storage,
+            label: None,
         })?;
         let image_buffer_view = image_buffer.create_view(BufferViewDescriptor {
             size: 1024,
@@ -792,6 +1448,8 @@ fn set_bind_group_dynamic_offsets() {
             address_mode_v: AddressMode::ClampToEdge,
             address_mode_w: AddressMode::ClampToEdge,
             compare_function: CompareFunction::Never,
+            label: None,
+            max_anisotropy: 1.0,
         })?;
         let texture = device.create_texture(TextureDescriptor {
             size: Extent3D {
@@ -805,6 +1463,7 @@ fn set_bind_group_dynamic_offsets() {
             sample_count: 1,
             mip_level_count: 1,
             array_layer_count: 1,
+            label: None,
         })?;
         let texture_view = texture.create_default_view()?;
 
@@ -832,6 +1491,7 @@ fn set_bind_group_dynamic_offsets() {
                     resource: BindingResource::TextureView(texture_view),
                 },
             ],
+            label: None,
         })?;
 
         let mut encoder = device.create_command_encoder()?;
@@ -847,7 +1507,7 @@ fn set_bind_group_dynamic_offsets() {
         compute_pass.end_pass();
 
         let command_buffer = encoder.finish()?;
-        device.get_queue().submit(&[command_buffer])?;
+        device.get_queue(QueueType::Graphics).submit(&[command_buffer])?;
 
         Ok(instance)
     });
@@ -1,4 +1,4 @@
-use vki::SamplerDescriptor;
+use vki::{DeviceDescriptor, Extensions, SamplerDescriptor};
 
 pub mod support;
 
@@ -11,3 +11,28 @@ fn create_sampler() {
         Ok(instance)
     });
 }
+
+#[test]
+fn create_sampler_anisotropy() {
+    vki::validate(|| {
+        let (instance, adapter, device) = support::init()?;
+        let descriptor = SamplerDescriptor {
+            max_anisotropy: 16.0,
+            ..SamplerDescriptor::default()
+        };
+
+        // anisotropic filtering must be enabled on the device
+        assert!(device.create_sampler(descriptor).is_err());
+
+        if adapter.extensions().sampler_anisotropy {
+            let extensions = Extensions {
+                sampler_anisotropy: true,
+                ..Extensions::default()
+            };
+            let device = adapter.create_device(DeviceDescriptor::default().with_extensions(extensions))?;
+            let _sampler = device.create_sampler(descriptor)?;
+        }
+
+        Ok(instance)
+    });
+}
@@ -1,4 +1,4 @@
-use vki::ShaderModuleDescriptor;
+use vki::{ShaderModuleDescriptor, ShaderStageFlags};
 
 pub mod support;
 
@@ -8,8 +8,69 @@ fn create_shader_module() {
         let (instance, _adapter, device) = support::init()?;
         let descriptor = ShaderModuleDescriptor {
             code: include_bytes!("shaders/shader.vert.spv"),
+            label: None,
         };
         let _shader_module = device.create_shader_module(descriptor)?;
         Ok(instance)
     });
 }
+
+#[test]
+fn shader_module_reflection() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+        let descriptor = ShaderModuleDescriptor {
+            code: include_bytes!("shaders/shader.vert.spv"),
+            label: None,
+        };
+        let shader_module = device.create_shader_module(descriptor)?;
+        let entry_point = shader_module.reflection().entry_point("main").unwrap();
+        assert_eq!(ShaderStageFlags::VERTEX, entry_point.stage);
+        assert!(entry_point.bindings.is_empty());
+        assert!(entry_point.inputs.is_empty());
+        assert!(entry_point.outputs.is_empty());
+        Ok(instance)
+    });
+}
+
+#[test]
+fn create_shader_module_with_invalid_code() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+        let descriptor = ShaderModuleDescriptor {
+            code: &[0; 20],
+            label: None,
+        };
+        assert!(device.create_shader_module(descriptor).is_err());
+        Ok(instance)
+    });
+}
+
+#[cfg(feature = "naga")]
+#[test]
+fn create_shader_module_from_source() {
+    use vki::{ShaderLanguage, ShaderSourceDescriptor};
+
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+        let descriptor = ShaderSourceDescriptor {
+            source: include_str!("shaders/pipeline.vert.glsl"),
+            language: ShaderLanguage::Glsl,
+            stage: ShaderStageFlags::VERTEX,
+            label: Some("pipeline.vert"),
+        };
+        let shader_module = device.create_shader_module_from_source(descriptor)?;
+        let entry_point = shader_module.reflection().entry_point("main").unwrap();
+        assert_eq!(ShaderStageFlags::VERTEX, entry_point.stage);
+        assert_eq!(2, entry_point.inputs.len());
+
+        let descriptor = ShaderSourceDescriptor {
+            source: "@compute @workgroup_size(1)\nfn main() {\n    let x: u32 = 1.0;\n}\n",
+            language: ShaderLanguage::Wgsl,
+            stage: ShaderStageFlags::COMPUTE,
+            label: None,
+        };
+        assert!(device.create_shader_module_from_source(descriptor).is_err());
+        Ok(instance)
+    });
+}
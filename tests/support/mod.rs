@@ -5,24 +5,29 @@
 
 use vki::{winit_surface_descriptor, PowerPreference};
 use vki::{
-    Adapter, AdapterOptions, Device, DeviceDescriptor, Instance, Surface, Swapchain, SwapchainDescriptor,
-    TextureFormat, TextureUsageFlags,
+    Adapter, AdapterOptions, Device, DeviceDescriptor, Instance, InstanceDescriptor, Surface, Swapchain,
+    SwapchainDescriptor, TextureFormat, TextureUsageFlags,
 };
 
 use winit::dpi::LogicalSize;
 use winit::event_loop::EventLoop;
 use winit::window::Window;
 
-/// Setup validation and logging. This is called automatically
-/// by `init` and `init_with_window`.
+/// Setup logging. This is called automatically by `init` and `init_with_window`.
 pub fn init_environment() {
-    // NOTE: This is currently *also* set via instance creation, but that should probably
-    //       be configurable. However, we *always* want it enabled for tests.
-    std::env::set_var("VK_INSTANCE_LAYERS", "VK_LAYER_LUNARG_standard_validation");
-
     let _ = pretty_env_logger::try_init();
 }
 
+/// Creates an instance with validation enabled. We *always* want it enabled for tests.
+pub fn create_instance() -> Result<Instance, Box<dyn std::error::Error>> {
+    let instance = Instance::with_descriptor(InstanceDescriptor {
+        validation: Some(true),
+        ..InstanceDescriptor::default()
+    })?;
+
+    Ok(instance)
+}
+
 fn select_power_preference() -> PowerPreference {
     use std::str::FromStr;
     let test_integrated_gpu = std::env::var("TEST_INTEGRATED_GPU").unwrap_or("false".to_owned());
@@ -35,12 +40,24 @@ fn select_power_preference() -> PowerPreference {
     }
 }
 
-pub fn init() -> Result<(Instance, Adapter, Device), Box<dyn std::error::Error>> {
-    init_environment();
+fn select_adapter_options() -> AdapterOptions {
     let power_preference = select_power_preference();
     log::debug!("power_preference: {:?}", power_preference);
-    let instance = Instance::new()?;
-    let adapter = instance.get_adapter(AdapterOptions { power_preference })?;
+    let options = AdapterOptions {
+        power_preference,
+        ..AdapterOptions::default()
+    };
+    // e.g. `TEST_ADAPTER_NAME=llvmpipe` selects Mesa's software rasterizer
+    match std::env::var("TEST_ADAPTER_NAME") {
+        Ok(name) => options.name(name),
+        Err(_) => options,
+    }
+}
+
+pub fn init() -> Result<(Instance, Adapter, Device), Box<dyn std::error::Error>> {
+    init_environment();
+    let instance = create_instance()?;
+    let adapter = instance.get_adapter(select_adapter_options())?;
     let device = adapter.create_device(DeviceDescriptor::default())?;
 
     Ok((instance, adapter, device))
@@ -50,7 +67,7 @@ pub fn init_with_window(
     window: &Window,
 ) -> Result<(Instance, Adapter, Device, Surface, Swapchain), Box<dyn std::error::Error>> {
     init_environment();
-    let instance = Instance::new()?;
+    let instance = create_instance()?;
     let adapter = instance.get_adapter(AdapterOptions::default())?;
     let surface_descriptor = winit_surface_descriptor!(window);
     let surface = instance.create_surface(&surface_descriptor)?;
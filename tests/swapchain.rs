@@ -1,5 +1,5 @@
 use vki::winit_surface_descriptor;
-use vki::{AdapterOptions, DeviceDescriptor, Instance};
+use vki::{AdapterOptions, DeviceDescriptor, Instance, QueueType};
 
 use winit::dpi::LogicalSize;
 use winit::event::{Event, WindowEvent};
@@ -90,7 +90,7 @@ fn present() {
 
         let frame = swapchain.acquire_next_image()?;
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
         queue.present(frame)?;
 
         Ok(instance)
@@ -107,7 +107,7 @@ fn recreate_after_resize() {
         let (mut event_loop, window) = support::headless_window()?;
         let (instance, _adapter, device, surface, mut swapchain) = support::init_with_window(&window)?;
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         let frame = swapchain.acquire_next_image()?;
 
@@ -160,7 +160,7 @@ fn keep_surface_alive() {
         drop(surface);
 
         let frame = swapchain.acquire_next_image()?;
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
         queue.present(frame)?;
 
         Ok(instance)
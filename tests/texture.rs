@@ -1,9 +1,11 @@
 use vki::{
-    BufferCopyView, BufferDescriptor, BufferUsageFlags, Extent3D, FilterMode, Origin3D, TextureAspectFlags,
-    TextureBlitView, TextureCopyView, TextureDescriptor, TextureDimension, TextureFormat, TextureUsageFlags,
-    TextureViewDescriptor, TextureViewDimension,
+    BufferCopyView, BufferDescriptor, BufferUsageFlags, DeviceDescriptor, Extensions, Extent3D, FilterMode, Origin3D,
+    QueueType, TextureAspectFlags, TextureBlitView, TextureCopyView, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureSubresourceRange, TextureUsageFlags, TextureViewDescriptor, TextureViewDimension,
 };
 
+use std::time::Duration;
+
 pub mod support;
 
 #[test]
@@ -23,6 +25,7 @@ fn create_texture() {
             sample_count: 1,
             dimension: TextureDimension::D2,
             format: TextureFormat::R8G8B8A8Unorm,
+            label: None,
         };
 
         let _texture = device.create_texture(descriptor)?;
@@ -31,6 +34,93 @@ fn create_texture() {
     });
 }
 
+#[test]
+fn create_texture_exceeding_limits() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let limits = device.limits();
+
+        let descriptor = TextureDescriptor {
+            usage: TextureUsageFlags::SAMPLED,
+            size: Extent3D {
+                width: limits.max_texture_dimension_2d + 1,
+                height: 1,
+                depth: 1,
+            },
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::R8G8B8A8Unorm,
+            label: None,
+        };
+        assert!(device.create_texture(descriptor).is_err());
+
+        let descriptor = TextureDescriptor {
+            size: Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            },
+            array_layer_count: limits.max_texture_array_layers + 1,
+            ..descriptor
+        };
+        assert!(device.create_texture(descriptor).is_err());
+
+        Ok(instance)
+    });
+}
+
+#[test]
+fn texture_format_capabilities() {
+    vki::validate(|| {
+        let (instance, adapter, device) = support::init()?;
+
+        // required by the Vulkan spec
+        let capabilities = adapter.texture_format_capabilities(TextureFormat::R8G8B8A8Unorm);
+        assert!(capabilities.sampled);
+        assert!(capabilities.storage);
+        assert!(capabilities.render_attachment);
+        assert!(capabilities.blendable);
+        assert!(capabilities.linear_filterable);
+        assert!(capabilities.blit_src);
+        assert!(capabilities.blit_dst);
+        assert!(capabilities.supports_sample_count(1));
+        assert!(capabilities.supports_sample_count(4));
+        assert!(!capabilities.supports_sample_count(3));
+
+        let descriptor = TextureDescriptor {
+            usage: TextureUsageFlags::OUTPUT_ATTACHMENT,
+            size: Extent3D {
+                width: 16,
+                height: 16,
+                depth: 1,
+            },
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 3,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::R8G8B8A8Unorm,
+            label: None,
+        };
+        assert!(device.create_texture(descriptor).is_err());
+
+        let capabilities = adapter.texture_format_capabilities(TextureFormat::R8G8B8A8UnormSRGB);
+        if !capabilities.storage {
+            let descriptor = TextureDescriptor {
+                usage: TextureUsageFlags::STORAGE,
+                sample_count: 1,
+                format: TextureFormat::R8G8B8A8UnormSRGB,
+                ..descriptor
+            };
+            assert!(device.create_texture(descriptor).is_err());
+        }
+
+        Ok(instance)
+    });
+}
+
 #[test]
 fn create_default_texture_view() {
     vki::validate(|| {
@@ -48,6 +138,7 @@ fn create_default_texture_view() {
             sample_count: 1,
             dimension: TextureDimension::D2,
             format: TextureFormat::R8G8B8A8Unorm,
+            label: None,
         };
 
         let texture = device.create_texture(descriptor)?;
@@ -76,6 +167,7 @@ fn create_texture_and_cube_view() {
             sample_count: 1,
             dimension: TextureDimension::D2,
             format: TextureFormat::R8G8B8A8Unorm,
+            label: None,
         };
 
         let texture = device.create_texture(descriptor)?;
@@ -88,6 +180,7 @@ fn create_texture_and_cube_view() {
             base_mip_level: 0,
             mip_level_count: descriptor.mip_level_count,
             format: descriptor.format,
+            label: None,
         };
 
         let _texture_view = texture.create_view(texture_view_descriptor)?;
@@ -96,6 +189,111 @@ fn create_texture_and_cube_view() {
     });
 }
 
+#[test]
+fn create_texture_array_views() {
+    vki::validate(|| {
+        let (instance, adapter, device) = support::init()?;
+
+        let descriptor = TextureDescriptor {
+            usage: TextureUsageFlags::SAMPLED,
+            size: Extent3D {
+                width: 64,
+                height: 64,
+                depth: 1,
+            },
+            array_layer_count: 12,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::R8G8B8A8Unorm,
+            label: None,
+        };
+
+        let texture = device.create_texture(descriptor)?;
+        let _texture_view = texture.create_default_view()?;
+
+        let view_descriptor = TextureViewDescriptor {
+            dimension: TextureViewDimension::D2Array,
+            aspect: TextureAspectFlags::COLOR,
+            base_array_layer: 2,
+            array_layer_count: 4,
+            base_mip_level: 0,
+            mip_level_count: 1,
+            format: descriptor.format,
+            label: None,
+        };
+        let _texture_view = texture.create_view(view_descriptor)?;
+
+        // a 2D view has exactly one layer
+        let invalid = TextureViewDescriptor {
+            dimension: TextureViewDimension::D2,
+            ..view_descriptor
+        };
+        assert!(texture.create_view(invalid).is_err());
+
+        // layers outside of the texture
+        let invalid = TextureViewDescriptor {
+            base_array_layer: 10,
+            ..view_descriptor
+        };
+        assert!(texture.create_view(invalid).is_err());
+
+        // cube views have 6 layers
+        let cube = TextureViewDescriptor {
+            dimension: TextureViewDimension::Cube,
+            base_array_layer: 6,
+            array_layer_count: 6,
+            ..view_descriptor
+        };
+        let _texture_view = texture.create_view(cube)?;
+        let invalid = TextureViewDescriptor {
+            array_layer_count: 5,
+            ..cube
+        };
+        assert!(texture.create_view(invalid).is_err());
+
+        // and require square textures
+        let rectangular = device.create_texture(TextureDescriptor {
+            size: Extent3D {
+                width: 64,
+                height: 32,
+                depth: 1,
+            },
+            ..descriptor
+        })?;
+        assert!(rectangular.create_view(cube).is_err());
+
+        // cube arrays have a multiple of 6 layers and must be enabled on the device
+        let cube_array = TextureViewDescriptor {
+            dimension: TextureViewDimension::CubeArray,
+            base_array_layer: 0,
+            array_layer_count: 12,
+            ..view_descriptor
+        };
+        assert!(texture.create_view(cube_array).is_err());
+
+        if !adapter.extensions().texture_cube_array {
+            return Ok(instance);
+        }
+
+        let extensions = Extensions {
+            texture_cube_array: true,
+            ..Extensions::default()
+        };
+        let device = adapter.create_device(DeviceDescriptor::default().with_extensions(extensions))?;
+        let texture = device.create_texture(descriptor)?;
+        let _texture_view = texture.create_view(cube_array)?;
+
+        let invalid = TextureViewDescriptor {
+            array_layer_count: 8,
+            ..cube_array
+        };
+        assert!(texture.create_view(invalid).is_err());
+
+        Ok(instance)
+    });
+}
+
 #[test]
 fn copy_texture_to_texture() {
     vki::validate(|| {
@@ -113,6 +311,7 @@ fn copy_texture_to_texture() {
             size,
             array_layer_count: 1,
             mip_level_count: 1,
+            label: None,
         })?;
 
         let texture2 = device.create_texture(TextureDescriptor {
@@ -123,12 +322,14 @@ fn copy_texture_to_texture() {
             size,
             array_layer_count: 1,
             mip_level_count: 1,
+            label: None,
         })?;
 
         let src = TextureCopyView {
             texture: &texture1,
             mip_level: 0,
             array_layer: 0,
+            array_layer_count: 1,
             origin: Origin3D { x: 0, y: 0, z: 0 },
         };
 
@@ -136,6 +337,7 @@ fn copy_texture_to_texture() {
             texture: &texture2,
             mip_level: 0,
             array_layer: 0,
+            array_layer_count: 1,
             origin: Origin3D { x: 0, y: 0, z: 0 },
         };
 
@@ -145,7 +347,7 @@ fn copy_texture_to_texture() {
 
         let command_buffers = &[encoder.finish()?];
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(command_buffers)?;
 
@@ -175,6 +377,7 @@ fn blit_texture_to_texture_generate_mipmaps() {
             size: Extent3D { width, height, depth },
             array_layer_count: 1,
             mip_level_count,
+            label: None,
         })?;
 
         let mut encoder = device.create_command_encoder()?;
@@ -224,7 +427,7 @@ fn blit_texture_to_texture_generate_mipmaps() {
 
         let command_buffers = &[encoder.finish()?];
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(command_buffers)?;
 
@@ -237,6 +440,218 @@ fn blit_texture_to_texture_generate_mipmaps() {
     })
 }
 
+#[test]
+fn generate_mipmaps_with_blits() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let (width, height, layers) = (64, 64, 6);
+        let size = Extent3D {
+            width,
+            height,
+            depth: 1,
+        };
+        let texels_per_layer = (width * height) as usize;
+
+        // every face of the cube has a single color, which the mipmaps keep
+        let colors: Vec<u32> = (0..layers).map(|layer| 0x1020_3040 * (layer + 1)).collect();
+        let data: Vec<u32> = colors.iter().flat_map(|&color| vec![color; texels_per_layer]).collect();
+
+        let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
+            size: std::mem::size_of::<u32>() * data.len(),
+            label: None,
+        })?;
+        write_buffer_mapped.copy_from_slice(&data)?;
+
+        let read_buffer = device.create_buffer(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
+            size: std::mem::size_of::<u32>() * colors.len(),
+            label: None,
+        })?;
+
+        let texture = device.create_texture(TextureDescriptor {
+            usage: TextureUsageFlags::SAMPLED | TextureUsageFlags::TRANSFER_SRC | TextureUsageFlags::TRANSFER_DST,
+            sample_count: 1,
+            format: TextureFormat::R8G8B8A8Unorm,
+            dimension: TextureDimension::D2,
+            size,
+            array_layer_count: layers,
+            mip_level_count: 7,
+            label: None,
+        })?;
+
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_texture(
+            BufferCopyView {
+                buffer: &write_buffer_mapped.unmap(),
+                bytes_per_row: width * 4,
+                rows_per_image: height,
+                offset: 0,
+            },
+            TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                array_layer: 0,
+                array_layer_count: layers,
+                origin: Origin3D { x: 0, y: 0, z: 0 },
+            },
+            size,
+        );
+        encoder.generate_mipmaps(&texture, texture.subresource_range());
+        encoder.copy_texture_to_buffer(
+            TextureCopyView {
+                texture: &texture,
+                mip_level: 6,
+                array_layer: 0,
+                array_layer_count: layers,
+                origin: Origin3D { x: 0, y: 0, z: 0 },
+            },
+            BufferCopyView {
+                buffer: &read_buffer,
+                bytes_per_row: 4,
+                rows_per_image: 1,
+                offset: 0,
+            },
+            Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            },
+        );
+
+        let queue = device.get_queue(QueueType::Graphics);
+        queue.submit(&[encoder.finish()?])?;
+
+        let fence = queue.create_fence()?;
+        fence.wait(Duration::from_millis(1_000_000_000))?;
+
+        let read_buffer_mapped = read_buffer.map_read()?;
+        let read: &[u32] = read_buffer_mapped.read(0, colors.len())?;
+        assert_eq!(&colors[..], read);
+
+        // the range must be inside the texture
+        let mut encoder = device.create_command_encoder()?;
+        let range = TextureSubresourceRange {
+            mip_level_count: 8,
+            ..texture.subresource_range()
+        };
+        encoder.generate_mipmaps(&texture, range);
+        assert!(encoder.finish().is_err());
+
+        Ok(instance)
+    })
+}
+
+#[test]
+fn generate_mipmaps_with_render_pipeline() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let layers = 6;
+        let size = Extent3D {
+            width: 2,
+            height: 2,
+            depth: 1,
+        };
+
+        // integer formats can not be filtered, so they are rendered
+        let data: Vec<u32> = (0..layers)
+            .flat_map(|layer| vec![layer * 100, layer * 100 + 4, layer * 100 + 8, layer * 100 + 12])
+            .collect();
+
+        let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
+            size: std::mem::size_of::<u32>() * data.len(),
+            label: None,
+        })?;
+        write_buffer_mapped.copy_from_slice(&data)?;
+
+        let read_buffer = device.create_buffer(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
+            size: std::mem::size_of::<u32>() * layers as usize,
+            label: None,
+        })?;
+
+        let descriptor = TextureDescriptor {
+            usage: TextureUsageFlags::OUTPUT_ATTACHMENT
+                | TextureUsageFlags::TRANSFER_SRC
+                | TextureUsageFlags::TRANSFER_DST,
+            sample_count: 1,
+            format: TextureFormat::R32Uint,
+            dimension: TextureDimension::D2,
+            size,
+            array_layer_count: layers,
+            mip_level_count: 2,
+            label: None,
+        };
+        let texture = device.create_texture(descriptor)?;
+
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_texture(
+            BufferCopyView {
+                buffer: &write_buffer_mapped.unmap(),
+                bytes_per_row: 8,
+                rows_per_image: 2,
+                offset: 0,
+            },
+            TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                array_layer: 0,
+                array_layer_count: layers,
+                origin: Origin3D { x: 0, y: 0, z: 0 },
+            },
+            size,
+        );
+        // the second call reuses the cached pipeline and produces the same levels
+        encoder.generate_mipmaps(&texture, texture.subresource_range());
+        encoder.generate_mipmaps(&texture, texture.subresource_range());
+        encoder.copy_texture_to_buffer(
+            TextureCopyView {
+                texture: &texture,
+                mip_level: 1,
+                array_layer: 0,
+                array_layer_count: layers,
+                origin: Origin3D { x: 0, y: 0, z: 0 },
+            },
+            BufferCopyView {
+                buffer: &read_buffer,
+                bytes_per_row: 4,
+                rows_per_image: 1,
+                offset: 0,
+            },
+            Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            },
+        );
+
+        let queue = device.get_queue(QueueType::Graphics);
+        queue.submit(&[encoder.finish()?])?;
+
+        let fence = queue.create_fence()?;
+        fence.wait(Duration::from_millis(1_000_000_000))?;
+
+        let read_buffer_mapped = read_buffer.map_read()?;
+        let read: &[u32] = read_buffer_mapped.read(0, layers as usize)?;
+        let expected: Vec<u32> = (0..layers).map(|layer| layer * 100 + 6).collect();
+        assert_eq!(&expected[..], read);
+
+        // rendering requires the texture to be usable as an attachment
+        let texture = device.create_texture(TextureDescriptor {
+            usage: TextureUsageFlags::TRANSFER_SRC | TextureUsageFlags::TRANSFER_DST,
+            ..descriptor
+        })?;
+        let mut encoder = device.create_command_encoder()?;
+        encoder.generate_mipmaps(&texture, texture.subresource_range());
+        assert!(encoder.finish().is_err());
+
+        Ok(instance)
+    })
+}
+
 #[test]
 fn create_depth_texture_and_view() {
     vki::validate(|| {
@@ -254,6 +669,7 @@ fn create_depth_texture_and_view() {
             sample_count: 1,
             dimension: TextureDimension::D2,
             format: TextureFormat::D32Float,
+            label: None,
         };
 
         let texture = device.create_texture(descriptor)?;
@@ -281,6 +697,7 @@ fn create_depth_stencil_texture_and_view() {
             sample_count: 1,
             dimension: TextureDimension::D2,
             format: TextureFormat::D32FloatS8Uint,
+            label: None,
         };
 
         let texture = device.create_texture(descriptor)?;
@@ -291,6 +708,62 @@ fn create_depth_stencil_texture_and_view() {
     });
 }
 
+#[test]
+fn create_d24_plus_s8_texture_and_view() {
+    vki::validate(|| {
+        let (instance, adapter, device) = support::init()?;
+
+        // the spec requires D24_UNORM_S8_UINT or D32_SFLOAT_S8_UINT to be a depth stencil attachment
+        let capabilities = adapter.texture_format_capabilities(TextureFormat::D24PlusS8);
+        assert!(capabilities.render_attachment);
+
+        let size = Extent3D {
+            width: 16,
+            height: 16,
+            depth: 1,
+        };
+        let texture = device.create_texture(TextureDescriptor {
+            usage: TextureUsageFlags::OUTPUT_ATTACHMENT | TextureUsageFlags::TRANSFER_DST,
+            size,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::D24PlusS8,
+            label: None,
+        })?;
+
+        let _texture_view = texture.create_default_view()?;
+
+        // the texel layout depends on the adapter
+        let buffer = device.create_buffer(BufferDescriptor {
+            size: 16 * 16 * 8,
+            usage: BufferUsageFlags::TRANSFER_SRC,
+            label: None,
+        })?;
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_texture(
+            BufferCopyView {
+                buffer: &buffer,
+                bytes_per_row: 0,
+                rows_per_image: 0,
+                offset: 0,
+            },
+            TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                array_layer: 0,
+                array_layer_count: 1,
+                origin: Origin3D { x: 0, y: 0, z: 0 },
+            },
+            size,
+        );
+        assert!(encoder.finish().is_err());
+
+        Ok(instance)
+    });
+}
+
 #[test]
 fn copy_buffer_to_texture() {
     vki::validate(|| {
@@ -302,6 +775,7 @@ fn copy_buffer_to_texture() {
         let buffer1 = device.create_buffer(BufferDescriptor {
             size: (width * height) as usize * std::mem::size_of::<f32>(),
             usage: BufferUsageFlags::TRANSFER_SRC,
+            label: None,
         })?;
 
         let texture1 = device.create_texture(TextureDescriptor {
@@ -312,12 +786,13 @@ fn copy_buffer_to_texture() {
             size,
             array_layer_count: 1,
             mip_level_count: 1,
+            label: None,
         })?;
 
         let src = BufferCopyView {
             buffer: &buffer1,
-            row_length: width, // TODO row_pitch
-            image_height: height,
+            bytes_per_row: width * 4,
+            rows_per_image: height,
             offset: 0,
         };
 
@@ -325,6 +800,7 @@ fn copy_buffer_to_texture() {
             texture: &texture1,
             mip_level: 0,
             array_layer: 0,
+            array_layer_count: 1,
             origin: Origin3D { x: 0, y: 0, z: 0 },
         };
 
@@ -334,7 +810,7 @@ fn copy_buffer_to_texture() {
 
         let command_buffers = &[encoder.finish()?];
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(command_buffers)?;
 
@@ -358,6 +834,7 @@ fn copy_texture_to_buffer() {
         let buffer1 = device.create_buffer(BufferDescriptor {
             size: (width * height) as usize * std::mem::size_of::<f32>(),
             usage: BufferUsageFlags::TRANSFER_DST,
+            label: None,
         })?;
 
         let texture1 = device.create_texture(TextureDescriptor {
@@ -368,12 +845,13 @@ fn copy_texture_to_buffer() {
             size,
             array_layer_count: 1,
             mip_level_count: 1,
+            label: None,
         })?;
 
         let dst = BufferCopyView {
             buffer: &buffer1,
-            row_length: width, // TODO row_pitch
-            image_height: height,
+            bytes_per_row: width * 4,
+            rows_per_image: height,
             offset: 0,
         };
 
@@ -381,6 +859,7 @@ fn copy_texture_to_buffer() {
             texture: &texture1,
             mip_level: 0,
             array_layer: 0,
+            array_layer_count: 1,
             origin: Origin3D { x: 0, y: 0, z: 0 },
         };
 
@@ -390,7 +869,7 @@ fn copy_texture_to_buffer() {
 
         let command_buffers = &[encoder.finish()?];
 
-        let queue = device.get_queue();
+        let queue = device.get_queue(QueueType::Graphics);
 
         queue.submit(command_buffers)?;
 
@@ -402,3 +881,408 @@ fn copy_texture_to_buffer() {
         Ok(instance)
     })
 }
+
+#[test]
+fn copy_buffer_to_texture_with_row_padding() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let (width, height) = (16, 4);
+        let size = Extent3D {
+            width,
+            height,
+            depth: 1,
+        };
+
+        // rows are padded to 256 bytes, 64 texels
+        let padded_row_texels = 64;
+        let data: Vec<u32> = (0..padded_row_texels * height).collect();
+        let data_byte_size = std::mem::size_of::<u32>() * data.len();
+
+        let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
+            size: data_byte_size,
+            label: None,
+        })?;
+        write_buffer_mapped.copy_from_slice(&data)?;
+        let write_buffer = write_buffer_mapped.unmap();
+
+        let read_buffer = device.create_buffer(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
+            size: (width * height) as usize * std::mem::size_of::<u32>(),
+            label: None,
+        })?;
+
+        let texture = device.create_texture(TextureDescriptor {
+            usage: TextureUsageFlags::TRANSFER_SRC | TextureUsageFlags::TRANSFER_DST,
+            sample_count: 1,
+            format: TextureFormat::R8G8B8A8Unorm,
+            dimension: TextureDimension::D2,
+            size,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            label: None,
+        })?;
+
+        let src = BufferCopyView {
+            buffer: &write_buffer,
+            bytes_per_row: 256,
+            rows_per_image: height,
+            offset: 0,
+        };
+        let texture_view = TextureCopyView {
+            texture: &texture,
+            mip_level: 0,
+            array_layer: 0,
+            array_layer_count: 1,
+            origin: Origin3D { x: 0, y: 0, z: 0 },
+        };
+        let dst = BufferCopyView {
+            buffer: &read_buffer,
+            bytes_per_row: width * 4,
+            rows_per_image: height,
+            offset: 0,
+        };
+
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_texture(src.clone(), texture_view.clone(), size);
+        encoder.copy_texture_to_buffer(texture_view.clone(), dst, size);
+
+        let queue = device.get_queue(QueueType::Graphics);
+        queue.submit(&[encoder.finish()?])?;
+
+        let fence = queue.create_fence()?;
+        fence.wait(Duration::from_millis(1_000_000_000))?;
+
+        let read_buffer_mapped = read_buffer.map_read()?;
+        let read: &[u32] = read_buffer_mapped.read(0, (width * height) as usize)?;
+        let expected: Vec<u32> = data
+            .chunks(padded_row_texels as usize)
+            .flat_map(|row| row[..width as usize].to_vec())
+            .collect();
+        assert_eq!(&expected[..], read);
+
+        // the pitch must cover whole texels and the rows of the copy
+        for &bytes_per_row in &[254, 32] {
+            let mut encoder = device.create_command_encoder()?;
+            let invalid = BufferCopyView {
+                bytes_per_row,
+                ..src.clone()
+            };
+            encoder.copy_buffer_to_texture(invalid, texture_view.clone(), size);
+            assert!(encoder.finish().is_err());
+        }
+
+        // the copy overruns the buffer
+        let mut encoder = device.create_command_encoder()?;
+        let invalid = BufferCopyView {
+            offset: 256,
+            ..src.clone()
+        };
+        encoder.copy_buffer_to_texture(invalid, texture_view.clone(), size);
+        assert!(encoder.finish().is_err());
+
+        Ok(instance)
+    })
+}
+
+#[test]
+fn copy_texture_array_layers() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let (width, height, layers) = (4, 4, 6);
+        let size = Extent3D {
+            width,
+            height,
+            depth: 1,
+        };
+        let texels_per_layer = (width * height) as usize;
+
+        let data: Vec<u32> = (0..texels_per_layer * layers as usize).map(|i| i as u32).collect();
+        let data_byte_size = std::mem::size_of::<u32>() * data.len();
+
+        let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
+            size: data_byte_size,
+            label: None,
+        })?;
+        write_buffer_mapped.copy_from_slice(&data)?;
+        let write_buffer = write_buffer_mapped.unmap();
+
+        let read_buffer = device.create_buffer(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
+            size: data_byte_size,
+            label: None,
+        })?;
+
+        let descriptor = TextureDescriptor {
+            usage: TextureUsageFlags::TRANSFER_SRC | TextureUsageFlags::TRANSFER_DST,
+            sample_count: 1,
+            format: TextureFormat::R8G8B8A8Unorm,
+            dimension: TextureDimension::D2,
+            size,
+            array_layer_count: layers,
+            mip_level_count: 1,
+            label: None,
+        };
+        let texture1 = device.create_texture(descriptor)?;
+        let texture2 = device.create_texture(descriptor)?;
+
+        let buffer_view = |buffer| BufferCopyView {
+            buffer,
+            bytes_per_row: width * 4,
+            rows_per_image: height,
+            offset: 0,
+        };
+        let all_layers = |texture| TextureCopyView {
+            texture,
+            mip_level: 0,
+            array_layer: 0,
+            array_layer_count: layers,
+            origin: Origin3D { x: 0, y: 0, z: 0 },
+        };
+
+        let mut encoder = device.create_command_encoder()?;
+
+        // upload every layer, then move layers 2..5 of the first texture to layers 0..3 of the second
+        encoder.copy_buffer_to_texture(buffer_view(&write_buffer), all_layers(&texture1), size);
+        encoder.copy_texture_to_texture(
+            TextureCopyView {
+                array_layer: 2,
+                array_layer_count: 3,
+                ..all_layers(&texture1)
+            },
+            TextureCopyView {
+                array_layer_count: 3,
+                ..all_layers(&texture2)
+            },
+            size,
+        );
+        encoder.copy_texture_to_buffer(
+            TextureCopyView {
+                array_layer_count: 3,
+                ..all_layers(&texture2)
+            },
+            buffer_view(&read_buffer),
+            size,
+        );
+
+        let queue = device.get_queue(QueueType::Graphics);
+        queue.submit(&[encoder.finish()?])?;
+
+        let fence = queue.create_fence()?;
+        fence.wait(Duration::from_millis(1_000_000_000))?;
+
+        let read_buffer_mapped = read_buffer.map_read()?;
+        let read: &[u32] = read_buffer_mapped.read(0, texels_per_layer * 3)?;
+        assert_eq!(&data[texels_per_layer * 2..texels_per_layer * 5], read);
+
+        // the layers must exist and match between source and destination
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_texture(
+            buffer_view(&write_buffer),
+            TextureCopyView {
+                array_layer: 1,
+                ..all_layers(&texture1)
+            },
+            size,
+        );
+        assert!(encoder.finish().is_err());
+
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_texture_to_texture(
+            all_layers(&texture1),
+            TextureCopyView {
+                array_layer_count: 3,
+                ..all_layers(&texture2)
+            },
+            size,
+        );
+        assert!(encoder.finish().is_err());
+
+        // array layers are not depth slices
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_texture(
+            buffer_view(&write_buffer),
+            all_layers(&texture1),
+            Extent3D { depth: 2, ..size },
+        );
+        assert!(encoder.finish().is_err());
+
+        Ok(instance)
+    })
+}
+
+#[test]
+fn copy_texture_3d_slices() {
+    vki::validate(|| {
+        let (instance, _adapter, device) = support::init()?;
+
+        let (width, height, depth) = (4, 4, 4);
+        let size = Extent3D { width, height, depth };
+        let texels_per_slice = (width * height) as usize;
+
+        let data: Vec<u32> = (0..texels_per_slice * depth as usize).map(|i| i as u32).collect();
+        let data_byte_size = std::mem::size_of::<u32>() * data.len();
+
+        let write_buffer_mapped = device.create_buffer_mapped(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_WRITE | BufferUsageFlags::TRANSFER_SRC,
+            size: data_byte_size,
+            label: None,
+        })?;
+        write_buffer_mapped.copy_from_slice(&data)?;
+        let write_buffer = write_buffer_mapped.unmap();
+
+        let read_buffer = device.create_buffer(BufferDescriptor {
+            usage: BufferUsageFlags::MAP_READ | BufferUsageFlags::TRANSFER_DST,
+            size: data_byte_size,
+            label: None,
+        })?;
+
+        let texture = device.create_texture(TextureDescriptor {
+            usage: TextureUsageFlags::TRANSFER_SRC | TextureUsageFlags::TRANSFER_DST,
+            sample_count: 1,
+            format: TextureFormat::R8G8B8A8Unorm,
+            dimension: TextureDimension::D3,
+            size,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            label: None,
+        })?;
+
+        let buffer_view = |buffer| BufferCopyView {
+            buffer,
+            bytes_per_row: width * 4,
+            rows_per_image: height,
+            offset: 0,
+        };
+        let texture_view = |z| TextureCopyView {
+            texture: &texture,
+            mip_level: 0,
+            array_layer: 0,
+            array_layer_count: 1,
+            origin: Origin3D { x: 0, y: 0, z },
+        };
+
+        let mut encoder = device.create_command_encoder()?;
+
+        // upload the whole volume and read back slices 1..3
+        encoder.copy_buffer_to_texture(buffer_view(&write_buffer), texture_view(0), size);
+        encoder.copy_texture_to_buffer(
+            texture_view(1),
+            buffer_view(&read_buffer),
+            Extent3D { depth: 2, ..size },
+        );
+
+        let queue = device.get_queue(QueueType::Graphics);
+        queue.submit(&[encoder.finish()?])?;
+
+        let fence = queue.create_fence()?;
+        fence.wait(Duration::from_millis(1_000_000_000))?;
+
+        let read_buffer_mapped = read_buffer.map_read()?;
+        let read: &[u32] = read_buffer_mapped.read(0, texels_per_slice * 2)?;
+        assert_eq!(&data[texels_per_slice..texels_per_slice * 3], read);
+
+        // the depth range must be inside the texture
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_texture_to_buffer(
+            texture_view(2),
+            buffer_view(&read_buffer),
+            Extent3D { depth: 3, ..size },
+        );
+        assert!(encoder.finish().is_err());
+
+        Ok(instance)
+    })
+}
+
+#[test]
+fn create_compressed_texture() {
+    vki::validate(|| {
+        let (instance, adapter, device) = support::init()?;
+
+        let (width, height, depth) = (64, 64, 1);
+        let size = Extent3D { width, height, depth };
+
+        let descriptor = TextureDescriptor {
+            usage: TextureUsageFlags::SAMPLED | TextureUsageFlags::TRANSFER_DST,
+            sample_count: 1,
+            format: TextureFormat::BC1RGBAUnorm,
+            dimension: TextureDimension::D2,
+            size,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            label: None,
+        };
+
+        // compressed formats must be enabled on the device
+        assert!(device.create_texture(descriptor).is_err());
+
+        if !adapter.extensions().texture_compression_bc {
+            return Ok(instance);
+        }
+
+        let extensions = Extensions {
+            texture_compression_bc: true,
+            ..Extensions::default()
+        };
+        let device = adapter.create_device(DeviceDescriptor::default().with_extensions(extensions))?;
+
+        // the size must be a multiple of the 4x4 blocks
+        let unaligned = TextureDescriptor {
+            size: Extent3D { width: 62, ..size },
+            ..descriptor
+        };
+        assert!(device.create_texture(unaligned).is_err());
+
+        let texture = device.create_texture(descriptor)?;
+
+        // 8 bytes per 4x4 block
+        let buffer = device.create_buffer(BufferDescriptor {
+            size: (width / 4 * height / 4) as usize * 8,
+            usage: BufferUsageFlags::TRANSFER_SRC,
+            label: None,
+        })?;
+
+        let src = BufferCopyView {
+            buffer: &buffer,
+            bytes_per_row: width / 4 * 8,
+            rows_per_image: height,
+            offset: 0,
+        };
+
+        let dst = TextureCopyView {
+            texture: &texture,
+            mip_level: 0,
+            array_layer: 0,
+            array_layer_count: 1,
+            origin: Origin3D { x: 0, y: 0, z: 0 },
+        };
+
+        let mut encoder = device.create_command_encoder()?;
+        encoder.copy_buffer_to_texture(src.clone(), dst.clone(), size);
+        let command_buffer = encoder.finish()?;
+        device.get_queue(QueueType::Graphics).submit(&[command_buffer])?;
+
+        // copies that split a block fail validation
+        let mut encoder = device.create_command_encoder()?;
+        let unaligned_dst = TextureCopyView {
+            origin: Origin3D { x: 2, y: 0, z: 0 },
+            ..dst
+        };
+        encoder.copy_buffer_to_texture(
+            src,
+            unaligned_dst,
+            Extent3D {
+                width: 4,
+                height: 4,
+                depth,
+            },
+        );
+        assert!(encoder.finish().is_err());
+
+        Ok(instance)
+    });
+}